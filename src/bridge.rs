@@ -0,0 +1,326 @@
+//! `WKWebView` 页面与应用之间的 `restgap://` 桥接协议。
+//!
+//! 设置页和倒计时页通过跳转到 `restgap://<命令>?<参数>` 通知应用，导航代理拦截跳转后用
+//! [`BridgeCommand::parse`] 解析为带类型的命令。参数按 `encodeURIComponent` 的规则做百分号编码；
+//! 可选的 `v` 参数标明协议版本，缺省视为当前版本 [`VERSION`]。
+
+use std::fmt::{self, Write};
+
+/// 桥接 URL 的前缀
+pub const SCHEME: &str = "restgap://";
+/// 当前协议版本
+pub const VERSION: u32 = 1;
+/// 标明协议版本的参数名
+const VERSION_PARAM: &str = "v";
+
+/// 页面发给应用的命令
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BridgeCommand {
+    /// 保存设置，参数为按提交顺序排列的键值对
+    Save(Vec<(String, String)>),
+    /// 关闭设置窗口
+    Cancel,
+    /// 从文件导入配置
+    Import,
+    /// 导出配置到文件
+    Export,
+    /// 跳过当前休息
+    Skip,
+    /// 推迟当前休息
+    Postpone,
+}
+
+/// 无法解析桥接 URL 的原因
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BridgeError {
+    /// 不是 `restgap://` URL
+    NotBridgeUrl,
+    /// 无法识别的命令
+    UnknownCommand(String),
+    /// 不支持的协议版本
+    UnsupportedVersion(String),
+    /// 命令不接受该参数
+    UnexpectedParam { command: String, key: String },
+    /// 百分号编码不正确或解码后不是 UTF-8
+    InvalidEncoding(String),
+}
+
+impl fmt::Display for BridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotBridgeUrl => write!(f, "不是 {SCHEME} URL"),
+            Self::UnknownCommand(command) => write!(f, "未知命令: {command:?}"),
+            Self::UnsupportedVersion(version) => write!(f, "不支持的协议版本: {version:?}"),
+            Self::UnexpectedParam { command, key } => {
+                write!(f, "命令 {command} 不接受参数 {key:?}")
+            }
+            Self::InvalidEncoding(text) => write!(f, "编码不正确: {text:?}"),
+        }
+    }
+}
+
+impl std::error::Error for BridgeError {}
+
+/// 是否为桥接 URL（需要拦截，而不是交给 `WKWebView` 加载）
+pub fn is_bridge_url(url: &str) -> bool {
+    url.starts_with(SCHEME)
+}
+
+impl BridgeCommand {
+    /// 命令名，即 URL 中 `restgap://` 之后、`?` 之前的部分
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Save(_) => "save",
+            Self::Cancel => "cancel",
+            Self::Import => "import",
+            Self::Export => "export",
+            Self::Skip => "skip",
+            Self::Postpone => "postpone",
+        }
+    }
+
+    /// 解析桥接 URL
+    pub fn parse(url: &str) -> Result<Self, BridgeError> {
+        let rest = url.strip_prefix(SCHEME).ok_or(BridgeError::NotBridgeUrl)?;
+        let (command, query) = rest.split_once('?').unwrap_or((rest, ""));
+        // WebKit 可能在无路径的 URL 末尾补上 `/`
+        let command = command.strip_suffix('/').unwrap_or(command);
+
+        let mut params = Vec::new();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let (key, value) = (percent_decode(key)?, percent_decode(value)?);
+            if key == VERSION_PARAM {
+                if value != VERSION.to_string() {
+                    return Err(BridgeError::UnsupportedVersion(value));
+                }
+                continue;
+            }
+            params.push((key, value));
+        }
+
+        let command = match command {
+            "save" => return Ok(Self::Save(params)),
+            "cancel" => Self::Cancel,
+            "import" => Self::Import,
+            "export" => Self::Export,
+            "skip" => Self::Skip,
+            "postpone" => Self::Postpone,
+            other => return Err(BridgeError::UnknownCommand(other.to_string())),
+        };
+        match params.into_iter().next() {
+            Some((key, _)) => Err(BridgeError::UnexpectedParam {
+                command: command.name().to_string(),
+                key,
+            }),
+            None => Ok(command),
+        }
+    }
+
+    /// 生成桥接 URL（带版本参数），与 [`Self::parse`] 互逆
+    pub fn to_url(&self) -> String {
+        let mut url = format!("{SCHEME}{}?{VERSION_PARAM}={VERSION}", self.name());
+        if let Self::Save(params) = self {
+            for (key, value) in params {
+                url.push('&');
+                url.push_str(&percent_encode(key));
+                url.push('=');
+                url.push_str(&percent_encode(value));
+            }
+        }
+        url
+    }
+}
+
+/// 按 `encodeURIComponent` 的规则编码：保留字母数字与 `-_.!~*'()`，其余字节编码为 `%XX`
+pub fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for &b in s.as_bytes() {
+        if b.is_ascii_alphanumeric() || b"-_.!~*'()".contains(&b) {
+            out.push(char::from(b));
+        } else {
+            let _ = write!(out, "%{b:02X}");
+        }
+    }
+    out
+}
+
+/// 解码百分号编码；`%` 后不是两位十六进制数或解码结果不是 UTF-8 时返回错误
+pub fn percent_decode(s: &str) -> Result<String, BridgeError> {
+    let invalid = || BridgeError::InvalidEncoding(s.to_string());
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3).ok_or_else(invalid)?;
+            if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(invalid());
+            }
+            out.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(list: &[(&str, &str)]) -> Vec<(String, String)> {
+        list.iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_commands_without_params() {
+        for (url, command) in [
+            ("restgap://cancel", BridgeCommand::Cancel),
+            ("restgap://import", BridgeCommand::Import),
+            ("restgap://export/", BridgeCommand::Export),
+            ("restgap://skip?v=1", BridgeCommand::Skip),
+            ("restgap://postpone", BridgeCommand::Postpone),
+        ] {
+            assert_eq!(BridgeCommand::parse(url), Ok(command), "{url}");
+        }
+    }
+
+    #[test]
+    fn save_params_are_decoded_in_order() {
+        let url = "restgap://save?v=1&interval=30&work_hours=Mon-Fri%2009%3A00-18%3A00\
+                   &profile=%E6%B7%B1%E5%BA%A6%20%2B%26%3D&empty=&flag";
+        assert_eq!(
+            BridgeCommand::parse(url),
+            Ok(BridgeCommand::Save(pairs(&[
+                ("interval", "30"),
+                ("work_hours", "Mon-Fri 09:00-18:00"),
+                ("profile", "深度 +&="),
+                ("empty", ""),
+                ("flag", ""),
+            ])))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_commands_versions_and_params() {
+        assert_eq!(
+            BridgeCommand::parse("https://example.com"),
+            Err(BridgeError::NotBridgeUrl)
+        );
+        assert_eq!(
+            BridgeCommand::parse("restgap://launch?x=1"),
+            Err(BridgeError::UnknownCommand("launch".to_string()))
+        );
+        assert_eq!(
+            BridgeCommand::parse("restgap://save?v=2&interval=30"),
+            Err(BridgeError::UnsupportedVersion("2".to_string()))
+        );
+        assert_eq!(
+            BridgeCommand::parse("restgap://skip?now=1"),
+            Err(BridgeError::UnexpectedParam {
+                command: "skip".to_string(),
+                key: "now".to_string(),
+            })
+        );
+        for bad in ["%", "%4", "%zz", "%FF", "a%e4%bd"] {
+            assert!(
+                matches!(percent_decode(bad), Err(BridgeError::InvalidEncoding(_))),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn encodes_like_encode_uri_component() {
+        assert_eq!(
+            percent_encode("Mon-Fri 09:00; a&b=c/'*'"),
+            "Mon-Fri%2009%3A00%3B%20a%26b%3Dc%2F'*'"
+        );
+        assert_eq!(percent_encode("番"), "%E7%95%AA");
+    }
+
+    /// 固定种子的 xorshift 生成器，使性质测试可复现且不依赖外部 crate
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+
+        /// 混合 ASCII 标点、URL 保留字符、中文与 emoji 的随机字符串
+        fn string(&mut self) -> String {
+            const POOL: &[char] = &[
+                'a', 'Z', '0', '9', ' ', '%', '&', '=', '?', '+', '/', ':', ';', '#', '-', '_',
+                '.', '~', '\'', '"', '<', '\n', '息', '间', 'é', '😀',
+            ];
+            let len = self.below(12);
+            (0..len)
+                .map(|_| POOL[usize::try_from(self.below(POOL.len() as u64)).unwrap()])
+                .collect()
+        }
+
+        fn command(&mut self) -> BridgeCommand {
+            match self.below(6) {
+                0 => {
+                    let len = self.below(5);
+                    // `v` 是保留的版本参数名，不会出现在保存参数中
+                    let params = (0..len)
+                        .map(|_| (format!("k{}", self.string()), self.string()))
+                        .collect();
+                    BridgeCommand::Save(params)
+                }
+                1 => BridgeCommand::Cancel,
+                2 => BridgeCommand::Import,
+                3 => BridgeCommand::Export,
+                4 => BridgeCommand::Skip,
+                _ => BridgeCommand::Postpone,
+            }
+        }
+    }
+
+    #[test]
+    fn property_encode_then_decode_is_identity() {
+        let mut rng = Rng(0x5EED_1234_ABCD_0001);
+        for _ in 0..2000 {
+            let s = rng.string();
+            let encoded = percent_encode(&s);
+            assert!(encoded.is_ascii());
+            assert!(!encoded.contains(['&', '=', '?', ' ']), "{encoded}");
+            assert_eq!(percent_decode(&encoded).as_deref(), Ok(s.as_str()));
+        }
+    }
+
+    #[test]
+    fn property_commands_round_trip_through_urls() {
+        let mut rng = Rng(0x5EED_1234_ABCD_0002);
+        for _ in 0..2000 {
+            let command = rng.command();
+            let url = command.to_url();
+            assert!(is_bridge_url(&url));
+            assert_eq!(BridgeCommand::parse(&url), Ok(command), "{url}");
+        }
+    }
+
+    #[test]
+    fn property_parse_never_panics_on_arbitrary_input() {
+        let mut rng = Rng(0x5EED_1234_ABCD_0003);
+        for _ in 0..2000 {
+            let url = format!("{SCHEME}{}", rng.string());
+            if let Ok(BridgeCommand::Save(params)) = BridgeCommand::parse(&url) {
+                assert!(params.iter().all(|(key, _)| key != VERSION_PARAM));
+            }
+        }
+    }
+}
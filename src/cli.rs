@@ -0,0 +1,371 @@
+//! 命令行子命令
+//!
+//! 不带子命令启动时运行菜单栏应用；`restgap config ...` 用于在命令行中导入导出配置，
+//! `restgap history export ...` 用于导出休息历史，`restgap report ...` 用于生成休息报告。
+
+use std::io::Write;
+use std::path::Path;
+use std::process::ExitCode;
+
+use crate::config::{Config, ConfigStore};
+use crate::export::{DEFAULT_DAYS, DateRange, ExportFormat};
+use crate::history::HistoryStore;
+use crate::i18n::Texts;
+use crate::report::Report;
+use crate::scheduler::Clock;
+use crate::stats;
+use crate::work_hours::SECS_PER_DAY;
+
+const USAGE: &str = "\
+用法:
+  restgap                        启动菜单栏应用
+  restgap config export [FILE]   导出配置为 JSON（省略 FILE 时输出到标准输出）
+  restgap config import FILE     校验并导入 JSON 配置
+  restgap history export [--from YYYY-MM-DD] [--to YYYY-MM-DD] FILE
+                                 导出休息历史，按 FILE 的扩展名生成 CSV（.csv）或
+                                 iCalendar（.ics）；省略日期时导出最近 30 天
+  restgap report --out FILE      由休息历史生成可离线打开的 HTML 报告
+  restgap help                   显示本帮助";
+
+/// 执行命令行子命令；参数不是已知子命令时返回 `None`，由调用方正常启动应用
+///
+/// `history` 为无法确定数据目录时的 `None`；`clock` 用于确定本地日期与时区。
+pub fn run(
+    args: &[String],
+    store: &dyn ConfigStore,
+    history: Option<&HistoryStore>,
+    clock: &dyn Clock,
+    out: &mut dyn Write,
+    err: &mut dyn Write,
+) -> Option<ExitCode> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result = match args.as_slice() {
+        ["help" | "--help" | "-h"] => writeln!(out, "{USAGE}").map_err(|e| e.to_string()),
+        ["config", "export"] => export(store, None, out),
+        ["config", "export", path] => export(store, Some(path), out),
+        ["config", "import", path] => import(store, path, out),
+        ["history", "export", rest @ ..] => export_history(store, history, clock, rest, out),
+        ["report", "--out", path] => report(store, history, clock, path, out),
+        ["config" | "history" | "report", ..] => Err(USAGE.to_string()),
+        _ => return None,
+    };
+    Some(match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(msg) => {
+            let _ = writeln!(err, "{msg}");
+            ExitCode::FAILURE
+        }
+    })
+}
+
+fn export(store: &dyn ConfigStore, path: Option<&str>, out: &mut dyn Write) -> Result<(), String> {
+    store.migrate().map_err(|e| format!("配置迁移失败: {e}"))?;
+    let config = store.load().map_err(|e| format!("加载配置失败: {e}"))?;
+    let text = config.to_json();
+    match path {
+        Some(path) => {
+            std::fs::write(path, text).map_err(|e| format!("写入 {path} 失败: {e}"))?;
+            writeln!(out, "已导出配置到 {path}").map_err(|e| e.to_string())
+        }
+        None => out.write_all(text.as_bytes()).map_err(|e| e.to_string()),
+    }
+}
+
+fn import(store: &dyn ConfigStore, path: &str, out: &mut dyn Write) -> Result<(), String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("读取 {path} 失败: {e}"))?;
+    let config = Config::from_json(&text).map_err(|e| e.to_string())?;
+    store.migrate().map_err(|e| format!("配置迁移失败: {e}"))?;
+    store
+        .save(&config)
+        .map_err(|e| format!("保存配置失败: {e}"))?;
+    writeln!(out, "已导入配置；正在运行的 RestGap 会自动重新加载").map_err(|e| e.to_string())
+}
+
+fn export_history(
+    store: &dyn ConfigStore,
+    history: Option<&HistoryStore>,
+    clock: &dyn Clock,
+    args: &[&str],
+    out: &mut dyn Write,
+) -> Result<(), String> {
+    let (mut from, mut to, mut file) = (None, None, None);
+    let mut args = args.iter().copied();
+    while let Some(arg) = args.next() {
+        match arg {
+            "--from" => from = Some(args.next().ok_or(USAGE)?),
+            "--to" => to = Some(args.next().ok_or(USAGE)?),
+            _ if file.is_none() && !arg.starts_with("--") => file = Some(arg),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let file = file.ok_or(USAGE)?;
+    let format = ExportFormat::from_path(Path::new(file))
+        .ok_or_else(|| format!("无法识别的导出格式: {file}（应以 .csv 或 .ics 结尾）"))?;
+    let history = history.ok_or(NO_HISTORY_LOCATION)?;
+
+    let utc_offset = clock.utc_offset();
+    let default = DateRange::last_days(clock.local_secs().div_euclid(SECS_PER_DAY), DEFAULT_DAYS);
+    let from = from.map_or_else(|| stats::format_date(default.from), str::to_string);
+    let to = to.map_or_else(|| stats::format_date(default.to), str::to_string);
+    let range = DateRange::parse(&from, &to).map_err(|e| e.to_string())?;
+
+    let (start, end) = range.bounds(utc_offset);
+    let records = history
+        .read_range(start, end)
+        .map_err(|e| read_error(history, &e))?;
+    // 日历事件的标题与说明跟随设置中的语言；配置无法加载时使用默认设置
+    let texts = Texts::new(store.load().unwrap_or_default().effective_language());
+    std::fs::write(file, format.render(&records, utc_offset, &texts))
+        .map_err(|e| format!("写入 {file} 失败: {e}"))?;
+    writeln!(
+        out,
+        "已导出 {from} 至 {to} 的休息历史（{} 条记录）到 {file}",
+        records.len()
+    )
+    .map_err(|e| e.to_string())
+}
+
+fn report(
+    store: &dyn ConfigStore,
+    history: Option<&HistoryStore>,
+    clock: &dyn Clock,
+    path: &str,
+    out: &mut dyn Write,
+) -> Result<(), String> {
+    let history = history.ok_or(NO_HISTORY_LOCATION)?;
+    let records = history.read().map_err(|e| read_error(history, &e))?;
+    // 配色与语言跟随设置；配置无法加载时使用默认设置
+    let config = store.load().unwrap_or_default();
+    let texts = Texts::new(config.effective_language());
+    let today = clock.local_secs().div_euclid(SECS_PER_DAY);
+    let html = Report::new(&records, today, clock.utc_offset()).to_html(config.theme, &texts);
+    std::fs::write(path, html).map_err(|e| format!("写入 {path} 失败: {e}"))?;
+    writeln!(out, "已生成休息报告 {path}（{} 条记录）", records.len()).map_err(|e| e.to_string())
+}
+
+const NO_HISTORY_LOCATION: &str = "无法确定休息历史的位置（未设置 HOME）";
+
+fn read_error(history: &HistoryStore, err: &std::io::Error) -> String {
+    format!("读取休息历史 {} 失败: {err}", history.path().display())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant, SystemTime};
+
+    use super::*;
+    use crate::config::FileStore;
+    use crate::history::{Record, RecordKind};
+    use crate::i18n::{Language, LanguagePreference};
+    use crate::scheduler::BreakKind;
+
+    /// 停在固定时刻的时钟
+    struct FixedClock {
+        wall: SystemTime,
+        utc_offset: i64,
+    }
+
+    impl Clock for FixedClock {
+        fn now(&self) -> Instant {
+            Instant::now()
+        }
+
+        fn now_wall(&self) -> SystemTime {
+            self.wall
+        }
+
+        fn utc_offset(&self) -> i64 {
+            self.utc_offset
+        }
+    }
+
+    /// 东八区 2024-01-10 20:00
+    fn clock() -> FixedClock {
+        FixedClock {
+            wall: SystemTime::UNIX_EPOCH + Duration::from_secs(1_704_888_000),
+            utc_offset: 8 * 3600,
+        }
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(ToString::to_string).collect()
+    }
+
+    fn run_with(store: &FileStore, list: &[&str]) -> (Option<ExitCode>, String, String) {
+        run_with_history(store, None, list)
+    }
+
+    fn run_with_history(
+        store: &FileStore,
+        history: Option<&HistoryStore>,
+        list: &[&str],
+    ) -> (Option<ExitCode>, String, String) {
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let code = run(&args(list), store, history, &clock(), &mut out, &mut err);
+        (
+            code,
+            String::from_utf8(out).unwrap(),
+            String::from_utf8(err).unwrap(),
+        )
+    }
+
+    #[test]
+    fn unknown_arguments_launch_the_app() {
+        let store = FileStore::new(std::env::temp_dir().join("restgap-cli-unused.json"));
+        assert_eq!(run_with(&store, &[]).0, None);
+        assert_eq!(run_with(&store, &["-NSDocumentRevisionsDebugMode"]).0, None);
+        assert_eq!(
+            run_with(&store, &["config", "frobnicate"]).0,
+            Some(ExitCode::FAILURE)
+        );
+    }
+
+    #[test]
+    fn export_then_import_round_trips_and_rejects_bad_files() {
+        let dir = std::env::temp_dir().join(format!("restgap-cli-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let store = FileStore::new(dir.join(FileStore::FILE_NAME));
+
+        let (code, out, _) = run_with(&store, &["config", "export"]);
+        assert_eq!(code, Some(ExitCode::SUCCESS));
+        assert_eq!(out, Config::default().to_json());
+
+        let shared = dir.join("shared.json");
+        std::fs::write(
+            &shared,
+            r#"{"interval_minutes": 52, "break_seconds": 1020}"#,
+        )
+        .unwrap();
+        let (code, ..) = run_with(&store, &["config", "import", shared.to_str().unwrap()]);
+        assert_eq!(code, Some(ExitCode::SUCCESS));
+        assert_eq!(store.load().unwrap().interval_minutes, 52);
+
+        std::fs::write(&shared, r#"{"interval_minutes": 0}"#).unwrap();
+        let (code, _, err) = run_with(&store, &["config", "import", shared.to_str().unwrap()]);
+        assert_eq!(code, Some(ExitCode::FAILURE));
+        assert!(err.contains("interval_minutes = 0"), "{err}");
+        assert_eq!(store.load().unwrap().interval_minutes, 52);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn history_export_writes_the_chosen_range() {
+        let dir = std::env::temp_dir().join(format!("restgap-cli-history-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let store = FileStore::new(dir.join(FileStore::FILE_NAME));
+        let history = HistoryStore::new(
+            dir.join(HistoryStore::FILE_NAME),
+            HistoryStore::DEFAULT_RETENTION,
+        );
+        // 东八区 2023-12-31 23:59、2024-01-01 00:00 与 2024-01-10 09:00 各完成一次休息
+        let records: Vec<Record> = [1_704_038_340, 1_704_038_400, 1_704_848_400]
+            .into_iter()
+            .map(|timestamp| Record {
+                timestamp,
+                kind: RecordKind::Completed,
+                break_kind: BreakKind::Short,
+                planned_seconds: 120,
+                actual_seconds: 120,
+                reason: Some("rested".to_string()),
+            })
+            .collect();
+        for record in &records {
+            history.append(record).unwrap();
+        }
+
+        let csv = dir.join("january.csv");
+        let csv_arg = csv.to_str().unwrap();
+        let list = [
+            "history",
+            "export",
+            "--from",
+            "2024-01-01",
+            "--to",
+            "2024-01-09",
+            csv_arg,
+        ];
+        let (code, out, _) = run_with_history(&store, Some(&history), &list);
+        assert_eq!(code, Some(ExitCode::SUCCESS));
+        assert!(out.contains("1 条记录"), "{out}");
+        assert_eq!(
+            std::fs::read_to_string(&csv).unwrap(),
+            crate::export::to_csv(&records[1..2], 8 * 3600)
+        );
+
+        // 省略日期时导出截至今天的最近 30 天；日历事件使用设置中的语言
+        store
+            .save(&Config {
+                language: LanguagePreference::En,
+                ..Config::default()
+            })
+            .unwrap();
+        let ics = dir.join("recent.ics");
+        let list = ["history", "export", ics.to_str().unwrap()];
+        let (code, out, _) = run_with_history(&store, Some(&history), &list);
+        assert_eq!(code, Some(ExitCode::SUCCESS));
+        assert!(out.contains("2023-12-12 至 2024-01-10"), "{out}");
+        assert_eq!(
+            std::fs::read_to_string(&ics).unwrap(),
+            crate::export::to_ics(&records, &Texts::new(Language::En))
+        );
+
+        for (list, message) in [
+            (vec!["history", "export", "out.json"], "无法识别的导出格式"),
+            (
+                vec![
+                    "history",
+                    "export",
+                    "--from",
+                    "2024-01-09",
+                    "--to",
+                    "2024-01-01",
+                    csv_arg,
+                ],
+                "晚于",
+            ),
+            (vec!["history", "export", "--from"], "用法"),
+            (vec!["history", "export", "a.csv", "b.csv"], "用法"),
+        ] {
+            let (code, _, err) = run_with_history(&store, Some(&history), &list);
+            assert_eq!(code, Some(ExitCode::FAILURE), "{list:?}");
+            assert!(err.contains(message), "{list:?}: {err}");
+        }
+        let (code, _, err) = run_with(&store, &["history", "export", csv_arg]);
+        assert_eq!(code, Some(ExitCode::FAILURE));
+        assert!(err.contains("HOME"), "{err}");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn report_writes_a_themed_page() {
+        let dir = std::env::temp_dir().join(format!("restgap-cli-report-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let store = FileStore::new(dir.join(FileStore::FILE_NAME));
+        store
+            .save(&Config {
+                theme: crate::config::Theme::Light,
+                language: LanguagePreference::Zh,
+                ..Config::default()
+            })
+            .unwrap();
+        let history = HistoryStore::new(
+            dir.join(HistoryStore::FILE_NAME),
+            HistoryStore::DEFAULT_RETENTION,
+        );
+
+        let page = dir.join("report.html");
+        let list = ["report", "--out", page.to_str().unwrap()];
+        let (code, out, _) = run_with_history(&store, Some(&history), &list);
+        assert_eq!(code, Some(ExitCode::SUCCESS));
+        assert!(out.contains("0 条记录"), "{out}");
+        let html = std::fs::read_to_string(&page).unwrap();
+        assert!(html.contains(r#"<html lang="zh-CN" class="light">"#));
+        assert!(html.contains("生成于 2024-01-10"));
+
+        let (code, ..) = run_with_history(&store, Some(&history), &["report"]);
+        assert_eq!(code, Some(ExitCode::FAILURE));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,1196 @@
+//! 配置管理模块
+//!
+//! 定义与平台无关的配置结构、默认值与取值范围，以及配置存储抽象 [`ConfigStore`]。
+//! 各存储只负责读写 [`RawConfig`]，默认值与范围限制统一由 [`Config::from_raw`] 处理。
+
+use std::ffi::OsString;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::AppError;
+use crate::i18n::{Language, LanguagePreference};
+use crate::idle::IdlePolicy;
+use crate::migration;
+use crate::profile::Profile;
+use crate::scheduler::Timings;
+use crate::work_hours::WorkHours;
+
+/// 界面主题外观
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Dark = 0,
+    Light = 1,
+}
+
+/// 闲置策略的类型（参数另行保存）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdlePolicyKind {
+    Tolerance = 0,
+    Disabled = 1,
+    Percent = 2,
+    BreakLength = 3,
+}
+
+impl IdlePolicyKind {
+    /// 由存储编号得到策略类型，未知编号按容差策略处理
+    pub const fn from_code(code: i64) -> Self {
+        match code {
+            1 => Self::Disabled,
+            2 => Self::Percent,
+            3 => Self::BreakLength,
+            _ => Self::Tolerance,
+        }
+    }
+
+    /// 存储编号
+    pub const fn code(self) -> i64 {
+        self as i64
+    }
+}
+
+/// 应用配置
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)] // 各开关相互独立，对应设置页中的开关项
+pub struct Config {
+    pub interval_minutes: u64,
+    pub break_seconds: u64,
+    pub language: LanguagePreference,
+    pub allow_skip_break: bool,
+    pub theme: Theme,
+    /// 每隔多少次休息安排一次长休息（0 表示关闭）
+    pub long_break_every: u64,
+    pub long_break_seconds: u64,
+    pub postpone_minutes: u64,
+    /// 每轮最多可推迟的次数（0 表示关闭推迟）
+    pub max_postpones: u64,
+    /// 休息前预告秒数（0 表示关闭）
+    pub pre_break_seconds: u64,
+    /// 是否仅在工作时段内提醒
+    pub work_hours_enabled: bool,
+    pub work_hours: WorkHours,
+    /// 工作周期结束时的闲置跳过策略
+    pub idle_policy: IdlePolicy,
+    /// 是否只把活跃时间计入工作时长
+    pub active_time: bool,
+    /// 活跃时间模式下，超过该秒数的连续空闲不计入工作时长
+    pub active_idle_threshold_seconds: u64,
+    /// 休息期间仍在操作电脑时，按操作时长延长休息
+    pub extend_active_breaks: bool,
+    /// 休息结束后等检测到输入再开始下一轮工作计时
+    pub await_return: bool,
+    /// 工作期间离开电脑达到休息时长时视为自然休息，重新开始工作周期
+    pub natural_break: bool,
+    /// 每日完成休息次数的目标（0 表示关闭目标与连续天数）
+    pub daily_goal: u64,
+    /// 用户保存的配置方案（内置方案见 [`Profile::presets`]）
+    pub profiles: Vec<Profile>,
+    /// 当前方案名称；修改时长后可能与方案不一致，见 [`Config::active_profile_matches`]
+    pub active_profile: Option<String>,
+}
+
+/// 存储中的原始配置
+///
+/// 未设置的字段为 `None`，由 [`Config::from_raw`] 补上默认值并限制在合法范围内。
+/// 字段名即文件存储中的 JSON 键名。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RawConfig {
+    pub interval_minutes: Option<u64>,
+    pub break_seconds: Option<u64>,
+    pub language: Option<LanguagePreference>,
+    pub allow_skip_break: Option<bool>,
+    pub theme: Option<Theme>,
+    pub long_break_every: Option<u64>,
+    pub long_break_seconds: Option<u64>,
+    pub postpone_minutes: Option<u64>,
+    pub max_postpones: Option<u64>,
+    pub pre_break_seconds: Option<u64>,
+    pub work_hours_enabled: Option<bool>,
+    /// 文本格式的工作时段，见 [`WorkHours::parse`]
+    pub work_hours: Option<String>,
+    pub idle_policy: Option<IdlePolicyKind>,
+    pub idle_tolerance_seconds: Option<u64>,
+    pub idle_percent: Option<u64>,
+    pub active_time: Option<bool>,
+    pub active_idle_threshold_seconds: Option<u64>,
+    pub extend_active_breaks: Option<bool>,
+    pub await_return: Option<bool>,
+    pub natural_break: Option<bool>,
+    pub daily_goal: Option<u64>,
+    pub profiles: Option<Vec<Profile>>,
+    pub active_profile: Option<String>,
+}
+
+impl Config {
+    pub const DEFAULT_INTERVAL_MINUTES: u64 = 30;
+    pub const DEFAULT_BREAK_SECONDS: u64 = 120;
+
+    pub const MIN_INTERVAL_MINUTES: u64 = 1;
+    pub const MAX_INTERVAL_MINUTES: u64 = 240;
+
+    pub const MIN_BREAK_SECONDS: u64 = 5;
+    pub const MAX_BREAK_SECONDS: u64 = 3600;
+
+    pub const DEFAULT_LONG_BREAK_EVERY: u64 = 0;
+    pub const DEFAULT_LONG_BREAK_SECONDS: u64 = 600;
+    pub const MAX_LONG_BREAK_EVERY: u64 = 12;
+
+    pub const DEFAULT_POSTPONE_MINUTES: u64 = 5;
+    pub const DEFAULT_MAX_POSTPONES: u64 = 2;
+    pub const MIN_POSTPONE_MINUTES: u64 = 1;
+    pub const MAX_POSTPONE_MINUTES: u64 = 60;
+    pub const MAX_POSTPONES: u64 = 10;
+
+    pub const DEFAULT_PRE_BREAK_SECONDS: u64 = 0;
+    pub const MAX_PRE_BREAK_SECONDS: u64 = 300;
+
+    pub const DEFAULT_IDLE_TOLERANCE_SECONDS: u64 = 8;
+    pub const MIN_IDLE_TOLERANCE_SECONDS: u64 = 1;
+    pub const MAX_IDLE_TOLERANCE_SECONDS: u64 = 300;
+
+    pub const DEFAULT_IDLE_PERCENT: u64 = 90;
+    pub const MIN_IDLE_PERCENT: u64 = 50;
+    pub const MAX_IDLE_PERCENT: u64 = 100;
+
+    pub const DEFAULT_ACTIVE_IDLE_THRESHOLD_SECONDS: u64 = 120;
+    pub const MIN_ACTIVE_IDLE_THRESHOLD_SECONDS: u64 = 30;
+    pub const MAX_ACTIVE_IDLE_THRESHOLD_SECONDS: u64 = 1800;
+
+    pub const DEFAULT_DAILY_GOAL: u64 = 8;
+    pub const MAX_DAILY_GOAL: u64 = 48;
+
+    /// 由原始配置构造：未设置的字段使用默认值，所有数值限制在合法范围内
+    pub fn from_raw(raw: &RawConfig) -> Self {
+        Self {
+            interval_minutes: clamp_u64(
+                raw.interval_minutes
+                    .unwrap_or(Self::DEFAULT_INTERVAL_MINUTES),
+                Self::MIN_INTERVAL_MINUTES,
+                Self::MAX_INTERVAL_MINUTES,
+            ),
+            break_seconds: clamp_u64(
+                raw.break_seconds.unwrap_or(Self::DEFAULT_BREAK_SECONDS),
+                Self::MIN_BREAK_SECONDS,
+                Self::MAX_BREAK_SECONDS,
+            ),
+            language: raw.language.unwrap_or_default(),
+            allow_skip_break: raw.allow_skip_break.unwrap_or(false),
+            theme: raw.theme.unwrap_or_default(),
+            long_break_every: raw
+                .long_break_every
+                .unwrap_or(Self::DEFAULT_LONG_BREAK_EVERY)
+                .min(Self::MAX_LONG_BREAK_EVERY),
+            long_break_seconds: clamp_u64(
+                raw.long_break_seconds
+                    .unwrap_or(Self::DEFAULT_LONG_BREAK_SECONDS),
+                Self::MIN_BREAK_SECONDS,
+                Self::MAX_BREAK_SECONDS,
+            ),
+            postpone_minutes: clamp_u64(
+                raw.postpone_minutes
+                    .unwrap_or(Self::DEFAULT_POSTPONE_MINUTES),
+                Self::MIN_POSTPONE_MINUTES,
+                Self::MAX_POSTPONE_MINUTES,
+            ),
+            max_postpones: raw
+                .max_postpones
+                .unwrap_or(Self::DEFAULT_MAX_POSTPONES)
+                .min(Self::MAX_POSTPONES),
+            pre_break_seconds: raw
+                .pre_break_seconds
+                .unwrap_or(Self::DEFAULT_PRE_BREAK_SECONDS)
+                .min(Self::MAX_PRE_BREAK_SECONDS),
+            work_hours_enabled: raw.work_hours_enabled.unwrap_or(false),
+            work_hours: raw
+                .work_hours
+                .as_deref()
+                .and_then(|spec| WorkHours::parse(spec).ok())
+                .unwrap_or_else(WorkHours::weekdays_nine_to_six),
+            idle_policy: Self::idle_policy_from_raw(
+                raw.idle_policy.map_or(0, IdlePolicyKind::code),
+                raw.idle_tolerance_seconds.unwrap_or(0),
+                raw.idle_percent.unwrap_or(0),
+            ),
+            active_time: raw.active_time.unwrap_or(false),
+            active_idle_threshold_seconds: clamp_u64(
+                raw.active_idle_threshold_seconds
+                    .unwrap_or(Self::DEFAULT_ACTIVE_IDLE_THRESHOLD_SECONDS),
+                Self::MIN_ACTIVE_IDLE_THRESHOLD_SECONDS,
+                Self::MAX_ACTIVE_IDLE_THRESHOLD_SECONDS,
+            ),
+            extend_active_breaks: raw.extend_active_breaks.unwrap_or(false),
+            await_return: raw.await_return.unwrap_or(false),
+            natural_break: raw.natural_break.unwrap_or(false),
+            daily_goal: raw
+                .daily_goal
+                .unwrap_or(Self::DEFAULT_DAILY_GOAL)
+                .min(Self::MAX_DAILY_GOAL),
+            profiles: Self::profiles_from_raw(raw.profiles.as_deref().unwrap_or_default()),
+            active_profile: None,
+        }
+        .with_active_profile(raw.active_profile.as_deref())
+    }
+
+    /// 限制方案时长并去掉无名和重名（保留最后一个）的方案
+    fn profiles_from_raw(raw: &[Profile]) -> Vec<Profile> {
+        let mut profiles: Vec<Profile> = Vec::new();
+        for profile in raw.iter().map(Profile::clamped) {
+            if profile.name.is_empty() {
+                continue;
+            }
+            profiles.retain(|p| p.name != profile.name);
+            profiles.push(profile);
+        }
+        profiles
+    }
+
+    /// 设置当前方案名称；方案已不存在时不使用任何方案
+    fn with_active_profile(mut self, name: Option<&str>) -> Self {
+        self.active_profile = name
+            .filter(|name| self.find_profile(name).is_some())
+            .map(str::to_string);
+        self
+    }
+
+    /// 转换为可直接写入存储的原始配置（所有字段均已设置）
+    pub fn to_raw(&self) -> RawConfig {
+        RawConfig {
+            interval_minutes: Some(self.interval_minutes),
+            break_seconds: Some(self.break_seconds),
+            language: Some(self.language),
+            allow_skip_break: Some(self.allow_skip_break),
+            theme: Some(self.theme),
+            long_break_every: Some(self.long_break_every),
+            long_break_seconds: Some(self.long_break_seconds),
+            postpone_minutes: Some(self.postpone_minutes),
+            max_postpones: Some(self.max_postpones),
+            pre_break_seconds: Some(self.pre_break_seconds),
+            work_hours_enabled: Some(self.work_hours_enabled),
+            work_hours: Some(self.work_hours.to_string()),
+            idle_policy: Some(IdlePolicyKind::from_code(self.idle_policy_kind())),
+            idle_tolerance_seconds: Some(self.idle_tolerance_seconds()),
+            idle_percent: Some(self.idle_percent()),
+            active_time: Some(self.active_time),
+            active_idle_threshold_seconds: Some(self.active_idle_threshold_seconds),
+            extend_active_breaks: Some(self.extend_active_breaks),
+            await_return: Some(self.await_return),
+            natural_break: Some(self.natural_break),
+            daily_goal: Some(self.daily_goal),
+            profiles: Some(self.profiles.clone()),
+            active_profile: self.active_profile.clone(),
+        }
+    }
+
+    /// 由存储的原始值构造闲置策略；未设置（0）的参数使用默认值并限制在合法范围内
+    pub fn idle_policy_from_raw(kind: i64, tolerance_secs: u64, percent: u64) -> IdlePolicy {
+        match IdlePolicyKind::from_code(kind) {
+            IdlePolicyKind::Disabled => IdlePolicy::Disabled,
+            IdlePolicyKind::Percent => {
+                let percent = if percent == 0 {
+                    Self::DEFAULT_IDLE_PERCENT
+                } else {
+                    clamp_u64(percent, Self::MIN_IDLE_PERCENT, Self::MAX_IDLE_PERCENT)
+                };
+                IdlePolicy::Percent(u8::try_from(percent).unwrap_or(100))
+            }
+            IdlePolicyKind::BreakLength => IdlePolicy::BreakLength,
+            IdlePolicyKind::Tolerance => {
+                let secs = if tolerance_secs == 0 {
+                    Self::DEFAULT_IDLE_TOLERANCE_SECONDS
+                } else {
+                    clamp_u64(
+                        tolerance_secs,
+                        Self::MIN_IDLE_TOLERANCE_SECONDS,
+                        Self::MAX_IDLE_TOLERANCE_SECONDS,
+                    )
+                };
+                IdlePolicy::Tolerance(Duration::from_secs(secs))
+            }
+        }
+    }
+
+    /// 闲置策略的存储编号
+    pub const fn idle_policy_kind(&self) -> i64 {
+        match self.idle_policy {
+            IdlePolicy::Tolerance(_) => IdlePolicyKind::Tolerance.code(),
+            IdlePolicy::Disabled => IdlePolicyKind::Disabled.code(),
+            IdlePolicy::Percent(_) => IdlePolicyKind::Percent.code(),
+            IdlePolicy::BreakLength => IdlePolicyKind::BreakLength.code(),
+        }
+    }
+
+    /// 闲置容差秒数（当前策略不是容差时返回默认值）
+    pub const fn idle_tolerance_seconds(&self) -> u64 {
+        match self.idle_policy {
+            IdlePolicy::Tolerance(tolerance) => tolerance.as_secs(),
+            _ => Self::DEFAULT_IDLE_TOLERANCE_SECONDS,
+        }
+    }
+
+    /// 闲置百分比阈值（当前策略不是百分比时返回默认值）
+    pub const fn idle_percent(&self) -> u64 {
+        match self.idle_policy {
+            IdlePolicy::Percent(percent) => percent as u64,
+            _ => Self::DEFAULT_IDLE_PERCENT,
+        }
+    }
+
+    pub fn effective_language(&self) -> Language {
+        self.language.resolve()
+    }
+
+    /// 获取工作间隔时长
+    pub const fn work_interval(&self) -> Duration {
+        Duration::from_secs(self.interval_minutes.saturating_mul(60))
+    }
+
+    /// 获取休息时长
+    pub const fn break_duration(&self) -> Duration {
+        Duration::from_secs(self.break_seconds)
+    }
+
+    /// 获取工作定时器容差（允许系统合并计时器唤醒）
+    pub fn work_tolerance(&self) -> Duration {
+        let secs = (self.work_interval().as_secs_f64() * 0.10).min(120.0);
+        Duration::from_secs_f64(secs.max(1.0))
+    }
+
+    /// 获取长休息时长
+    pub const fn long_break_duration(&self) -> Duration {
+        Duration::from_secs(self.long_break_seconds)
+    }
+
+    /// 获取单次推迟时长
+    pub const fn postpone_duration(&self) -> Duration {
+        Duration::from_secs(self.postpone_minutes.saturating_mul(60))
+    }
+
+    /// 获取每日目标休息次数；0 表示未设目标
+    pub fn daily_goal_breaks(&self) -> u32 {
+        u32::try_from(self.daily_goal).unwrap_or(u32::MAX)
+    }
+
+    /// 获取休息定时器容差
+    pub fn break_tolerance(&self) -> Duration {
+        break_tolerance_for(self.break_duration())
+    }
+
+    /// 获取长休息定时器容差
+    pub fn long_break_tolerance(&self) -> Duration {
+        break_tolerance_for(self.long_break_duration())
+    }
+
+    /// 获取调度器使用的时长参数
+    pub fn timings(&self) -> Timings {
+        Timings {
+            work_interval: self.work_interval(),
+            break_duration: self.break_duration(),
+            work_tolerance: self.work_tolerance(),
+            break_tolerance: self.break_tolerance(),
+            long_break_duration: self.long_break_duration(),
+            long_break_tolerance: self.long_break_tolerance(),
+            long_break_every: self.long_break_every,
+            postpone_duration: self.postpone_duration(),
+            max_postpones: u32::try_from(self.max_postpones).unwrap_or(u32::MAX),
+            pre_break_duration: Duration::from_secs(self.pre_break_seconds),
+            natural_break: self.natural_break,
+            idle_policy: self.idle_policy,
+            active_threshold: self
+                .active_time
+                .then(|| Duration::from_secs(self.active_idle_threshold_seconds)),
+            extend_active_breaks: self.extend_active_breaks,
+            await_return: self.await_return,
+        }
+    }
+
+    /// 获取生效的工作时段（未启用或未配置任何时段时返回 `None`）
+    pub fn active_work_hours(&self) -> Option<WorkHours> {
+        (self.work_hours_enabled && !self.work_hours.is_empty()).then(|| self.work_hours.clone())
+    }
+}
+
+impl Config {
+    /// 检查所有取值是否在合法范围内，逐字段返回错误
+    ///
+    /// [`Config::from_raw`] 会把存储中的越界值限制到范围内；用户输入则应先经过这里的检查，
+    /// 不合法时拒绝保存，而不是悄悄改成最接近的合法值。
+    pub fn validate(&self) -> Result<(), Vec<AppError>> {
+        let errors: Vec<AppError> = self
+            .to_raw()
+            .out_of_range()
+            .into_iter()
+            .map(AppError::from)
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::from_raw(&RawConfig::default())
+    }
+}
+
+/// 导入时被拒绝的值
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rejected {
+    /// JSON 键名
+    pub field: String,
+    /// 文件中的原始值
+    pub value: String,
+    pub reason: RejectReason,
+}
+
+/// 值被拒绝的原因
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RejectReason {
+    /// 数值超出允许范围
+    OutOfRange { min: u64, max: u64 },
+    /// 类型或格式不正确
+    Invalid(String),
+    /// 无法识别的键
+    UnknownField,
+    /// 由更新版本的 `RestGap` 导出
+    NewerSchema,
+}
+
+impl fmt::Display for Rejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            field,
+            value,
+            reason,
+        } = self;
+        match reason {
+            RejectReason::OutOfRange { min, max } => {
+                write!(f, "{field} = {value}: 超出范围 {min}..={max}")
+            }
+            RejectReason::Invalid(msg) => write!(f, "{field} = {value}: {msg}"),
+            RejectReason::UnknownField => write!(f, "{field}: 无法识别的配置项"),
+            RejectReason::NewerSchema => {
+                write!(f, "{field} = {value}: 由更新版本的 RestGap 导出")
+            }
+        }
+    }
+}
+
+/// 导入配置失败的原因
+#[derive(Debug)]
+pub enum ImportError {
+    /// 文件不是 JSON 对象
+    Parse(serde_json::Error),
+    /// 存在被拒绝的值；导入不会对它们做范围限制
+    Rejected(Vec<Rejected>),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "无法解析配置文件: {err}"),
+            Self::Rejected(rejected) => {
+                write!(f, "配置文件中有 {} 个值被拒绝", rejected.len())?;
+                for item in rejected {
+                    write!(f, "\n  {item}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl Config {
+    /// 导出为 JSON 文本，格式与 [`FileStore`] 相同，并包含结构版本
+    pub fn to_json(&self) -> String {
+        let mut object = match serde_json::to_value(self.to_raw()) {
+            Ok(Value::Object(object)) => object,
+            _ => Map::new(),
+        };
+        object.insert(
+            migration::VERSION_KEY.to_string(),
+            migration::SCHEMA_VERSION.into(),
+        );
+        let mut text = serde_json::to_string_pretty(&Value::Object(object)).unwrap_or_default();
+        text.push('\n');
+        text
+    }
+
+    /// 从导出的 JSON 文本导入
+    ///
+    /// 缺失的字段使用默认值；超出范围、格式错误或无法识别的值会被全部列出并拒绝导入，
+    /// 而不是像读取存储时那样限制到合法范围内。旧版本导出的文件会先执行迁移。
+    pub fn from_json(text: &str) -> Result<Self, ImportError> {
+        let mut object: Map<String, Value> =
+            serde_json::from_str(text).map_err(ImportError::Parse)?;
+        let mut rejected = Vec::new();
+
+        match object.get(migration::VERSION_KEY).map(Value::as_i64) {
+            Some(Some(version)) if version > migration::SCHEMA_VERSION => {
+                rejected.push(Rejected {
+                    field: migration::VERSION_KEY.to_string(),
+                    value: version.to_string(),
+                    reason: RejectReason::NewerSchema,
+                });
+            }
+            Some(Some(version)) if version < migration::SCHEMA_VERSION => {
+                migration::migrate(&mut object);
+            }
+            _ => {}
+        }
+        object.remove(migration::VERSION_KEY);
+        object.remove(migration::HISTORY_KEY);
+
+        // 逐个字段反序列化，以便准确指出每个格式错误的值
+        let known = match serde_json::to_value(RawConfig::default()) {
+            Ok(Value::Object(fields)) => fields,
+            _ => Map::new(),
+        };
+        for (key, value) in &object {
+            if !known.contains_key(key) {
+                rejected.push(Rejected {
+                    field: key.clone(),
+                    value: value.to_string(),
+                    reason: RejectReason::UnknownField,
+                });
+                continue;
+            }
+            let single = Value::Object(Map::from_iter([(key.clone(), value.clone())]));
+            if let Err(err) = serde_json::from_value::<RawConfig>(single) {
+                rejected.push(Rejected {
+                    field: key.clone(),
+                    value: value.to_string(),
+                    reason: RejectReason::Invalid(err.to_string()),
+                });
+            }
+        }
+        if !rejected.is_empty() {
+            return Err(ImportError::Rejected(rejected));
+        }
+
+        let raw: RawConfig =
+            serde_json::from_value(Value::Object(object)).map_err(ImportError::Parse)?;
+        let rejected = raw.out_of_range();
+        if rejected.is_empty() {
+            Ok(Self::from_raw(&raw))
+        } else {
+            Err(ImportError::Rejected(rejected))
+        }
+    }
+}
+
+impl RawConfig {
+    /// 数值字段及其合法范围：（字段名，值，最小值，最大值）
+    const fn bounds(&self) -> [(&'static str, Option<u64>, u64, u64); 11] {
+        [
+            (
+                "interval_minutes",
+                self.interval_minutes,
+                Config::MIN_INTERVAL_MINUTES,
+                Config::MAX_INTERVAL_MINUTES,
+            ),
+            (
+                "break_seconds",
+                self.break_seconds,
+                Config::MIN_BREAK_SECONDS,
+                Config::MAX_BREAK_SECONDS,
+            ),
+            (
+                "long_break_every",
+                self.long_break_every,
+                0,
+                Config::MAX_LONG_BREAK_EVERY,
+            ),
+            (
+                "long_break_seconds",
+                self.long_break_seconds,
+                Config::MIN_BREAK_SECONDS,
+                Config::MAX_BREAK_SECONDS,
+            ),
+            (
+                "postpone_minutes",
+                self.postpone_minutes,
+                Config::MIN_POSTPONE_MINUTES,
+                Config::MAX_POSTPONE_MINUTES,
+            ),
+            (
+                "max_postpones",
+                self.max_postpones,
+                0,
+                Config::MAX_POSTPONES,
+            ),
+            (
+                "pre_break_seconds",
+                self.pre_break_seconds,
+                0,
+                Config::MAX_PRE_BREAK_SECONDS,
+            ),
+            (
+                "idle_tolerance_seconds",
+                self.idle_tolerance_seconds,
+                Config::MIN_IDLE_TOLERANCE_SECONDS,
+                Config::MAX_IDLE_TOLERANCE_SECONDS,
+            ),
+            (
+                "idle_percent",
+                self.idle_percent,
+                Config::MIN_IDLE_PERCENT,
+                Config::MAX_IDLE_PERCENT,
+            ),
+            (
+                "active_idle_threshold_seconds",
+                self.active_idle_threshold_seconds,
+                Config::MIN_ACTIVE_IDLE_THRESHOLD_SECONDS,
+                Config::MAX_ACTIVE_IDLE_THRESHOLD_SECONDS,
+            ),
+            ("daily_goal", self.daily_goal, 0, Config::MAX_DAILY_GOAL),
+        ]
+    }
+
+    /// 列出超出合法范围的值；[`Config::from_raw`] 会限制这些值，导入时则拒绝它们
+    pub fn out_of_range(&self) -> Vec<Rejected> {
+        let mut rejected: Vec<Rejected> = self
+            .bounds()
+            .into_iter()
+            .filter_map(|(field, value, min, max)| {
+                let value = value.filter(|v| !(min..=max).contains(v))?;
+                Some(Rejected {
+                    field: field.to_string(),
+                    value: value.to_string(),
+                    reason: RejectReason::OutOfRange { min, max },
+                })
+            })
+            .collect();
+
+        if let Some(spec) = &self.work_hours
+            && let Err(msg) = WorkHours::parse(spec)
+        {
+            rejected.push(Rejected {
+                field: "work_hours".to_string(),
+                value: format!("{spec:?}"),
+                reason: RejectReason::Invalid(msg),
+            });
+        }
+
+        let profiles = self.profiles.as_deref().unwrap_or_default();
+        for (i, profile) in profiles.iter().enumerate() {
+            rejected.extend(profile.out_of_range(&format!("profiles[{i}]")));
+        }
+        if let Some(name) = &self.active_profile {
+            let known = Profile::presets()
+                .iter()
+                .chain(profiles)
+                .any(|p| p.name == *name);
+            if !known {
+                rejected.push(Rejected {
+                    field: "active_profile".to_string(),
+                    value: format!("{name:?}"),
+                    reason: RejectReason::Invalid("没有这个名称的方案".to_string()),
+                });
+            }
+        }
+        rejected
+    }
+}
+
+/// 配置存储
+pub trait ConfigStore {
+    /// 把存储中的数据迁移到当前结构版本，返回本次执行的迁移名；应在 [`Self::load`] 之前调用
+    fn migrate(&self) -> io::Result<Vec<&'static str>>;
+    /// 读取存储中的原始值；存储中尚无配置时返回全部未设置的原始配置
+    fn load_raw(&self) -> io::Result<RawConfig>;
+    /// 加载配置：未设置的字段使用默认值，越界值限制到合法范围内
+    fn load(&self) -> io::Result<Config> {
+        Ok(Config::from_raw(&self.load_raw()?))
+    }
+    /// 保存配置
+    fn save(&self, config: &Config) -> io::Result<()>;
+}
+
+/// 以 JSON 文件保存配置，默认位于 `$XDG_CONFIG_HOME/restgap/config.json`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileStore {
+    path: PathBuf,
+}
+
+impl FileStore {
+    pub const FILE_NAME: &'static str = "config.json";
+
+    pub const fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// 默认位置的文件存储；无法确定配置目录时返回 `None`
+    pub fn default_location() -> Option<Self> {
+        let dir = config_dir(
+            std::env::var_os("XDG_CONFIG_HOME"),
+            std::env::var_os("HOME"),
+        )?;
+        Some(Self::new(dir.join(Self::FILE_NAME)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 读取文件中的 JSON 对象；文件不存在时返回 `None`
+    fn read(&self) -> io::Result<Option<Map<String, Value>>> {
+        let text = match std::fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        serde_json::from_str(&text).map(Some).map_err(Into::into)
+    }
+
+    fn write(&self, object: Map<String, Value>) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut text = serde_json::to_string_pretty(&Value::Object(object))?;
+        text.push('\n');
+
+        // 先写临时文件再重命名，避免写到一半时留下损坏的配置
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, text)?;
+        std::fs::rename(&tmp, &self.path)
+    }
+}
+
+impl ConfigStore for FileStore {
+    fn migrate(&self) -> io::Result<Vec<&'static str>> {
+        let Some(mut object) = self.read()? else {
+            return Ok(Vec::new());
+        };
+        let applied = migration::migrate(&mut object);
+        if !applied.is_empty() {
+            self.write(object)?;
+        }
+        Ok(applied)
+    }
+
+    fn load_raw(&self) -> io::Result<RawConfig> {
+        let Some(object) = self.read()? else {
+            return Ok(RawConfig::default());
+        };
+        Ok(serde_json::from_value(Value::Object(object))?)
+    }
+
+    fn save(&self, config: &Config) -> io::Result<()> {
+        // 保留版本、迁移记录以及无法识别的键（可能来自更新的版本）
+        let mut object = self.read().ok().flatten().unwrap_or_default();
+        if let Value::Object(fields) = serde_json::to_value(config.to_raw())? {
+            object.extend(fields);
+        }
+        object.insert(
+            migration::VERSION_KEY.to_string(),
+            migration::SCHEMA_VERSION.into(),
+        );
+        self.write(object)
+    }
+}
+
+/// 按 XDG 规范确定配置目录：`$XDG_CONFIG_HOME/restgap`，未设置（或不是绝对路径）时使用
+/// `$HOME/.config/restgap`
+fn config_dir(xdg_config_home: Option<OsString>, home: Option<OsString>) -> Option<PathBuf> {
+    let base = xdg_config_home
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| {
+            home.map(PathBuf::from)
+                .filter(|dir| dir.is_absolute())
+                .map(|home| home.join(".config"))
+        })?;
+    Some(base.join("restgap"))
+}
+
+fn break_tolerance_for(duration: Duration) -> Duration {
+    let secs = (duration.as_secs_f64() * 0.10).min(5.0);
+    Duration::from_secs_f64(secs.max(0.5))
+}
+
+/// 将值限制在指定范围内
+pub fn clamp_u64(v: u64, min: u64, max: u64) -> u64 {
+    v.max(min).min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamp_u64() {
+        assert_eq!(clamp_u64(5, 1, 10), 5);
+        assert_eq!(clamp_u64(0, 1, 10), 1);
+        assert_eq!(clamp_u64(15, 1, 10), 10);
+    }
+
+    #[test]
+    fn test_config_default() {
+        let config = Config::default();
+        assert_eq!(config.interval_minutes, Config::DEFAULT_INTERVAL_MINUTES);
+        assert_eq!(config.break_seconds, Config::DEFAULT_BREAK_SECONDS);
+        assert_eq!(config.language, LanguagePreference::Auto);
+        assert!(!config.allow_skip_break);
+        assert_eq!(config.theme, Theme::Dark);
+        assert_eq!(config.long_break_every, 0);
+        assert_eq!(config.pre_break_seconds, 0);
+    }
+
+    #[test]
+    fn test_work_interval() {
+        let config = Config {
+            interval_minutes: 30,
+            break_seconds: 120,
+            language: LanguagePreference::Auto,
+            allow_skip_break: false,
+            theme: Theme::Dark,
+            ..Config::default()
+        };
+        assert_eq!(config.work_interval(), Duration::from_secs(1800));
+    }
+
+    #[test]
+    fn test_break_duration() {
+        let config = Config {
+            interval_minutes: 30,
+            break_seconds: 120,
+            language: LanguagePreference::Auto,
+            allow_skip_break: false,
+            theme: Theme::Dark,
+            ..Config::default()
+        };
+        assert_eq!(config.break_duration(), Duration::from_secs(120));
+    }
+
+    #[test]
+    fn test_long_break_timings() {
+        let config = Config {
+            long_break_every: 4,
+            long_break_seconds: 900,
+            ..Config::default()
+        };
+        let timings = config.timings();
+        assert_eq!(timings.long_break_every, 4);
+        assert_eq!(timings.long_break_duration, Duration::from_secs(900));
+        assert_eq!(timings.long_break_tolerance, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_postpone_timings() {
+        let config = Config::default();
+        let timings = config.timings();
+        assert_eq!(timings.postpone_duration, Duration::from_secs(300));
+        assert_eq!(timings.max_postpones, 2);
+    }
+
+    #[test]
+    fn test_work_hours_disabled_by_default() {
+        let mut config = Config::default();
+        assert_eq!(config.active_work_hours(), None);
+
+        config.work_hours_enabled = true;
+        assert_eq!(
+            config.active_work_hours(),
+            Some(WorkHours::weekdays_nine_to_six())
+        );
+
+        config.work_hours = WorkHours::default();
+        assert_eq!(config.active_work_hours(), None);
+    }
+
+    #[test]
+    fn test_active_time_timings() {
+        let mut config = Config::default();
+        assert_eq!(config.timings().active_threshold, None);
+
+        config.active_time = true;
+        assert_eq!(
+            config.timings().active_threshold,
+            Some(Duration::from_secs(120))
+        );
+    }
+
+    #[test]
+    fn test_idle_policy_from_raw() {
+        assert_eq!(Config::idle_policy_from_raw(0, 0, 0), IdlePolicy::default());
+        assert_eq!(
+            Config::idle_policy_from_raw(0, 999, 0),
+            IdlePolicy::Tolerance(Duration::from_secs(300))
+        );
+        assert_eq!(Config::idle_policy_from_raw(1, 0, 0), IdlePolicy::Disabled);
+        assert_eq!(
+            Config::idle_policy_from_raw(2, 0, 10),
+            IdlePolicy::Percent(50)
+        );
+        assert_eq!(
+            Config::idle_policy_from_raw(3, 0, 0),
+            IdlePolicy::BreakLength
+        );
+
+        let config = Config {
+            idle_policy: IdlePolicy::Percent(80),
+            ..Config::default()
+        };
+        assert_eq!(config.idle_policy_kind(), 2);
+        assert_eq!(config.idle_percent(), 80);
+        assert_eq!(
+            config.idle_tolerance_seconds(),
+            Config::DEFAULT_IDLE_TOLERANCE_SECONDS
+        );
+    }
+
+    #[test]
+    fn test_from_raw_clamps_out_of_range_values() {
+        let config = Config::from_raw(&RawConfig {
+            interval_minutes: Some(0),
+            break_seconds: Some(99_999),
+            long_break_every: Some(50),
+            max_postpones: Some(99),
+            pre_break_seconds: Some(1000),
+            daily_goal: Some(100),
+            work_hours: Some("Funday 09:00-10:00".to_string()),
+            ..RawConfig::default()
+        });
+        assert_eq!(config.daily_goal, Config::MAX_DAILY_GOAL);
+        assert_eq!(config.interval_minutes, Config::MIN_INTERVAL_MINUTES);
+        assert_eq!(config.break_seconds, Config::MAX_BREAK_SECONDS);
+        assert_eq!(config.long_break_every, Config::MAX_LONG_BREAK_EVERY);
+        assert_eq!(config.max_postpones, Config::MAX_POSTPONES);
+        assert_eq!(config.pre_break_seconds, Config::MAX_PRE_BREAK_SECONDS);
+        assert_eq!(config.work_hours, WorkHours::weekdays_nine_to_six());
+    }
+
+    #[test]
+    fn test_validate_reports_each_invalid_field() {
+        assert!(Config::default().validate().is_ok());
+
+        let config = Config {
+            interval_minutes: 999,
+            break_seconds: 1,
+            idle_policy: IdlePolicy::Percent(20),
+            ..Config::default()
+        };
+        let errors = config.validate().unwrap_err();
+        let fields: Vec<_> = errors
+            .iter()
+            .map(|err| match err {
+                AppError::Config(rejected) => rejected.field.as_str(),
+                _ => "",
+            })
+            .collect();
+        assert_eq!(
+            fields,
+            ["interval_minutes", "break_seconds", "idle_percent"]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "配置错误: interval_minutes = 999: 超出范围 1..=240"
+        );
+    }
+
+    #[test]
+    fn test_raw_round_trip() {
+        let config = Config {
+            interval_minutes: 52,
+            break_seconds: 1020,
+            language: LanguagePreference::Zh,
+            theme: Theme::Light,
+            work_hours_enabled: true,
+            work_hours: WorkHours::parse("Mon-Fri 09:00-12:00 13:00-18:30").unwrap(),
+            idle_policy: IdlePolicy::Percent(75),
+            active_time: true,
+            await_return: true,
+            natural_break: true,
+            profiles: vec![Profile {
+                name: "Deep work".to_string(),
+                interval_minutes: 50,
+                break_seconds: 600,
+                long_break_every: 0,
+                long_break_seconds: 600,
+            }],
+            active_profile: Some("Deep work".to_string()),
+            ..Config::default()
+        };
+        assert_eq!(Config::from_raw(&config.to_raw()), config);
+    }
+
+    #[test]
+    fn test_config_dir_follows_xdg() {
+        assert_eq!(
+            config_dir(Some("/xdg".into()), Some("/home/me".into())),
+            Some(PathBuf::from("/xdg/restgap"))
+        );
+        assert_eq!(
+            config_dir(Some("relative".into()), Some("/home/me".into())),
+            Some(PathBuf::from("/home/me/.config/restgap"))
+        );
+        assert_eq!(config_dir(None, None), None);
+    }
+
+    fn temp_store(name: &str) -> FileStore {
+        let dir = std::env::temp_dir().join(format!("restgap-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        FileStore::new(dir.join(FileStore::FILE_NAME))
+    }
+
+    #[test]
+    fn test_file_store_round_trip() {
+        let store = temp_store("round-trip");
+        assert_eq!(store.load().unwrap(), Config::default());
+
+        let config = Config {
+            interval_minutes: 25,
+            break_seconds: 300,
+            language: LanguagePreference::En,
+            ..Config::default()
+        };
+        store.save(&config).unwrap();
+        assert_eq!(store.load().unwrap(), config);
+
+        let text = std::fs::read_to_string(store.path()).unwrap();
+        assert!(text.contains("\"language\": \"en\""), "{text}");
+        let _ = std::fs::remove_dir_all(store.path().parent().unwrap());
+    }
+
+    #[test]
+    fn test_file_store_fills_missing_fields_with_defaults() {
+        let store = temp_store("partial");
+        std::fs::create_dir_all(store.path().parent().unwrap()).unwrap();
+        std::fs::write(
+            store.path(),
+            r#"{"interval_minutes": 500, "theme": "light", "idle_policy": "break_length"}"#,
+        )
+        .unwrap();
+
+        let config = store.load().unwrap();
+        assert_eq!(config.interval_minutes, Config::MAX_INTERVAL_MINUTES);
+        assert_eq!(config.theme, Theme::Light);
+        assert_eq!(config.idle_policy, IdlePolicy::BreakLength);
+        assert_eq!(config.break_seconds, Config::DEFAULT_BREAK_SECONDS);
+
+        std::fs::write(store.path(), "not json").unwrap();
+        assert!(store.load().is_err());
+        let _ = std::fs::remove_dir_all(store.path().parent().unwrap());
+    }
+
+    #[test]
+    fn test_file_store_migrates_old_files_and_versions_new_ones() {
+        let store = temp_store("migrate");
+        assert!(store.migrate().unwrap().is_empty());
+
+        std::fs::create_dir_all(store.path().parent().unwrap()).unwrap();
+        std::fs::write(store.path(), r#"{"interval_minutes": 0, "custom": true}"#).unwrap();
+        assert_eq!(store.migrate().unwrap().len(), MIGRATIONS_LEN);
+        assert!(store.migrate().unwrap().is_empty());
+        assert_eq!(
+            store.load().unwrap().interval_minutes,
+            Config::DEFAULT_INTERVAL_MINUTES
+        );
+
+        store.save(&Config::default()).unwrap();
+        let object = store.read().unwrap().unwrap();
+        assert_eq!(object["custom"], Value::Bool(true));
+        assert_eq!(object[migration::VERSION_KEY], migration::SCHEMA_VERSION);
+        assert!(object.contains_key(migration::HISTORY_KEY));
+        let _ = std::fs::remove_dir_all(store.path().parent().unwrap());
+    }
+
+    #[test]
+    fn test_json_export_round_trip() {
+        let config = Config {
+            interval_minutes: 52,
+            break_seconds: 1020,
+            language: LanguagePreference::En,
+            theme: Theme::Light,
+            idle_policy: IdlePolicy::BreakLength,
+            ..Config::default()
+        };
+        let text = config.to_json();
+        assert!(text.contains("\"theme\": \"light\""), "{text}");
+        assert!(text.contains("\"schema_version\": 2"), "{text}");
+        assert_eq!(Config::from_json(&text).unwrap(), config);
+
+        let partial = Config::from_json(r#"{"break_seconds": 300}"#).unwrap();
+        assert_eq!(partial.break_seconds, 300);
+        assert_eq!(partial.interval_minutes, Config::DEFAULT_INTERVAL_MINUTES);
+    }
+
+    #[test]
+    fn test_json_import_reports_rejected_values() {
+        let Err(ImportError::Rejected(rejected)) = Config::from_json(
+            r#"{"interval_minutes": 500, "break_seconds": 2, "work_hours": "Funday 09:00-10:00"}"#,
+        ) else {
+            panic!("out-of-range values must be rejected");
+        };
+        assert_eq!(
+            rejected
+                .iter()
+                .map(|r| r.field.as_str())
+                .collect::<Vec<_>>(),
+            ["interval_minutes", "break_seconds", "work_hours"]
+        );
+        assert_eq!(
+            rejected[0].reason,
+            RejectReason::OutOfRange {
+                min: Config::MIN_INTERVAL_MINUTES,
+                max: Config::MAX_INTERVAL_MINUTES,
+            }
+        );
+        assert_eq!(
+            rejected[0].to_string(),
+            "interval_minutes = 500: 超出范围 1..=240"
+        );
+
+        let Err(ImportError::Rejected(rejected)) = Config::from_json(
+            r#"{"theme": "blue", "interval": 30, "max_postpones": -1, "schema_version": 99}"#,
+        ) else {
+            panic!("malformed values must be rejected");
+        };
+        let reasons: Vec<_> = rejected
+            .iter()
+            .map(|r| (r.field.as_str(), &r.reason))
+            .collect();
+        assert_eq!(reasons.len(), 4, "{rejected:?}");
+        assert!(reasons.contains(&("schema_version", &RejectReason::NewerSchema)));
+        assert!(reasons.contains(&("interval", &RejectReason::UnknownField)));
+        assert!(
+            reasons
+                .iter()
+                .any(|(f, r)| *f == "theme" && matches!(r, RejectReason::Invalid(_)))
+        );
+
+        let Err(ImportError::Rejected(rejected)) = Config::from_json(
+            r#"{"profiles": [{"name": "Crunch", "interval_minutes": 0, "break_seconds": 60}],
+                "active_profile": "Normal day"}"#,
+        ) else {
+            panic!("invalid profiles must be rejected");
+        };
+        assert_eq!(
+            rejected
+                .iter()
+                .map(|r| r.field.as_str())
+                .collect::<Vec<_>>(),
+            ["profiles[0].interval_minutes", "active_profile"]
+        );
+
+        assert!(matches!(
+            Config::from_json("[1, 2]"),
+            Err(ImportError::Parse(_))
+        ));
+    }
+
+    const MIGRATIONS_LEN: usize = migration::MIGRATIONS.len();
+}
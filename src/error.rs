@@ -4,12 +4,14 @@
 
 use std::fmt;
 
+use crate::config::Rejected;
+
 /// 应用错误类型
 #[derive(Debug)]
 #[allow(dead_code)]
 pub enum AppError {
-    /// 配置错误
-    Config(String),
+    /// 配置错误：某个字段的取值不合法
+    Config(Rejected),
     /// UI 错误
     Ui(String),
     /// 系统错误
@@ -19,7 +21,7 @@ pub enum AppError {
 impl fmt::Display for AppError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Config(msg) => write!(f, "配置错误: {msg}"),
+            Self::Config(rejected) => write!(f, "配置错误: {rejected}"),
             Self::Ui(msg) => write!(f, "UI 错误: {msg}"),
             Self::System(msg) => write!(f, "系统错误: {msg}"),
         }
@@ -28,6 +30,12 @@ impl fmt::Display for AppError {
 
 impl std::error::Error for AppError {}
 
+impl From<Rejected> for AppError {
+    fn from(rejected: Rejected) -> Self {
+        Self::Config(rejected)
+    }
+}
+
 /// 应用结果类型
 #[allow(dead_code)]
 pub type AppResult<T> = Result<T, AppError>;
@@ -0,0 +1,409 @@
+//! 休息历史导出。
+//!
+//! 把指定日期范围内的休息历史导出为 CSV（每条记录一行）或 iCalendar（每次完成的休息一个
+//! `VEVENT`），供需要休息记录的职业健康等场合使用。输出只取决于记录和时区偏移，
+//! 同样的历史总是得到同样的文件。
+
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use crate::history::Record;
+use crate::i18n::Texts;
+use crate::scheduler::BreakKind;
+use crate::stats;
+use crate::work_hours::SECS_PER_DAY;
+
+/// 未指定范围时导出最近多少天
+pub const DEFAULT_DAYS: i64 = 30;
+
+/// CSV 表头，列名与历史文件中的字段名一致
+const CSV_HEADER: &str =
+    "timestamp,local_time,kind,break_kind,planned_seconds,actual_seconds,reason";
+/// iCalendar 的 `PRODID`，末尾附加语言
+const ICS_PRODID: &str = "-//RestGap//Break History";
+/// iCalendar 内容行（不含换行）的最大字节数，超出时折行
+const ICS_LINE_LIMIT: usize = 75;
+
+/// 导出格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// 逗号分隔，每条记录一行
+    Csv,
+    /// iCalendar，每次完成的休息一个事件
+    Ics,
+}
+
+impl ExportFormat {
+    /// 全部格式，按菜单中的展示顺序
+    pub const ALL: [Self; 2] = [Self::Csv, Self::Ics];
+
+    /// 文件扩展名（不含点）
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Ics => "ics",
+        }
+    }
+
+    /// 按文件扩展名（`.csv` / `.ics`，不区分大小写）确定格式
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|format| format.extension() == ext)
+    }
+
+    /// 生成导出文件的内容；CSV 只含代码，不受 `texts` 的语言影响
+    pub fn render(self, records: &[Record], utc_offset: i64, texts: &Texts) -> String {
+        match self {
+            Self::Csv => to_csv(records, utc_offset),
+            Self::Ics => to_ics(records, texts),
+        }
+    }
+}
+
+/// 按本地日期表示的导出范围，首尾两天都包含在内
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DateRange {
+    /// 开始日期（本地天数）
+    pub from: i64,
+    /// 结束日期（本地天数）
+    pub to: i64,
+}
+
+/// 无法解析导出范围的原因
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RangeError {
+    /// 不是 `YYYY-MM-DD` 格式的有效日期
+    InvalidDate(String),
+    /// 开始日期晚于结束日期
+    Reversed { from: String, to: String },
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidDate(date) => write!(f, "日期无效: {date:?}（应为 YYYY-MM-DD）"),
+            Self::Reversed { from, to } => write!(f, "开始日期 {from} 晚于结束日期 {to}"),
+        }
+    }
+}
+
+impl std::error::Error for RangeError {}
+
+impl DateRange {
+    /// 解析 `YYYY-MM-DD` 格式的起止日期
+    pub fn parse(from: &str, to: &str) -> Result<Self, RangeError> {
+        let day = |s: &str| stats::parse_date(s).ok_or_else(|| RangeError::InvalidDate(s.into()));
+        let range = Self {
+            from: day(from)?,
+            to: day(to)?,
+        };
+        if range.from > range.to {
+            return Err(RangeError::Reversed {
+                from: stats::format_date(range.from),
+                to: stats::format_date(range.to),
+            });
+        }
+        Ok(range)
+    }
+
+    /// 截至 `today`（含）的最近 `days` 天
+    pub const fn last_days(today: i64, days: i64) -> Self {
+        Self {
+            from: today - (days - 1),
+            to: today,
+        }
+    }
+
+    /// 对应的时间段 `[开始日 00:00, 结束日次日 00:00)`，可直接用于
+    /// [`crate::history::HistoryStore::read_range`]
+    pub fn bounds(self, utc_offset: i64) -> (SystemTime, SystemTime) {
+        let midnight = |day: i64| {
+            let secs = day.saturating_mul(SECS_PER_DAY).saturating_sub(utc_offset);
+            SystemTime::UNIX_EPOCH + Duration::from_secs(u64::try_from(secs).unwrap_or(0))
+        };
+        (midnight(self.from), midnight(self.to + 1))
+    }
+}
+
+/// 导出为 CSV：带表头，每条记录一行，按时间先后排列
+pub fn to_csv(records: &[Record], utc_offset: i64) -> String {
+    let mut out = String::new();
+    out.push_str(CSV_HEADER);
+    out.push('\n');
+    for record in sorted(records) {
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{},{}",
+            record.timestamp,
+            local_time(record.timestamp, utc_offset),
+            record.kind.code(),
+            break_kind_code(record.break_kind),
+            record.planned_seconds,
+            record.actual_seconds,
+            csv_field(record.reason.as_deref().unwrap_or_default()),
+        );
+    }
+    out
+}
+
+/// 导出为 iCalendar：每次完成的休息（按时结束、工作中离开、睡眠）一个 `VEVENT`，
+/// 时间使用 UTC，标题与说明使用 `texts` 的语言；跳过、推迟等没有实际休息的记录不导出
+pub fn to_ics(records: &[Record], texts: &Texts) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{ICS_PRODID}//{}", texts.ics_language_tag()),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+    ];
+    for record in sorted(records).filter(|record| record.kind.is_rest()) {
+        let end = record.timestamp;
+        let start = end.saturating_sub(record.actual_seconds);
+        let kind = record.kind.code();
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{end}-{kind}@restgap"));
+        lines.push(format!("DTSTAMP:{}", utc_time(end)));
+        lines.push(format!("DTSTART:{}", utc_time(start)));
+        // 时长为 0 时省略 DTEND，事件表示一个时刻
+        if end > start {
+            lines.push(format!("DTEND:{}", utc_time(end)));
+        }
+        lines.push(format!("SUMMARY:{}", texts.ics_summary(record.break_kind)));
+        lines.push(format!(
+            "DESCRIPTION:{}",
+            ics_text(&texts.ics_description(record))
+        ));
+        lines.push("TRANSP:TRANSPARENT".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in &lines {
+        push_folded(&mut out, line);
+    }
+    out
+}
+
+/// 按时间先后排列；同一时刻的记录保持写入顺序
+fn sorted(records: &[Record]) -> impl Iterator<Item = &Record> {
+    let mut sorted: Vec<&Record> = records.iter().collect();
+    sorted.sort_by_key(|record| record.timestamp);
+    sorted.into_iter()
+}
+
+const fn break_kind_code(kind: BreakKind) -> &'static str {
+    match kind {
+        BreakKind::Short => "short",
+        BreakKind::Long => "long",
+    }
+}
+
+/// 本地时间，ISO 8601 格式并带时区偏移，例如 `2024-01-01T09:30:00+08:00`
+fn local_time(timestamp: u64, utc_offset: i64) -> String {
+    let local = i64::try_from(timestamp)
+        .unwrap_or(i64::MAX)
+        .saturating_add(utc_offset);
+    let secs = local.rem_euclid(SECS_PER_DAY);
+    let sign = if utc_offset < 0 { '-' } else { '+' };
+    let offset_minutes = utc_offset.unsigned_abs() / 60;
+    format!(
+        "{}T{:02}:{:02}:{:02}{sign}{:02}:{:02}",
+        stats::format_date(local.div_euclid(SECS_PER_DAY)),
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+        offset_minutes / 60,
+        offset_minutes % 60,
+    )
+}
+
+/// iCalendar 的 UTC 时间，例如 `20240101T013000Z`
+fn utc_time(timestamp: u64) -> String {
+    let secs = i64::try_from(timestamp).unwrap_or(i64::MAX);
+    let (year, month, day) = stats::civil_date(secs.div_euclid(SECS_PER_DAY));
+    let secs = secs.rem_euclid(SECS_PER_DAY);
+    format!(
+        "{year:04}{month:02}{day:02}T{:02}{:02}{:02}Z",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+    )
+}
+
+/// CSV 字段：含逗号、引号或换行时用双引号包裹，内部引号重复一次
+fn csv_field(s: &str) -> Cow<'_, str> {
+    if s.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", s.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+/// iCalendar 的 TEXT 值：转义反斜杠、分号、逗号与换行
+fn ics_text(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '\\' | ';' | ',' => {
+                out.push('\\');
+                out.push(ch);
+            }
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// 写入一行并以 CRLF 结尾；超过 75 字节时在字符边界处折行，续行以空格开头
+fn push_folded(out: &mut String, line: &str) {
+    let mut width = 0;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > ICS_LINE_LIMIT {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += ch.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::RecordKind;
+    use crate::i18n::Language;
+
+    /// 2024-01-01（周一）00:00 UTC 的本地天数
+    const MONDAY: i64 = 19_723;
+    /// 东八区
+    const UTC8: i64 = 8 * 3600;
+
+    fn record(
+        utc: (i64, u64, u64),
+        kind: RecordKind,
+        break_kind: BreakKind,
+        actual: u64,
+        reason: Option<&str>,
+    ) -> Record {
+        let (day, hh, mm) = utc;
+        Record {
+            timestamp: u64::try_from(day * SECS_PER_DAY).unwrap() + hh * 3600 + mm * 60,
+            kind,
+            break_kind,
+            planned_seconds: match break_kind {
+                BreakKind::Short => 120,
+                BreakKind::Long => 600,
+            },
+            actual_seconds: actual,
+            reason: reason.map(str::to_string),
+        }
+    }
+
+    /// 东八区 2024-01-01 至 01-02 的一段历史，写入顺序与时间顺序不完全一致
+    fn sample() -> Vec<Record> {
+        use BreakKind::{Long, Short};
+        use RecordKind::*;
+        vec![
+            record((MONDAY, 1, 28), Started, Short, 0, None),
+            record((MONDAY, 1, 30), Completed, Short, 118, Some("rested")),
+            record((MONDAY, 2, 15), Postponed, Short, 0, Some("user")),
+            record((MONDAY, 2, 41), Skipped, Short, 12, Some("user")),
+            record((MONDAY, 4, 0), IdleSkipped, Long, 0, Some("idle_active")),
+            record((MONDAY, 3, 10), NaturalBreak, Short, 120, Some("away")),
+            record((MONDAY, 6, 30), Completed, Long, 612, Some("partial")),
+            record((MONDAY, 16, 5), SleepRest, Short, 36_000, Some("sleep")),
+        ]
+    }
+
+    /// 与 `tests/golden/` 下的文件比对；设置 `RESTGAP_UPDATE_GOLDEN=1` 时改为重写该文件
+    fn assert_golden(name: &str, actual: &str) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/golden")
+            .join(name);
+        if std::env::var_os("RESTGAP_UPDATE_GOLDEN").is_some() {
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path).unwrap();
+        assert!(expected == actual, "{name} 与输出不一致：\n{actual}");
+    }
+
+    #[test]
+    fn csv_matches_golden_file() {
+        assert_golden("history.csv", &to_csv(&sample(), UTC8));
+    }
+
+    #[test]
+    fn ics_matches_golden_files() {
+        for (lang, name) in [
+            (Language::Zh, "history.ics"),
+            (Language::En, "history.en.ics"),
+        ] {
+            let ics = to_ics(&sample(), &Texts::new(lang));
+            assert!(ics.lines().all(|line| line.len() <= ICS_LINE_LIMIT));
+            assert!(!ics.replace("\r\n", "").contains('\n'));
+            assert_golden(name, &ics);
+        }
+    }
+
+    #[test]
+    fn escapes_special_characters() {
+        assert_eq!(csv_field("rested"), "rested");
+        assert_eq!(csv_field(r#"a,"b""#), r#""a,""b""""#);
+        assert_eq!(ics_text("a;b,c\\d\ne"), r"a\;b\,c\\d\ne");
+
+        let mut out = String::new();
+        push_folded(&mut out, &"息".repeat(30));
+        assert_eq!(
+            out,
+            format!("{}\r\n {}\r\n", "息".repeat(25), "息".repeat(5))
+        );
+    }
+
+    #[test]
+    fn date_ranges_cover_whole_local_days() {
+        let range = DateRange::parse("2024-01-01", "2024-01-02").unwrap();
+        assert_eq!(
+            range,
+            DateRange {
+                from: MONDAY,
+                to: MONDAY + 1
+            }
+        );
+        assert_eq!(DateRange::last_days(MONDAY + 1, 2), range);
+
+        let (from, to) = range.bounds(UTC8);
+        let secs = |t: SystemTime| t.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+        // 东八区 01-01 00:00 为 UTC 12-31 16:00
+        assert_eq!(
+            secs(from),
+            u64::try_from(MONDAY * SECS_PER_DAY - UTC8).unwrap()
+        );
+        assert_eq!(secs(to) - secs(from), 2 * 86_400);
+
+        assert_eq!(
+            DateRange::parse("2024-01-02", "2024-01-01"),
+            Err(RangeError::Reversed {
+                from: "2024-01-02".to_string(),
+                to: "2024-01-01".to_string(),
+            })
+        );
+        assert_eq!(
+            DateRange::parse("2024-01-01", "yesterday"),
+            Err(RangeError::InvalidDate("yesterday".to_string()))
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out/History.ICS")),
+            Some(ExportFormat::Ics)
+        );
+        assert_eq!(ExportFormat::from_path(Path::new("history.json")), None);
+    }
+}
@@ -0,0 +1,221 @@
+//! 每日目标、连续天数与成就。
+//!
+//! 全部由休息历史计算，不单独保存状态。没有应当休息的休息的日期（周末、请假或整天闲置）
+//! 既不计入也不打断连续天数；今天尚未达标时同样不打断，直到一天结束。
+
+use crate::history::Record;
+use crate::stats::{self, DayStats, Tally};
+
+/// 解锁“百次休息”所需完成的休息次数
+pub const HUNDRED_BREAKS: u32 = 100;
+/// 解锁“一周不跳过”“一周达标”所需的连续天数
+pub const WEEK_DAYS: u32 = 7;
+
+/// 由历史判定的成就
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Achievement {
+    /// 连续 7 个有休息的日期都没有跳过
+    SkipFreeWeek,
+    /// 累计完成 100 次休息
+    HundredBreaks,
+    /// 连续 7 个有休息的日期都达到每日目标
+    GoalWeek,
+}
+
+impl Achievement {
+    /// 全部成就，按菜单中的展示顺序
+    pub const ALL: [Self; 3] = [Self::SkipFreeWeek, Self::HundredBreaks, Self::GoalWeek];
+}
+
+/// 一次休息带来的值得庆祝的变化
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Milestone {
+    /// 当天完成次数刚好达到目标；`streak` 为包含当天的连续达标天数
+    GoalReached { goal: u32, streak: u32 },
+    /// 新解锁的成就
+    Unlocked(Achievement),
+}
+
+/// 截至 `today`（含）连续达到每日目标的天数；目标为 0 时为 0
+pub fn streak(days: &[DayStats], today: i64, goal: u32) -> u32 {
+    if goal == 0 {
+        return 0;
+    }
+    let mut streak = 0;
+    for stats in days.iter().rev().filter(|stats| stats.day <= today) {
+        if stats.tally.due() == 0 {
+            continue;
+        }
+        if stats.tally.taken >= goal {
+            streak += 1;
+        } else if stats.day != today {
+            break;
+        }
+    }
+    streak
+}
+
+/// 已解锁的成就，按 [`Achievement::ALL`] 的顺序
+pub fn achievements(days: &[DayStats], goal: u32) -> Vec<Achievement> {
+    Achievement::ALL
+        .into_iter()
+        .filter(|achievement| match achievement {
+            Achievement::SkipFreeWeek => {
+                longest_run(days, |tally| tally.taken > 0 && tally.skipped == 0) >= WEEK_DAYS
+            }
+            Achievement::HundredBreaks => stats::total(days).taken >= HUNDRED_BREAKS,
+            Achievement::GoalWeek => {
+                goal > 0 && longest_run(days, |tally| tally.taken >= goal) >= WEEK_DAYS
+            }
+        })
+        .collect()
+}
+
+/// 把 `new` 追加到 `before` 之后出现的里程碑；只有完成的休息会带来里程碑
+pub fn milestones(before: &[Record], new: &Record, goal: u32, utc_offset: i64) -> Vec<Milestone> {
+    if !new.kind.is_rest() {
+        return Vec::new();
+    }
+    let mut after = before.to_vec();
+    after.push(new.clone());
+    let days_before = stats::daily(before, utc_offset);
+    let days_after = stats::daily(&after, utc_offset);
+
+    let mut milestones = Vec::new();
+    let today = stats::local_day(new.timestamp, utc_offset);
+    let taken = days_after
+        .iter()
+        .find(|stats| stats.day == today)
+        .map_or(0, |stats| stats.tally.taken);
+    if goal > 0 && taken == goal {
+        milestones.push(Milestone::GoalReached {
+            goal,
+            streak: streak(&days_after, today, goal),
+        });
+    }
+
+    let unlocked = achievements(&days_before, goal);
+    milestones.extend(
+        achievements(&days_after, goal)
+            .into_iter()
+            .filter(|achievement| !unlocked.contains(achievement))
+            .map(Milestone::Unlocked),
+    );
+    milestones
+}
+
+/// 有应当休息的日期中满足条件的最长连续段
+fn longest_run(days: &[DayStats], meets: impl Fn(&Tally) -> bool) -> u32 {
+    let mut longest = 0;
+    let mut run = 0;
+    for stats in days.iter().filter(|stats| stats.tally.due() > 0) {
+        run = if meets(&stats.tally) { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    longest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::RecordKind;
+    use crate::scheduler::BreakKind;
+
+    fn day(day: i64, taken: u32, skipped: u32) -> DayStats {
+        DayStats {
+            day,
+            tally: Tally {
+                taken,
+                skipped,
+                ..Tally::default()
+            },
+        }
+    }
+
+    fn record(day: u64, kind: RecordKind) -> Record {
+        Record {
+            timestamp: day * 86_400 + 36_000,
+            kind,
+            break_kind: BreakKind::Short,
+            planned_seconds: 20,
+            actual_seconds: 20,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn streak_skips_empty_days_and_waits_for_today() {
+        let days = [
+            day(1, 8, 0),
+            day(2, 3, 1),
+            day(3, 8, 0),
+            // 周末没有记录
+            day(6, 9, 2),
+            day(7, 0, 0),
+            day(8, 2, 0),
+        ];
+        assert_eq!(streak(&days, 8, 8), 2);
+        assert_eq!(streak(&days, 7, 8), 2);
+        assert_eq!(streak(&days, 6, 9), 1);
+        assert_eq!(streak(&days, 8, 2), 5);
+        assert_eq!(streak(&days, 8, 0), 0);
+        assert_eq!(streak(&[], 8, 8), 0);
+    }
+
+    #[test]
+    fn achievements_need_seven_active_days() {
+        let mut days: Vec<DayStats> = (0..6).map(|d| day(d, 10, 0)).collect();
+        assert!(achievements(&days, 8).is_empty());
+
+        // 中间隔一个没有记录的日期不打断
+        days.push(day(9, 40, 0));
+        assert_eq!(
+            achievements(&days, 8),
+            [
+                Achievement::SkipFreeWeek,
+                Achievement::HundredBreaks,
+                Achievement::GoalWeek
+            ]
+        );
+        assert_eq!(
+            achievements(&days, 0),
+            [Achievement::SkipFreeWeek, Achievement::HundredBreaks]
+        );
+
+        days[3].tally.skipped = 1;
+        assert_eq!(
+            achievements(&days, 8),
+            [Achievement::HundredBreaks, Achievement::GoalWeek]
+        );
+    }
+
+    #[test]
+    fn milestones_fire_once_when_crossed() {
+        let goal = 2;
+        let mut history = vec![
+            record(1, RecordKind::Completed),
+            record(1, RecordKind::Completed),
+            record(2, RecordKind::Completed),
+        ];
+
+        let second = record(2, RecordKind::NaturalBreak);
+        assert_eq!(
+            milestones(&history, &second, goal, 0),
+            [Milestone::GoalReached { goal, streak: 2 }]
+        );
+        history.push(second);
+
+        let third = record(2, RecordKind::Completed);
+        assert!(milestones(&history, &third, goal, 0).is_empty());
+        assert!(milestones(&history, &record(2, RecordKind::Skipped), goal, 0).is_empty());
+
+        // 只有 5 天有休息，凑不满连续 7 天
+        let history: Vec<Record> = (0..u64::from(HUNDRED_BREAKS) - 1)
+            .map(|i| record(i / 20 * 2, RecordKind::Completed))
+            .collect();
+        assert_eq!(
+            milestones(&history, &record(8, RecordKind::SleepRest), 0, 0),
+            [Milestone::Unlocked(Achievement::HundredBreaks)]
+        );
+    }
+}
@@ -0,0 +1,441 @@
+//! 休息历史记录。
+//!
+//! 每次休息开始、结束、跳过或推迟时追加一条记录到 JSONL 文件（每行一个 JSON 对象），
+//! 默认位于 `$XDG_DATA_HOME/restgap/history.jsonl`。文件只追加不修改，启动时按保留期限
+//! 删除过旧的记录。读取时跳过无法解析的行（例如写入中途退出留下的半行）。
+
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::idle::{BreakCompliance, IdleReason};
+use crate::scheduler::{BreakKind, Event};
+
+/// 记录的事件类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordKind {
+    /// 休息开始（按计划或立即休息）
+    Started,
+    /// 休息按时结束
+    Completed,
+    /// 用户跳过休息
+    Skipped,
+    /// 用户推迟休息
+    Postponed,
+    /// 按闲置策略自动跳过休息
+    IdleSkipped,
+    /// 工作期间离开足够久，视为自然休息
+    NaturalBreak,
+    /// 系统睡眠时长达到休息时长，视为已休息
+    SleepRest,
+}
+
+impl RecordKind {
+    /// 历史文件中的写法，例如 `idle_skipped`
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::Started => "started",
+            Self::Completed => "completed",
+            Self::Skipped => "skipped",
+            Self::Postponed => "postponed",
+            Self::IdleSkipped => "idle_skipped",
+            Self::NaturalBreak => "natural_break",
+            Self::SleepRest => "sleep_rest",
+        }
+    }
+
+    /// 是否确实休息过：按时结束、工作中离开足够久或睡眠达到休息时长
+    pub const fn is_rest(self) -> bool {
+        matches!(self, Self::Completed | Self::NaturalBreak | Self::SleepRest)
+    }
+}
+
+/// 一条历史记录
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Record {
+    /// 事件发生时刻（Unix 秒）
+    pub timestamp: u64,
+    pub kind: RecordKind,
+    pub break_kind: BreakKind,
+    /// 按设置应有的休息时长
+    pub planned_seconds: u64,
+    /// 实际休息（或离开、睡眠）的时长；开始与推迟时为 0
+    pub actual_seconds: u64,
+    /// 原因代码：休息判定（`rested` / `partial` / `active` / `unknown`）、
+    /// 闲置策略（`idle_active` / `idle_share` / `idle_break_length` 等）或 `user` / `away` / `sleep`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl Record {
+    /// 休息开始的记录
+    pub fn started(break_kind: BreakKind, planned: Duration, at: SystemTime) -> Self {
+        Self {
+            timestamp: unix_secs(at),
+            kind: RecordKind::Started,
+            break_kind,
+            planned_seconds: planned.as_secs(),
+            actual_seconds: 0,
+            reason: None,
+        }
+    }
+
+    /// 由调度事件生成记录；与休息无关的事件（暂停、唤醒等）返回 `None`
+    ///
+    /// `planned` 给出指定类型休息按当前设置的时长。
+    pub fn from_event(
+        event: &Event,
+        planned: impl Fn(BreakKind) -> Duration,
+        at: SystemTime,
+    ) -> Option<Self> {
+        let (kind, break_kind, actual, reason) = match *event {
+            Event::BreakCompleted {
+                kind,
+                compliance,
+                elapsed,
+            } => (
+                RecordKind::Completed,
+                kind,
+                elapsed,
+                compliance_code(compliance),
+            ),
+            Event::BreakSkipped { kind, elapsed } => (RecordKind::Skipped, kind, elapsed, "user"),
+            Event::BreakPostponed { kind, .. } => {
+                (RecordKind::Postponed, kind, Duration::ZERO, "user")
+            }
+            Event::IdleSkipped { kind, reason } => {
+                let actual = match reason {
+                    IdleReason::RestedBreakLength { idle, .. } => idle,
+                    _ => Duration::ZERO,
+                };
+                (RecordKind::IdleSkipped, kind, actual, idle_code(reason))
+            }
+            Event::NaturalBreak { kind, idle } => (RecordKind::NaturalBreak, kind, idle, "away"),
+            Event::SleepRest { kind, slept } => (RecordKind::SleepRest, kind, slept, "sleep"),
+            Event::BreakExtended { .. }
+            | Event::Paused { .. }
+            | Event::Resumed
+            | Event::Woke { .. }
+            | Event::Returned { .. } => return None,
+        };
+        Some(Self {
+            timestamp: unix_secs(at),
+            kind,
+            break_kind,
+            planned_seconds: planned(break_kind).as_secs(),
+            actual_seconds: actual.as_secs(),
+            reason: Some(reason.to_string()),
+        })
+    }
+}
+
+const fn compliance_code(compliance: BreakCompliance) -> &'static str {
+    match compliance {
+        BreakCompliance::Rested => "rested",
+        BreakCompliance::Partial => "partial",
+        BreakCompliance::Active => "active",
+        BreakCompliance::Unknown => "unknown",
+    }
+}
+
+const fn idle_code(reason: IdleReason) -> &'static str {
+    match reason {
+        IdleReason::Disabled => "idle_disabled",
+        IdleReason::Unavailable => "idle_unavailable",
+        IdleReason::Active { .. } => "idle_active",
+        IdleReason::IdleShare { .. } => "idle_share",
+        IdleReason::RestedBreakLength { .. } => "idle_break_length",
+    }
+}
+
+fn unix_secs(at: SystemTime) -> u64 {
+    at.duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// 以 JSONL 文件保存的历史记录
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistoryStore {
+    path: PathBuf,
+    retention: Duration,
+}
+
+impl HistoryStore {
+    pub const FILE_NAME: &'static str = "history.jsonl";
+    /// 默认保留一年的记录
+    pub const DEFAULT_RETENTION: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+    pub const fn new(path: PathBuf, retention: Duration) -> Self {
+        Self { path, retention }
+    }
+
+    /// 默认位置的历史记录；无法确定数据目录时返回 `None`
+    pub fn default_location() -> Option<Self> {
+        let dir = data_dir(std::env::var_os("XDG_DATA_HOME"), std::env::var_os("HOME"))?;
+        Some(Self::new(
+            dir.join(Self::FILE_NAME),
+            Self::DEFAULT_RETENTION,
+        ))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 追加一条记录
+    pub fn append(&self, record: &Record) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        // 整行一次写入，避免与其他进程的追加交错
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())
+    }
+
+    /// 按写入顺序读取全部记录；文件不存在时返回空列表
+    pub fn read(&self) -> io::Result<Vec<Record>> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(text) => Ok(parse_lines(&text)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// 按写入顺序读取时刻位于 `[from, to)` 内的记录
+    pub fn read_range(&self, from: SystemTime, to: SystemTime) -> io::Result<Vec<Record>> {
+        let range = unix_secs(from)..unix_secs(to);
+        let mut records = self.read()?;
+        records.retain(|record| range.contains(&record.timestamp));
+        Ok(records)
+    }
+
+    /// 保留期限的起点（Unix 秒），更早的记录会被删除
+    fn cutoff(&self, now: SystemTime) -> u64 {
+        unix_secs(now).saturating_sub(self.retention.as_secs())
+    }
+
+    /// 按与 [`Self::prune`] 相同的期限去掉内存中过期的记录
+    pub fn retain_recent(&self, records: &mut Vec<Record>, now: SystemTime) {
+        let cutoff = self.cutoff(now);
+        records.retain(|record| record.timestamp >= cutoff);
+    }
+
+    /// 删除早于保留期限的记录，返回删除的条数
+    pub fn prune(&self, now: SystemTime) -> io::Result<usize> {
+        let cutoff = self.cutoff(now);
+        let records = self.read()?;
+        let kept: Vec<&Record> = records.iter().filter(|r| r.timestamp >= cutoff).collect();
+        let removed = records.len() - kept.len();
+        if removed == 0 {
+            return Ok(0);
+        }
+
+        let mut text = String::new();
+        for record in kept {
+            text.push_str(&serde_json::to_string(record)?);
+            text.push('\n');
+        }
+        // 先写临时文件再重命名，避免中途退出时丢失历史
+        let tmp = self.path.with_extension("jsonl.tmp");
+        std::fs::write(&tmp, text)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(removed)
+    }
+}
+
+/// 解析 JSONL 文本，跳过空行与无法解析的行
+pub fn parse_lines(text: &str) -> Vec<Record> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// 按 XDG 规范确定数据目录：`$XDG_DATA_HOME/restgap`，未设置（或不是绝对路径）时使用
+/// `$HOME/.local/share/restgap`
+fn data_dir(xdg_data_home: Option<OsString>, home: Option<OsString>) -> Option<PathBuf> {
+    let base = xdg_data_home
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| {
+            home.map(PathBuf::from)
+                .filter(|dir| dir.is_absolute())
+                .map(|home| home.join(".local/share"))
+        })?;
+    Some(base.join("restgap"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    fn at(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn planned(kind: BreakKind) -> Duration {
+        match kind {
+            BreakKind::Short => Duration::from_secs(120),
+            BreakKind::Long => Duration::from_secs(600),
+        }
+    }
+
+    fn temp_store(name: &str) -> HistoryStore {
+        let dir =
+            std::env::temp_dir().join(format!("restgap-history-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        HistoryStore::new(
+            dir.join(HistoryStore::FILE_NAME),
+            Duration::from_secs(30 * DAY),
+        )
+    }
+
+    #[test]
+    fn events_map_to_typed_records() {
+        let completed = Event::BreakCompleted {
+            kind: BreakKind::Long,
+            compliance: BreakCompliance::Partial,
+            elapsed: Duration::from_secs(610),
+        };
+        assert_eq!(
+            Record::from_event(&completed, planned, at(1_000)),
+            Some(Record {
+                timestamp: 1_000,
+                kind: RecordKind::Completed,
+                break_kind: BreakKind::Long,
+                planned_seconds: 600,
+                actual_seconds: 610,
+                reason: Some("partial".to_string()),
+            })
+        );
+
+        let idle = Event::IdleSkipped {
+            kind: BreakKind::Short,
+            reason: IdleReason::Active {
+                active: Duration::from_secs(3),
+                tolerance: Duration::from_secs(8),
+            },
+        };
+        let record = Record::from_event(&idle, planned, at(2_000)).unwrap();
+        assert_eq!(record.kind, RecordKind::IdleSkipped);
+        assert_eq!(record.reason.as_deref(), Some("idle_active"));
+        assert_eq!(record.actual_seconds, 0);
+
+        // 自然休息记录实际离开的时长，而不是计划时长
+        let away = Event::NaturalBreak {
+            kind: BreakKind::Short,
+            idle: Duration::from_secs(437),
+        };
+        let record = Record::from_event(&away, planned, at(3_000)).unwrap();
+        assert_eq!(record.kind, RecordKind::NaturalBreak);
+        assert_eq!((record.planned_seconds, record.actual_seconds), (120, 437));
+
+        assert_eq!(Record::from_event(&Event::Resumed, planned, at(0)), None);
+        for kind in [
+            RecordKind::Started,
+            RecordKind::Completed,
+            RecordKind::Skipped,
+            RecordKind::Postponed,
+            RecordKind::IdleSkipped,
+            RecordKind::NaturalBreak,
+            RecordKind::SleepRest,
+        ] {
+            assert_eq!(
+                serde_json::to_string(&kind).unwrap(),
+                format!("\"{}\"", kind.code())
+            );
+        }
+        assert_eq!(
+            serde_json::to_string(&Record::started(
+                BreakKind::Short,
+                planned(BreakKind::Short),
+                at(5)
+            ))
+            .unwrap(),
+            r#"{"timestamp":5,"kind":"started","break_kind":"short","planned_seconds":120,"actual_seconds":0}"#
+        );
+    }
+
+    #[test]
+    fn store_appends_and_reads_back_skipping_torn_lines() {
+        let store = temp_store("append");
+        assert!(store.read().unwrap().is_empty());
+
+        let skipped = Event::BreakSkipped {
+            kind: BreakKind::Short,
+            elapsed: Duration::from_secs(12),
+        };
+        let first = Record::started(BreakKind::Short, planned(BreakKind::Short), at(100));
+        let second = Record::from_event(&skipped, planned, at(112)).unwrap();
+        store.append(&first).unwrap();
+        store.append(&second).unwrap();
+
+        // 模拟写入中途退出留下的半行
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(store.path())
+            .unwrap();
+        file.write_all(br#"{"timestamp":120,"kind":"comp"#).unwrap();
+
+        assert_eq!(store.read().unwrap(), [first.clone(), second.clone()]);
+        assert_eq!(store.read_range(at(100), at(112)).unwrap(), [first]);
+        assert_eq!(store.read_range(at(101), at(1_000)).unwrap(), [second]);
+        let _ = std::fs::remove_dir_all(store.path().parent().unwrap());
+    }
+
+    #[test]
+    fn prune_drops_records_past_retention() {
+        let store = temp_store("prune");
+        let now = 100 * DAY;
+        for day in [60, 69, 70, 99] {
+            store
+                .append(&Record::started(
+                    BreakKind::Short,
+                    planned(BreakKind::Short),
+                    at(day * DAY),
+                ))
+                .unwrap();
+        }
+        assert_eq!(store.prune(at(now)).unwrap(), 2);
+        let days: Vec<u64> = store
+            .read()
+            .unwrap()
+            .iter()
+            .map(|r| r.timestamp / DAY)
+            .collect();
+        assert_eq!(days, [70, 99]);
+        assert_eq!(store.prune(at(now)).unwrap(), 0);
+
+        let mut records: Vec<Record> = [69, 70, 99]
+            .into_iter()
+            .map(|day| Record::started(BreakKind::Short, planned(BreakKind::Short), at(day * DAY)))
+            .collect();
+        store.retain_recent(&mut records, at(now));
+        assert_eq!(records.len(), 2);
+        let _ = std::fs::remove_dir_all(store.path().parent().unwrap());
+    }
+
+    #[test]
+    fn data_dir_follows_xdg() {
+        assert_eq!(
+            data_dir(Some("/xdg".into()), Some("/home/me".into())),
+            Some(PathBuf::from("/xdg/restgap"))
+        );
+        assert_eq!(
+            data_dir(Some("relative".into()), Some("/home/me".into())),
+            Some(PathBuf::from("/home/me/.local/share/restgap"))
+        );
+        assert_eq!(data_dir(None, None), None);
+    }
+}
@@ -2,8 +2,15 @@
 //!
 //! Supports English and Simplified Chinese with an `Auto` mode that follows the OS language.
 
+use std::fmt::Write;
+
 use serde::{Deserialize, Serialize};
 
+#[cfg(target_os = "macos")]
+use crate::goals::{Achievement, Milestone};
+use crate::history::{Record, RecordKind};
+use crate::scheduler::BreakKind;
+
 /// Supported UI languages.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Language {
@@ -36,17 +43,257 @@ impl LanguagePreference {
     }
 }
 
-#[cfg(target_os = "macos")]
 pub struct Texts {
     lang: Language,
 }
 
-#[cfg(target_os = "macos")]
+/// Texts shared by the menu bar app and the command line (history export, report).
 impl Texts {
     pub const fn new(lang: Language) -> Self {
         Self { lang }
     }
 
+    /// Language suffix of the iCalendar `PRODID`.
+    pub const fn ics_language_tag(&self) -> &'static str {
+        match self.lang {
+            Language::En => "EN",
+            Language::Zh => "ZH",
+        }
+    }
+
+    /// iCalendar event title for a break.
+    pub const fn ics_summary(&self, kind: BreakKind) -> &'static str {
+        match (self.lang, kind) {
+            (Language::En, BreakKind::Short) => "Short break",
+            (Language::En, BreakKind::Long) => "Long break",
+            (Language::Zh, BreakKind::Short) => "短休息",
+            (Language::Zh, BreakKind::Long) => "长休息",
+        }
+    }
+
+    /// iCalendar event description: how the break happened and how long it lasted.
+    pub fn ics_description(&self, record: &Record) -> String {
+        let (planned, actual) = (record.planned_seconds, record.actual_seconds);
+        let mut text = match (self.lang, record.kind) {
+            (Language::En, RecordKind::NaturalBreak) => {
+                format!("Stepped away during work, planned {planned}s, actual {actual}s")
+            }
+            (Language::En, RecordKind::SleepRest) => {
+                format!("System sleep, planned {planned}s, actual {actual}s")
+            }
+            (Language::En, _) => format!("Finished on time, planned {planned}s, actual {actual}s"),
+            (Language::Zh, RecordKind::NaturalBreak) => {
+                format!("工作中离开，计划 {planned} 秒，实际 {actual} 秒")
+            }
+            (Language::Zh, RecordKind::SleepRest) => {
+                format!("系统睡眠，计划 {planned} 秒，实际 {actual} 秒")
+            }
+            (Language::Zh, _) => format!("按时结束，计划 {planned} 秒，实际 {actual} 秒"),
+        };
+        if let Some(reason) = &record.reason {
+            let _ = match self.lang {
+                Language::En => write!(text, " ({reason})"),
+                Language::Zh => write!(text, "（{reason}）"),
+            };
+        }
+        text
+    }
+
+    /// `lang` attribute of the report page.
+    pub const fn report_html_lang(&self) -> &'static str {
+        match self.lang {
+            Language::En => "en",
+            Language::Zh => "zh-CN",
+        }
+    }
+
+    pub const fn report_title(&self) -> &'static str {
+        match self.lang {
+            Language::En => "RestGap · Break Report",
+            Language::Zh => "息间 · 休息报告",
+        }
+    }
+
+    pub fn report_subtitle(&self, date: &str) -> String {
+        match self.lang {
+            Language::En => {
+                format!("Generated on {date} · Data comes only from this Mac's break history")
+            }
+            Language::Zh => format!("生成于 {date} · 数据仅来自本机休息历史"),
+        }
+    }
+
+    pub const fn report_section_overview(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Overview",
+            Language::Zh => "总览",
+        }
+    }
+
+    pub const fn report_section_heatmap(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Breaks per day",
+            Language::Zh => "每日休息次数",
+        }
+    }
+
+    pub const fn report_section_trend(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Weekly compliance",
+            Language::Zh => "每周遵守率",
+        }
+    }
+
+    pub const fn report_section_skip_hours(&self) -> &'static str {
+        match self.lang {
+            Language::En => "When breaks get skipped",
+            Language::Zh => "跳过休息的时段",
+        }
+    }
+
+    pub const fn report_section_streaks(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Longest skip-free streaks",
+            Language::Zh => "最长无跳过连续天数",
+        }
+    }
+
+    pub const fn report_footer(&self) -> &'static str {
+        match self.lang {
+            Language::En => {
+                "Compliance = taken ÷ (taken + skipped); breaks skipped automatically while idle are not counted"
+            }
+            Language::Zh => "遵守率 = 已休息 ÷（已休息 + 跳过）；闲置时自动跳过的休息不计入",
+        }
+    }
+
+    pub const fn report_taken_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Taken",
+            Language::Zh => "已休息",
+        }
+    }
+
+    pub const fn report_skipped_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Skipped",
+            Language::Zh => "跳过",
+        }
+    }
+
+    pub const fn report_compliance_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Compliance",
+            Language::Zh => "遵守率",
+        }
+    }
+
+    pub const fn report_rest_time_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Total rest",
+            Language::Zh => "休息总时长",
+        }
+    }
+
+    pub const fn report_longest_work_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Longest work stretch",
+            Language::Zh => "最长连续工作",
+        }
+    }
+
+    /// Number of breaks in a summary card, e.g. "12 times".
+    pub fn report_times(&self, count: u32) -> String {
+        match (self.lang, count) {
+            (Language::En, 1) => "1 time".to_string(),
+            (Language::En, count) => format!("{count} times"),
+            (Language::Zh, count) => format!("{count} 次"),
+        }
+    }
+
+    /// Duration in hours and minutes, e.g. "2 h 5 min".
+    pub fn report_duration(&self, secs: u64) -> String {
+        let minutes = secs / 60;
+        match (self.lang, minutes / 60, minutes % 60) {
+            (Language::En, 0, minutes) => format!("{minutes} min"),
+            (Language::En, hours, 0) => format!("{hours} h"),
+            (Language::En, hours, minutes) => format!("{hours} h {minutes} min"),
+            (Language::Zh, 0, minutes) => format!("{minutes} 分"),
+            (Language::Zh, hours, 0) => format!("{hours} 小时"),
+            (Language::Zh, hours, minutes) => format!("{hours} 小时 {minutes} 分"),
+        }
+    }
+
+    /// Heatmap row labels for Monday, Wednesday and Friday.
+    pub const fn report_weekday_labels(&self) -> [&'static str; 3] {
+        match self.lang {
+            Language::En => ["Mon", "Wed", "Fri"],
+            Language::Zh => ["一", "三", "五"],
+        }
+    }
+
+    /// Heatmap column label for the first week of a month (1–12).
+    pub fn report_month_label(&self, month: u32) -> String {
+        const MONTHS: [&str; 12] = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+        match self.lang {
+            Language::En => usize::try_from(month)
+                .ok()
+                .and_then(|month| MONTHS.get(month.wrapping_sub(1)))
+                .map_or_else(|| month.to_string(), |name| (*name).to_string()),
+            Language::Zh => format!("{month}月"),
+        }
+    }
+
+    /// Tooltip of a heatmap cell.
+    pub fn report_day_tooltip(&self, date: &str, taken: u32, skipped: u32) -> String {
+        match self.lang {
+            Language::En => format!("{date}: {taken} taken, {skipped} skipped"),
+            Language::Zh => format!("{date}：休息 {taken} 次，跳过 {skipped} 次"),
+        }
+    }
+
+    /// Tooltip of a point on the weekly compliance chart.
+    pub fn report_week_tooltip(
+        &self,
+        week_start: &str,
+        percent: u8,
+        taken: u32,
+        due: u32,
+    ) -> String {
+        match self.lang {
+            Language::En => format!("Week of {week_start}: {percent}% ({taken}/{due})"),
+            Language::Zh => format!("{week_start} 起的一周：{percent}%（{taken}/{due}）"),
+        }
+    }
+
+    /// Tooltip of a bar on the skipped-by-hour chart.
+    pub fn report_hour_tooltip(&self, hour: i64, skips: u32) -> String {
+        match self.lang {
+            Language::En => format!("{hour}:00–{hour}:59: {skips} skipped"),
+            Language::Zh => format!("{hour}:00–{hour}:59：跳过 {skips} 次"),
+        }
+    }
+
+    pub fn report_streak_days(&self, days: i64) -> String {
+        match (self.lang, days) {
+            (Language::En, 1) => "1 day".to_string(),
+            (Language::En, days) => format!("{days} days"),
+            (Language::Zh, days) => format!("{days} 天"),
+        }
+    }
+
+    pub const fn report_no_data(&self) -> &'static str {
+        match self.lang {
+            Language::En => "No data yet",
+            Language::Zh => "暂无数据",
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Texts {
     pub const fn language(&self) -> Language {
         self.lang
     }
@@ -126,8 +373,9 @@ impl Texts {
         }
     }
 
-    pub fn header_title(&self, interval_minutes: u64, break_seconds: u64) -> String {
-        match self.lang {
+    /// Menu header; `streak` is the number of consecutive days meeting the daily goal.
+    pub fn header_title(&self, interval_minutes: u64, break_seconds: u64, streak: u32) -> String {
+        let title = match self.lang {
             Language::En => format!(
                 "{} · Break every {} min for {} sec",
                 self.app_name_short(),
@@ -140,6 +388,11 @@ impl Texts {
                 interval_minutes,
                 break_seconds
             ),
+        };
+        match (self.lang, streak) {
+            (_, 0) => title,
+            (Language::En, n) => format!("{title} · 🔥 {n}-day streak"),
+            (Language::Zh, n) => format!("{title} · 🔥 连续 {n} 天"),
         }
     }
 
@@ -150,6 +403,175 @@ impl Texts {
         }
     }
 
+    /// Breaks taken today out of those due, plus this week's compliance if any.
+    pub fn today_breaks_title(&self, taken: u32, due: u32, week_percent: Option<u8>) -> String {
+        match (self.lang, week_percent) {
+            (Language::En, None) => format!("Today: {taken}/{due} breaks"),
+            (Language::En, Some(p)) => format!("Today: {taken}/{due} breaks · Week {p}%"),
+            (Language::Zh, None) => format!("今日：已休息 {taken}/{due} 次"),
+            (Language::Zh, Some(p)) => format!("今日：已休息 {taken}/{due} 次 · 本周 {p}%"),
+        }
+    }
+
+    pub fn next_long_break_title(&self, hm: &str, approx: &str) -> String {
+        match self.lang {
+            Language::En => format!("Next: long break {hm} ({approx})"),
+            Language::Zh => format!("下次：长休息 {hm}（{approx}）"),
+        }
+    }
+
+    pub fn break_imminent_title(&self, approx: &str) -> String {
+        match self.lang {
+            Language::En => format!("Break starts in {approx}"),
+            Language::Zh => format!("即将休息（{approx}）"),
+        }
+    }
+
+    pub const fn off_hours_title(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Outside working hours",
+            Language::Zh => "不在工作时段",
+        }
+    }
+
+    pub fn paused_until_title(&self, day: &str, hm: &str) -> String {
+        match self.lang {
+            Language::En => format!("Paused until {day} {hm}"),
+            Language::Zh => format!("暂停至 {day} {hm}"),
+        }
+    }
+
+    pub const fn weekday_short(&self, day: usize) -> &'static str {
+        const EN: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+        const ZH: [&str; 7] = ["周一", "周二", "周三", "周四", "周五", "周六", "周日"];
+        match self.lang {
+            Language::En => EN[day % 7],
+            Language::Zh => ZH[day % 7],
+        }
+    }
+
+    pub const fn menu_start_break_now(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Start break now",
+            Language::Zh => "立即开始休息",
+        }
+    }
+
+    pub const fn menu_pause(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Pause reminders",
+            Language::Zh => "暂停提醒",
+        }
+    }
+
+    pub const fn menu_profile(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Profile",
+            Language::Zh => "配置方案",
+        }
+    }
+
+    pub const fn menu_resume(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Resume reminders",
+            Language::Zh => "恢复提醒",
+        }
+    }
+
+    pub fn pause_for_minutes(&self, minutes: u64) -> String {
+        match (self.lang, minutes) {
+            (Language::En, 60) => "For 1 hour".to_string(),
+            (Language::En, m) if m % 60 == 0 => format!("For {} hours", m / 60),
+            (Language::En, m) => format!("For {m} minutes"),
+            (Language::Zh, m) if m % 60 == 0 => format!("{} 小时", m / 60),
+            (Language::Zh, m) => format!("{m} 分钟"),
+        }
+    }
+
+    pub const fn pause_until_tomorrow(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Until tomorrow",
+            Language::Zh => "直到明天",
+        }
+    }
+
+    pub const fn pause_indefinitely(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Until I resume",
+            Language::Zh => "直到手动恢复",
+        }
+    }
+
+    pub const fn awaiting_return_title(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Timer starts when you're back",
+            Language::Zh => "等你回来再开始计时",
+        }
+    }
+
+    pub const fn welcome_back_title(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Welcome back",
+            Language::Zh => "欢迎回来",
+        }
+    }
+
+    pub const fn paused_title(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Paused",
+            Language::Zh => "已暂停",
+        }
+    }
+
+    pub fn idle_skipped_title(&self, reason: &str) -> String {
+        match self.lang {
+            Language::En => format!("Last break skipped: {reason}"),
+            Language::Zh => format!("上次休息已跳过：{reason}"),
+        }
+    }
+
+    pub const fn idle_reason_disabled(&self) -> &'static str {
+        match self.lang {
+            Language::En => "idle skipping off",
+            Language::Zh => "闲置跳过已关闭",
+        }
+    }
+
+    pub const fn idle_reason_unavailable(&self) -> &'static str {
+        match self.lang {
+            Language::En => "idle time unavailable",
+            Language::Zh => "无法获取空闲时长",
+        }
+    }
+
+    pub fn idle_reason_active(&self, active_secs: u64, tolerance_secs: u64) -> String {
+        match self.lang {
+            Language::En => format!("active only {active_secs}s (≤ {tolerance_secs}s)"),
+            Language::Zh => format!("仅活跃 {active_secs} 秒（≤ {tolerance_secs} 秒）"),
+        }
+    }
+
+    pub fn idle_reason_share(&self, percent: u8, threshold: u8) -> String {
+        match self.lang {
+            Language::En => format!("idle {percent}% of cycle (≥ {threshold}%)"),
+            Language::Zh => format!("空闲占本轮 {percent}%（≥ {threshold}%）"),
+        }
+    }
+
+    pub fn idle_reason_rested(&self, idle: &str, break_len: &str) -> String {
+        match self.lang {
+            Language::En => format!("away {idle} (≥ break {break_len})"),
+            Language::Zh => format!("已离开 {idle}（≥ 休息 {break_len}）"),
+        }
+    }
+
+    pub fn postpone_title(&self, minutes: u64, left: u32) -> String {
+        match self.lang {
+            Language::En => format!("Postpone {minutes} min ({left} left)"),
+            Language::Zh => format!("推迟 {minutes} 分钟（剩余 {left} 次）"),
+        }
+    }
+
     pub fn remaining_title_breaking(&self, approx: &str, end_hm: &str) -> String {
         match self.lang {
             Language::En => format!("Break remaining: {approx} (until {end_hm})"),
@@ -196,6 +618,170 @@ impl Texts {
         }
     }
 
+    #[cfg(target_os = "macos")]
+    pub const fn settings_long_break_every_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Long break every N breaks:",
+            Language::Zh => "每 N 次休息一次长休息：",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_long_break_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Long break for N seconds:",
+            Language::Zh => "长休息 N 秒：",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_pre_break_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Warn N seconds before a break:",
+            Language::Zh => "休息前 N 秒预告：",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_postpone_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Postpone by N minutes:",
+            Language::Zh => "每次推迟 N 分钟：",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_max_postpones_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Postpones per cycle:",
+            Language::Zh => "每轮最多推迟次数：",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_daily_goal_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Daily break goal:",
+            Language::Zh => "每日休息目标次数：",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_work_hours_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Only remind during working hours:",
+            Language::Zh => "仅在工作时段提醒：",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_work_hours_hint(&self) -> &'static str {
+        match self.lang {
+            Language::En => "e.g. Mon-Fri 09:00-12:00 13:00-18:30; Sat 10:00-12:00",
+            Language::Zh => "例如 Mon-Fri 09:00-12:00 13:00-18:30; Sat 10:00-12:00",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_natural_break_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Count time away as a break:",
+            Language::Zh => "离开电脑视为休息：",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_natural_break_hint(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Idle for a full break length restarts the work interval",
+            Language::Zh => "工作中空闲达到休息时长时重新开始本轮计时",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_await_return_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Wait for me to return after a break:",
+            Language::Zh => "休息后等我回来再计时：",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_await_return_hint(&self) -> &'static str {
+        match self.lang {
+            Language::En => "The next interval starts on keyboard or mouse input",
+            Language::Zh => "检测到键盘或鼠标输入后才开始下一轮",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_extend_breaks_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Extend breaks I didn't rest during:",
+            Language::Zh => "未真正休息时延长休息：",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_active_time_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Count only active time:",
+            Language::Zh => "只计算活跃时间：",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_active_threshold_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Ignore idle longer than:",
+            Language::Zh => "不计入超过此时长的空闲：",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_idle_policy_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "When I was away all cycle:",
+            Language::Zh => "整轮未使用电脑时：",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn idle_policy_option(&self, kind: usize) -> &'static str {
+        const EN: [&str; 4] = [
+            "Skip if active time ≤ tolerance",
+            "Never skip",
+            "Skip if idle ≥ share of cycle",
+            "Count idle ≥ break length as the break",
+        ];
+        const ZH: [&str; 4] = [
+            "活跃时长不超过容差则跳过",
+            "从不跳过",
+            "空闲占比达到阈值则跳过",
+            "空闲达到休息时长即视为已休息",
+        ];
+        match self.lang {
+            Language::En => EN[kind % 4],
+            Language::Zh => ZH[kind % 4],
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_idle_tolerance_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Active tolerance:",
+            Language::Zh => "活跃容差：",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_idle_percent_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Idle share threshold:",
+            Language::Zh => "空闲占比阈值：",
+        }
+    }
+
     #[cfg(target_os = "macos")]
     pub const fn settings_skip_break_label(&self) -> &'static str {
         match self.lang {
@@ -236,6 +822,209 @@ impl Texts {
         }
     }
 
+    #[cfg(target_os = "macos")]
+    pub const fn settings_profile_label(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Profile name:",
+            Language::Zh => "方案名称：",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_profile_hint(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Saving stores the timings above under this name",
+            Language::Zh => "保存时以此名称记录上方的时长",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_import_button(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Import…",
+            Language::Zh => "导入…",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_export_button(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Export…",
+            Language::Zh => "导出…",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn import_failed_title(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Could not import settings",
+            Language::Zh => "无法导入配置",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn export_failed_title(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Could not export settings",
+            Language::Zh => "无法导出配置",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub fn menu_achievements(&self, unlocked: usize, total: usize) -> String {
+        match self.lang {
+            Language::En => format!("Achievements ({unlocked}/{total})"),
+            Language::Zh => format!("成就（{unlocked}/{total}）"),
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn achievement_name(&self, achievement: Achievement) -> &'static str {
+        match (self.lang, achievement) {
+            (Language::En, Achievement::SkipFreeWeek) => "A week without skipping",
+            (Language::En, Achievement::HundredBreaks) => "100 breaks",
+            (Language::En, Achievement::GoalWeek) => "A week on target",
+            (Language::Zh, Achievement::SkipFreeWeek) => "一周不跳过",
+            (Language::Zh, Achievement::HundredBreaks) => "百次休息",
+            (Language::Zh, Achievement::GoalWeek) => "一周达标",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn achievement_hint(&self, achievement: Achievement) -> &'static str {
+        match (self.lang, achievement) {
+            (Language::En, Achievement::SkipFreeWeek) => "7 days in a row without skipping a break",
+            (Language::En, Achievement::HundredBreaks) => "Completed 100 breaks",
+            (Language::En, Achievement::GoalWeek) => "Met the daily goal 7 days in a row",
+            (Language::Zh, Achievement::SkipFreeWeek) => "连续 7 天没有跳过休息",
+            (Language::Zh, Achievement::HundredBreaks) => "累计完成 100 次休息",
+            (Language::Zh, Achievement::GoalWeek) => "连续 7 天达到每日目标",
+        }
+    }
+
+    /// Headline shown on the countdown screen when a break hits a milestone.
+    #[cfg(target_os = "macos")]
+    pub const fn milestone_title(&self, milestone: Milestone) -> &'static str {
+        match (self.lang, milestone) {
+            (Language::En, Milestone::GoalReached { .. }) => "Daily goal reached!",
+            (Language::En, Milestone::Unlocked(_)) => "Achievement unlocked!",
+            (Language::Zh, Milestone::GoalReached { .. }) => "今日目标达成！",
+            (Language::Zh, Milestone::Unlocked(_)) => "解锁新成就！",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub fn milestone_hint(&self, milestone: Milestone) -> String {
+        match (self.lang, milestone) {
+            (Language::En, Milestone::GoalReached { goal, streak }) => {
+                format!("{goal} breaks today · {streak}-day streak")
+            }
+            (Language::Zh, Milestone::GoalReached { goal, streak }) => {
+                format!("今天已完成 {goal} 次休息 · 连续达标 {streak} 天")
+            }
+            (Language::En, Milestone::Unlocked(achievement)) => format!(
+                "{}: {}",
+                self.achievement_name(achievement),
+                self.achievement_hint(achievement)
+            ),
+            (Language::Zh, Milestone::Unlocked(achievement)) => format!(
+                "{}：{}",
+                self.achievement_name(achievement),
+                self.achievement_hint(achievement)
+            ),
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn menu_export_history(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Export Break History…",
+            Language::Zh => "导出休息历史…",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn export_history_title(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Export break history",
+            Language::Zh => "导出休息历史",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn export_history_message(&self) -> &'static str {
+        match self.lang {
+            Language::En => {
+                "Enter the first and last day (YYYY-MM-DD) and choose a format: CSV for a \
+                 spreadsheet, iCalendar to import into a calendar."
+            }
+            Language::Zh => {
+                "填写开始和结束日期（YYYY-MM-DD）并选择格式：CSV 可用表格打开，iCalendar 可导入日历。"
+            }
+        }
+    }
+
+    /// Entry of the export format menu.
+    #[cfg(target_os = "macos")]
+    pub const fn export_format_name(&self, format: crate::export::ExportFormat) -> &'static str {
+        use crate::export::ExportFormat;
+
+        match (self.lang, format) {
+            (Language::En, ExportFormat::Csv) => "CSV spreadsheet (.csv)",
+            (Language::En, ExportFormat::Ics) => "iCalendar (.ics)",
+            (Language::Zh, ExportFormat::Csv) => "CSV 表格（.csv）",
+            (Language::Zh, ExportFormat::Ics) => "iCalendar 日历（.ics）",
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn export_history_failed_title(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Could not export break history",
+            Language::Zh => "无法导出休息历史",
+        }
+    }
+
+    /// Why a settings value was rejected, shown next to the offending field.
+    #[cfg(target_os = "macos")]
+    pub fn rejected_reason(&self, reason: &crate::config::RejectReason) -> String {
+        use crate::config::RejectReason;
+
+        match (self.lang, reason) {
+            (Language::En, RejectReason::OutOfRange { min, max }) => {
+                format!("Must be between {min} and {max}")
+            }
+            (Language::Zh, RejectReason::OutOfRange { min, max }) => {
+                format!("应在 {min} 到 {max} 之间")
+            }
+            (_, RejectReason::Invalid(msg)) => msg.clone(),
+            (Language::En, RejectReason::UnknownField) => "Unknown setting".to_string(),
+            (Language::Zh, RejectReason::UnknownField) => "无法识别的配置项".to_string(),
+            (Language::En, RejectReason::NewerSchema) => "Exported by a newer RestGap".to_string(),
+            (Language::Zh, RejectReason::NewerSchema) => "由更新版本的 RestGap 导出".to_string(),
+        }
+    }
+
+    /// One line describing a value rejected on import.
+    #[cfg(target_os = "macos")]
+    pub fn import_rejected_line(&self, rejected: &crate::config::Rejected) -> String {
+        let reason = self.rejected_reason(&rejected.reason);
+        let field = &rejected.field;
+        match (self.lang, &rejected.reason) {
+            (_, crate::config::RejectReason::UnknownField) => format!("{field}: {reason}"),
+            (Language::En, _) => format!("{field} = {}: {reason}", rejected.value),
+            (Language::Zh, _) => format!("{field} = {}：{reason}", rejected.value),
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub const fn settings_not_a_number(&self) -> &'static str {
+        match self.lang {
+            Language::En => "Enter a whole number",
+            Language::Zh => "请输入整数",
+        }
+    }
+
     #[cfg(target_os = "macos")]
     pub const fn ok_button(&self) -> &'static str {
         match self.lang {
@@ -273,6 +1062,13 @@ impl Texts {
         }
     }
 
+    pub fn long_countdown_title(&self) -> String {
+        match self.lang {
+            Language::En => "Long Break".to_string(),
+            Language::Zh => "长休息".to_string(),
+        }
+    }
+
     pub const fn countdown_hint(&self) -> &'static str {
         match self.lang {
             Language::En | Language::Zh => "",
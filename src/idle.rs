@@ -1,29 +1,157 @@
 //! 低功耗闲置跳过判定。
 //!
 //! 仅在工作阶段结束时查询一次系统空闲时长，不做持续轮询。
+//! 判定规则由 [`IdlePolicy`] 配置，每次判定都附带可展示的原因。
 
 use std::time::Duration;
 
-const MAX_ALLOWED_ACTIVE_TIME: Duration = Duration::from_secs(8);
+/// 工作周期结束时的闲置跳过策略
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdlePolicy {
+    /// 从不因闲置跳过休息
+    Disabled,
+    /// 整轮的活跃时间不超过该容差时跳过
+    Tolerance(Duration),
+    /// 空闲时长达到整轮的该百分比时跳过
+    Percent(u8),
+    /// 周期结束时空闲时长已达休息时长，视为已完成这次休息
+    BreakLength,
+}
 
-pub fn should_skip_break(cycle_elapsed: Duration) -> bool {
-    let Some(idle_duration) = current_idle_duration() else {
-        return false;
-    };
-    should_skip_break_with_idle(cycle_elapsed, idle_duration)
+impl IdlePolicy {
+    /// 默认容差：整轮活跃不超过 8 秒
+    pub const DEFAULT_TOLERANCE: Duration = Duration::from_secs(8);
+
+    /// 根据本轮时长与当前空闲时长做出判定
+    pub fn decide(
+        self,
+        cycle_elapsed: Duration,
+        idle_duration: Option<Duration>,
+        break_duration: Duration,
+    ) -> IdleDecision {
+        let Some(idle) = idle_duration else {
+            return IdleDecision::Keep(IdleReason::Unavailable);
+        };
+        match self {
+            Self::Disabled => IdleDecision::Keep(IdleReason::Disabled),
+            Self::Tolerance(tolerance) => {
+                let active = cycle_elapsed.saturating_sub(idle);
+                let reason = IdleReason::Active { active, tolerance };
+                if cycle_elapsed > tolerance && active <= tolerance {
+                    IdleDecision::Skip(reason)
+                } else {
+                    IdleDecision::Keep(reason)
+                }
+            }
+            Self::Percent(threshold) => {
+                let idle = idle.min(cycle_elapsed);
+                let percent = if cycle_elapsed.is_zero() {
+                    0
+                } else {
+                    idle.as_millis() * 100 / cycle_elapsed.as_millis()
+                };
+                let reason = IdleReason::IdleShare {
+                    percent: u8::try_from(percent).unwrap_or(100),
+                    threshold,
+                };
+                if !cycle_elapsed.is_zero() && percent >= u128::from(threshold) {
+                    IdleDecision::Skip(reason)
+                } else {
+                    IdleDecision::Keep(reason)
+                }
+            }
+            Self::BreakLength => {
+                let reason = IdleReason::RestedBreakLength {
+                    idle,
+                    break_duration,
+                };
+                if idle >= break_duration {
+                    IdleDecision::Skip(reason)
+                } else {
+                    IdleDecision::Keep(reason)
+                }
+            }
+        }
+    }
 }
 
-fn should_skip_break_with_idle(cycle_elapsed: Duration, idle_duration: Duration) -> bool {
-    if cycle_elapsed <= MAX_ALLOWED_ACTIVE_TIME {
-        return false;
+impl Default for IdlePolicy {
+    fn default() -> Self {
+        Self::Tolerance(Self::DEFAULT_TOLERANCE)
     }
+}
+
+/// 闲置判定结果
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdleDecision {
+    /// 跳过本次休息
+    Skip(IdleReason),
+    /// 照常休息
+    Keep(IdleReason),
+}
+
+/// 闲置判定的原因，供菜单与日志展示
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdleReason {
+    /// 策略已关闭
+    Disabled,
+    /// 无法获取系统空闲时长
+    Unavailable,
+    /// 本轮活跃时长与容差
+    Active {
+        active: Duration,
+        tolerance: Duration,
+    },
+    /// 空闲占本轮的百分比与阈值
+    IdleShare { percent: u8, threshold: u8 },
+    /// 周期结束时的空闲时长与休息时长
+    RestedBreakLength {
+        idle: Duration,
+        break_duration: Duration,
+    },
+}
 
-    idle_duration + MAX_ALLOWED_ACTIVE_TIME >= cycle_elapsed
+/// 休息是否真的在休息：休息结束时按空闲时长占比分类
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakCompliance {
+    /// 空闲时长覆盖了几乎整个休息
+    Rested,
+    /// 休息期间有一段时间在操作电脑
+    Partial,
+    /// 休息期间基本一直在操作电脑
+    Active,
+    /// 无法获取空闲时长
+    Unknown,
+}
+
+impl BreakCompliance {
+    /// 空闲占比不低于该百分比视为充分休息
+    const RESTED_PERCENT: u128 = 90;
+    /// 空闲占比低于该百分比视为没有休息
+    const ACTIVE_PERCENT: u128 = 50;
+
+    /// 比较休息时长与休息结束时的空闲时长
+    pub fn classify(break_elapsed: Duration, idle_duration: Option<Duration>) -> Self {
+        let Some(idle) = idle_duration else {
+            return Self::Unknown;
+        };
+        if break_elapsed.is_zero() {
+            return Self::Rested;
+        }
+        let percent = idle.min(break_elapsed).as_millis() * 100 / break_elapsed.as_millis();
+        if percent >= Self::RESTED_PERCENT {
+            Self::Rested
+        } else if percent >= Self::ACTIVE_PERCENT {
+            Self::Partial
+        } else {
+            Self::Active
+        }
+    }
 }
 
 #[cfg(target_os = "macos")]
 #[allow(unsafe_code)]
-fn current_idle_duration() -> Option<Duration> {
+pub fn current_idle_duration() -> Option<Duration> {
     use std::time::Duration;
 
     type CGEventSourceStateID = u32;
@@ -55,7 +183,7 @@ fn current_idle_duration() -> Option<Duration> {
 }
 
 #[cfg(not(target_os = "macos"))]
-const fn current_idle_duration() -> Option<Duration> {
+pub const fn current_idle_duration() -> Option<Duration> {
     None
 }
 
@@ -63,14 +191,83 @@ const fn current_idle_duration() -> Option<Duration> {
 mod tests {
     use super::*;
 
+    const CYCLE: Duration = Duration::from_secs(30 * 60);
+    const BREAK: Duration = Duration::from_secs(120);
+
+    const fn secs(s: u64) -> Duration {
+        Duration::from_secs(s)
+    }
+
+    fn skips(policy: IdlePolicy, idle: Option<Duration>) -> bool {
+        matches!(policy.decide(CYCLE, idle, BREAK), IdleDecision::Skip(_))
+    }
+
     #[test]
     fn skips_only_when_idle_almost_covers_cycle() {
-        let cycle = Duration::from_secs(30 * 60);
-        let almost_all_idle = cycle.checked_sub(Duration::from_secs(5)).unwrap();
-        assert!(should_skip_break_with_idle(cycle, almost_all_idle));
-        assert!(!should_skip_break_with_idle(
-            cycle,
-            cycle.checked_sub(Duration::from_secs(20)).unwrap()
+        let policy = IdlePolicy::default();
+        assert!(skips(policy, Some(secs(30 * 60 - 5))));
+        assert!(!skips(policy, Some(secs(30 * 60 - 20))));
+        assert_eq!(
+            policy.decide(CYCLE, Some(secs(30 * 60 - 5)), BREAK),
+            IdleDecision::Skip(IdleReason::Active {
+                active: Duration::from_secs(5),
+                tolerance: IdlePolicy::DEFAULT_TOLERANCE,
+            })
+        );
+    }
+
+    #[test]
+    fn tolerance_never_skips_a_cycle_shorter_than_itself() {
+        let policy = IdlePolicy::Tolerance(Duration::from_secs(60));
+        assert!(matches!(
+            policy.decide(Duration::from_secs(30), Some(secs(30)), BREAK),
+            IdleDecision::Keep(_)
         ));
     }
+
+    #[test]
+    fn disabled_and_unavailable_never_skip() {
+        assert_eq!(
+            IdlePolicy::Disabled.decide(CYCLE, Some(secs(30 * 60)), BREAK),
+            IdleDecision::Keep(IdleReason::Disabled)
+        );
+        assert_eq!(
+            IdlePolicy::default().decide(CYCLE, None, BREAK),
+            IdleDecision::Keep(IdleReason::Unavailable)
+        );
+    }
+
+    #[test]
+    fn percent_compares_idle_share_of_cycle() {
+        let policy = IdlePolicy::Percent(75);
+        assert_eq!(
+            policy.decide(CYCLE, Some(secs(27 * 60)), BREAK),
+            IdleDecision::Skip(IdleReason::IdleShare {
+                percent: 90,
+                threshold: 75,
+            })
+        );
+        assert!(!skips(policy, Some(secs(20 * 60))));
+        assert!(matches!(
+            policy.decide(Duration::ZERO, Some(secs(60)), BREAK),
+            IdleDecision::Keep(_)
+        ));
+    }
+
+    #[test]
+    fn classifies_break_compliance_by_idle_share() {
+        let classify = |idle| BreakCompliance::classify(BREAK, idle);
+        assert_eq!(classify(Some(secs(120))), BreakCompliance::Rested);
+        assert_eq!(classify(Some(secs(110))), BreakCompliance::Rested);
+        assert_eq!(classify(Some(secs(80))), BreakCompliance::Partial);
+        assert_eq!(classify(Some(secs(10))), BreakCompliance::Active);
+        assert_eq!(classify(None), BreakCompliance::Unknown);
+    }
+
+    #[test]
+    fn break_length_counts_recent_rest() {
+        let policy = IdlePolicy::BreakLength;
+        assert!(skips(policy, Some(secs(120))));
+        assert!(!skips(policy, Some(secs(119))));
+    }
 }
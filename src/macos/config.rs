@@ -1,237 +1,291 @@
-//! 配置管理模块
+//! 基于 `NSUserDefaults` 的配置存储
 //!
-//! 负责应用配置的加载、保存和验证。
+//! 配置结构、默认值与取值范围见 [`crate::config`]。
 
-use std::time::Duration;
+use std::io;
+use std::time::{Duration, UNIX_EPOCH};
 
 use objc2::ffi::NSInteger;
+use objc2::msg_send;
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
 use objc2_foundation::{NSString, NSUserDefaults};
 
-use crate::i18n::{Language, LanguagePreference};
-
-/// 界面主题外观
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
-pub enum Theme {
-    #[default]
-    Dark = 0,
-    Light = 1,
+use crate::config::IdlePolicyKind;
+pub use crate::config::{Config, ConfigStore, FileStore, ImportError, RawConfig, Theme};
+use crate::i18n::LanguagePreference;
+use crate::migration::{self, Settings};
+use crate::profile::Profile;
+use crate::scheduler::Pause;
+
+/// 本应用在 `NSUserDefaults` 中的键名前缀
+const KEY_PREFIX: &str = "restgap.";
+
+const KEY_INTERVAL_MINUTES: &str = "restgap.interval_minutes";
+const KEY_BREAK_SECONDS: &str = "restgap.break_seconds";
+const KEY_LANGUAGE: &str = "restgap.language";
+const KEY_ALLOW_SKIP_BREAK: &str = "restgap.allow_skip_break";
+const KEY_THEME: &str = "restgap.theme";
+const KEY_LONG_BREAK_EVERY: &str = "restgap.long_break_every";
+const KEY_LONG_BREAK_SECONDS: &str = "restgap.long_break_seconds";
+const KEY_POSTPONE_MINUTES: &str = "restgap.postpone_minutes";
+const KEY_MAX_POSTPONES: &str = "restgap.max_postpones";
+const KEY_PRE_BREAK_SECONDS: &str = "restgap.pre_break_seconds";
+const KEY_WORK_HOURS_ENABLED: &str = "restgap.work_hours_enabled";
+const KEY_WORK_HOURS: &str = "restgap.work_hours";
+const KEY_IDLE_POLICY: &str = "restgap.idle_policy";
+const KEY_IDLE_TOLERANCE_SECONDS: &str = "restgap.idle_tolerance_seconds";
+const KEY_IDLE_PERCENT: &str = "restgap.idle_percent";
+const KEY_ACTIVE_TIME: &str = "restgap.active_time";
+const KEY_ACTIVE_IDLE_THRESHOLD_SECONDS: &str = "restgap.active_idle_threshold_seconds";
+const KEY_EXTEND_ACTIVE_BREAKS: &str = "restgap.extend_active_breaks";
+const KEY_AWAIT_RETURN: &str = "restgap.await_return";
+const KEY_NATURAL_BREAK: &str = "restgap.natural_break";
+const KEY_DAILY_GOAL: &str = "restgap.daily_goal";
+/// 用户方案以 JSON 数组文本保存
+const KEY_PROFILES: &str = "restgap.profiles";
+const KEY_ACTIVE_PROFILE: &str = "restgap.active_profile";
+
+/// 当前使用的配置存储
+///
+/// 默认位置（`$XDG_CONFIG_HOME/restgap/config.json`）存在配置文件时使用文件存储，
+/// 便于随 dotfiles 同步；否则使用 `NSUserDefaults`。
+pub fn store() -> Box<dyn ConfigStore> {
+    match file_store() {
+        Some(file) => Box::new(file),
+        None => Box::new(UserDefaultsStore),
+    }
 }
 
-/// 应用配置
-#[derive(Clone, Debug)]
-pub struct Config {
-    pub interval_minutes: u64,
-    pub break_seconds: u64,
-    pub language: LanguagePreference,
-    pub allow_skip_break: bool,
-    pub theme: Theme,
+/// 默认位置存在配置文件时返回文件存储
+pub fn file_store() -> Option<FileStore> {
+    FileStore::default_location().filter(|file| file.path().exists())
 }
 
-impl Config {
-    pub const DEFAULT_INTERVAL_MINUTES: u64 = 30;
-    pub const DEFAULT_BREAK_SECONDS: u64 = 120;
-
-    pub const MIN_INTERVAL_MINUTES: u64 = 1;
-    pub const MAX_INTERVAL_MINUTES: u64 = 240;
-
-    pub const MIN_BREAK_SECONDS: u64 = 5;
-    pub const MAX_BREAK_SECONDS: u64 = 3600;
+/// 基于 `NSUserDefaults` 的配置存储（macOS 默认）
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UserDefaultsStore;
 
-    const KEY_INTERVAL_MINUTES: &'static str = "restgap.interval_minutes";
-    const KEY_BREAK_SECONDS: &'static str = "restgap.break_seconds";
-    const KEY_LANGUAGE: &'static str = "restgap.language";
-    const KEY_ALLOW_SKIP_BREAK: &'static str = "restgap.allow_skip_break";
-    const KEY_THEME: &'static str = "restgap.theme";
-
-    const LEGACY_KEY_INTERVAL_MINUTES: &'static str = "restp.interval_minutes";
-    const LEGACY_KEY_BREAK_SECONDS: &'static str = "restp.break_seconds";
-
-    /// 从 `NSUserDefaults` 加载配置
-    pub fn load() -> Self {
+impl UserDefaultsStore {
+    /// 读取各键的原始值（已迁移到当前结构版本，未设置的键不存在）
+    fn read_raw() -> RawConfig {
         let defaults = NSUserDefaults::standardUserDefaults();
+        let integer = |key: &str| defaults.integerForKey(&NSString::from_str(key));
+        let number = |key: &str| {
+            has_key(&defaults, &NSString::from_str(key))
+                .then(|| u64::try_from(integer(key)).ok())
+                .flatten()
+        };
+        // 未设置的开关返回 `None`，由默认配置补全，而不是当作关闭
+        let boolean = |key: &str| {
+            let key = NSString::from_str(key);
+            has_key(&defaults, &key).then(|| defaults.boolForKey(&key))
+        };
+        let string = |key: &str| {
+            defaults
+                .stringForKey(&NSString::from_str(key))
+                .map(|s| s.to_string())
+        };
 
-        let interval_key = NSString::from_str(Self::KEY_INTERVAL_MINUTES);
-        let break_key = NSString::from_str(Self::KEY_BREAK_SECONDS);
-        let language_key = NSString::from_str(Self::KEY_LANGUAGE);
-        let allow_skip_key = NSString::from_str(Self::KEY_ALLOW_SKIP_BREAK);
-        let theme_key = NSString::from_str(Self::KEY_THEME);
-
-        let legacy_interval_key = NSString::from_str(Self::LEGACY_KEY_INTERVAL_MINUTES);
-        let legacy_break_key = NSString::from_str(Self::LEGACY_KEY_BREAK_SECONDS);
+        RawConfig {
+            interval_minutes: number(KEY_INTERVAL_MINUTES),
+            break_seconds: number(KEY_BREAK_SECONDS),
+            language: Some(match integer(KEY_LANGUAGE) {
+                1 => LanguagePreference::En,
+                2 => LanguagePreference::Zh,
+                _ => LanguagePreference::Auto,
+            }),
+            allow_skip_break: boolean(KEY_ALLOW_SKIP_BREAK),
+            theme: Some(match integer(KEY_THEME) {
+                1 => Theme::Light,
+                _ => Theme::Dark,
+            }),
+            long_break_every: number(KEY_LONG_BREAK_EVERY),
+            long_break_seconds: number(KEY_LONG_BREAK_SECONDS),
+            postpone_minutes: number(KEY_POSTPONE_MINUTES),
+            max_postpones: number(KEY_MAX_POSTPONES),
+            pre_break_seconds: number(KEY_PRE_BREAK_SECONDS),
+            work_hours_enabled: boolean(KEY_WORK_HOURS_ENABLED),
+            work_hours: string(KEY_WORK_HOURS),
+            idle_policy: Some(IdlePolicyKind::from_code(
+                i64::try_from(integer(KEY_IDLE_POLICY)).unwrap_or(0),
+            )),
+            idle_tolerance_seconds: number(KEY_IDLE_TOLERANCE_SECONDS),
+            idle_percent: number(KEY_IDLE_PERCENT),
+            active_time: boolean(KEY_ACTIVE_TIME),
+            active_idle_threshold_seconds: number(KEY_ACTIVE_IDLE_THRESHOLD_SECONDS),
+            extend_active_breaks: boolean(KEY_EXTEND_ACTIVE_BREAKS),
+            await_return: boolean(KEY_AWAIT_RETURN),
+            natural_break: boolean(KEY_NATURAL_BREAK),
+            daily_goal: number(KEY_DAILY_GOAL),
+            profiles: string(KEY_PROFILES).and_then(|json| {
+                serde_json::from_str::<Vec<Profile>>(&json)
+                    .inspect_err(|err| crate::log_warn!("配置方案无法解析，已忽略: {err}"))
+                    .ok()
+            }),
+            active_profile: string(KEY_ACTIVE_PROFILE),
+        }
+    }
+}
 
-        let interval_raw = defaults.integerForKey(&interval_key);
-        let break_raw = defaults.integerForKey(&break_key);
-        let language_raw = defaults.integerForKey(&language_key);
-        let allow_skip_break = defaults.boolForKey(&allow_skip_key);
-        let theme_raw = defaults.integerForKey(&theme_key);
+/// 供迁移使用的 `NSUserDefaults` 键值视图
+struct DefaultsSettings(Retained<NSUserDefaults>);
 
-        let interval_raw = if interval_raw <= 0 {
-            defaults.integerForKey(&legacy_interval_key)
-        } else {
-            interval_raw
-        };
-        let break_raw = if break_raw <= 0 {
-            defaults.integerForKey(&legacy_break_key)
+impl DefaultsSettings {
+    /// 不含 `.` 的键加上本应用前缀，含 `.` 的键视为完整键名
+    fn key(key: &str) -> Retained<NSString> {
+        if key.contains('.') {
+            NSString::from_str(key)
         } else {
-            break_raw
-        };
+            NSString::from_str(&format!("{KEY_PREFIX}{key}"))
+        }
+    }
+}
 
-        let interval_minutes = if interval_raw <= 0 {
-            Self::DEFAULT_INTERVAL_MINUTES
-        } else {
-            u64::try_from(interval_raw).unwrap_or(Self::DEFAULT_INTERVAL_MINUTES)
-        };
+impl Settings for DefaultsSettings {
+    fn integer(&self, key: &str) -> Option<i64> {
+        let key = Self::key(key);
+        has_key(&self.0, &key).then(|| i64::try_from(self.0.integerForKey(&key)).unwrap_or(0))
+    }
 
-        let break_seconds = if break_raw <= 0 {
-            Self::DEFAULT_BREAK_SECONDS
-        } else {
-            u64::try_from(break_raw).unwrap_or(Self::DEFAULT_BREAK_SECONDS)
-        };
+    fn string(&self, key: &str) -> Option<String> {
+        self.0.stringForKey(&Self::key(key)).map(|s| s.to_string())
+    }
 
-        let language = match language_raw {
-            1 => LanguagePreference::En,
-            2 => LanguagePreference::Zh,
-            _ => LanguagePreference::Auto,
-        };
+    fn set_integer(&mut self, key: &str, value: i64) {
+        let value = NSInteger::try_from(value).unwrap_or(NSInteger::MAX);
+        self.0.setInteger_forKey(value, &Self::key(key));
+    }
 
-        let theme = match theme_raw {
-            1 => Theme::Light,
-            _ => Theme::Dark,
-        };
+    fn set_string(&mut self, key: &str, value: &str) {
+        let value = NSString::from_str(value);
+        unsafe { self.0.setObject_forKey(Some(&value), &Self::key(key)) };
+    }
 
-        Self {
-            interval_minutes: clamp_u64(
-                interval_minutes,
-                Self::MIN_INTERVAL_MINUTES,
-                Self::MAX_INTERVAL_MINUTES,
-            ),
-            break_seconds: clamp_u64(
-                break_seconds,
-                Self::MIN_BREAK_SECONDS,
-                Self::MAX_BREAK_SECONDS,
-            ),
-            language,
-            allow_skip_break,
-            theme,
-        }
+    fn remove(&mut self, key: &str) {
+        self.0.removeObjectForKey(&Self::key(key));
     }
+}
 
-    /// 保存配置到 `NSUserDefaults`
-    pub fn save(&self) {
-        let defaults = NSUserDefaults::standardUserDefaults();
-        let interval_key = NSString::from_str(Self::KEY_INTERVAL_MINUTES);
-        let break_key = NSString::from_str(Self::KEY_BREAK_SECONDS);
-        let language_key = NSString::from_str(Self::KEY_LANGUAGE);
-        let allow_skip_key = NSString::from_str(Self::KEY_ALLOW_SKIP_BREAK);
-        let theme_key = NSString::from_str(Self::KEY_THEME);
+impl ConfigStore for UserDefaultsStore {
+    fn migrate(&self) -> io::Result<Vec<&'static str>> {
+        let mut settings = DefaultsSettings(NSUserDefaults::standardUserDefaults());
+        Ok(migration::migrate(&mut settings))
+    }
 
-        let interval_minutes = NSInteger::try_from(self.interval_minutes).unwrap_or(NSInteger::MAX);
-        let break_seconds = NSInteger::try_from(self.break_seconds).unwrap_or(NSInteger::MAX);
+    fn load_raw(&self) -> io::Result<RawConfig> {
+        Ok(Self::read_raw())
+    }
 
-        defaults.setInteger_forKey(interval_minutes, &interval_key);
-        defaults.setInteger_forKey(break_seconds, &break_key);
+    fn save(&self, config: &Config) -> io::Result<()> {
+        let defaults = NSUserDefaults::standardUserDefaults();
+        let set_integer = |key: &str, value: u64| {
+            let value = NSInteger::try_from(value).unwrap_or(NSInteger::MAX);
+            defaults.setInteger_forKey(value, &NSString::from_str(key));
+        };
+        let set_bool = |key: &str, value: bool| {
+            defaults.setBool_forKey(value, &NSString::from_str(key));
+        };
+        let set_string = |key: &str, value: Option<&str>| {
+            let key = NSString::from_str(key);
+            match value {
+                Some(value) => unsafe {
+                    defaults.setObject_forKey(Some(&NSString::from_str(value)), &key);
+                },
+                None => defaults.removeObjectForKey(&key),
+            }
+        };
+
+        set_integer(KEY_INTERVAL_MINUTES, config.interval_minutes);
+        set_integer(KEY_BREAK_SECONDS, config.break_seconds);
 
-        let language_raw = match self.language {
+        let language_raw = match config.language {
             LanguagePreference::Auto => 0,
             LanguagePreference::En => 1,
             LanguagePreference::Zh => 2,
         };
-        defaults.setInteger_forKey(language_raw, &language_key);
-        defaults.setBool_forKey(self.allow_skip_break, &allow_skip_key);
+        set_integer(KEY_LANGUAGE, language_raw);
+        set_bool(KEY_ALLOW_SKIP_BREAK, config.allow_skip_break);
 
-        let theme_raw = match self.theme {
+        let theme_raw = match config.theme {
             Theme::Dark => 0,
             Theme::Light => 1,
         };
-        defaults.setInteger_forKey(theme_raw, &theme_key);
-    }
-
-    pub fn effective_language(&self) -> Language {
-        self.language.resolve()
+        set_integer(KEY_THEME, theme_raw);
+
+        set_integer(KEY_LONG_BREAK_EVERY, config.long_break_every);
+        set_integer(KEY_LONG_BREAK_SECONDS, config.long_break_seconds);
+        set_integer(KEY_POSTPONE_MINUTES, config.postpone_minutes);
+        set_integer(KEY_MAX_POSTPONES, config.max_postpones);
+        set_integer(KEY_PRE_BREAK_SECONDS, config.pre_break_seconds);
+
+        set_bool(KEY_WORK_HOURS_ENABLED, config.work_hours_enabled);
+        set_string(KEY_WORK_HOURS, Some(&config.work_hours.to_string()));
+
+        set_integer(
+            KEY_IDLE_POLICY,
+            u64::try_from(config.idle_policy_kind()).unwrap_or(0),
+        );
+        set_integer(KEY_IDLE_TOLERANCE_SECONDS, config.idle_tolerance_seconds());
+        set_integer(KEY_IDLE_PERCENT, config.idle_percent());
+
+        set_bool(KEY_ACTIVE_TIME, config.active_time);
+        set_integer(
+            KEY_ACTIVE_IDLE_THRESHOLD_SECONDS,
+            config.active_idle_threshold_seconds,
+        );
+        set_bool(KEY_EXTEND_ACTIVE_BREAKS, config.extend_active_breaks);
+        set_bool(KEY_AWAIT_RETURN, config.await_return);
+        set_bool(KEY_NATURAL_BREAK, config.natural_break);
+        set_integer(KEY_DAILY_GOAL, config.daily_goal);
+
+        set_string(
+            KEY_PROFILES,
+            Some(&serde_json::to_string(&config.profiles)?),
+        );
+        set_string(KEY_ACTIVE_PROFILE, config.active_profile.as_deref());
+        Ok(())
     }
+}
 
-    /// 获取工作间隔时长
-    pub const fn work_interval(&self) -> Duration {
-        Duration::from_secs(self.interval_minutes.saturating_mul(60))
-    }
+const KEY_PAUSED_UNTIL: &str = "restgap.paused_until";
+/// 无限期暂停在 `KEY_PAUSED_UNTIL` 中的取值
+const PAUSED_INDEFINITELY: f64 = -1.0;
 
-    /// 获取休息时长
-    pub const fn break_duration(&self) -> Duration {
-        Duration::from_secs(self.break_seconds)
+/// 加载上次退出前的暂停状态
+pub fn load_pause() -> Option<Pause> {
+    let defaults = NSUserDefaults::standardUserDefaults();
+    let key = NSString::from_str(KEY_PAUSED_UNTIL);
+    if !has_key(&defaults, &key) {
+        return None;
     }
-
-    /// 获取工作定时器容差（允许系统合并计时器唤醒）
-    pub fn work_tolerance(&self) -> Duration {
-        let secs = (self.work_interval().as_secs_f64() * 0.10).min(120.0);
-        Duration::from_secs_f64(secs.max(1.0))
-    }
-
-    /// 获取休息定时器容差
-    pub fn break_tolerance(&self) -> Duration {
-        let secs = (self.break_duration().as_secs_f64() * 0.10).min(5.0);
-        Duration::from_secs_f64(secs.max(0.5))
+    let raw = defaults.doubleForKey(&key);
+    if raw < 0.0 {
+        return Some(Pause::Indefinitely);
     }
+    Duration::try_from_secs_f64(raw)
+        .ok()
+        .map(|since_epoch| Pause::Until(UNIX_EPOCH + since_epoch))
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            interval_minutes: Self::DEFAULT_INTERVAL_MINUTES,
-            break_seconds: Self::DEFAULT_BREAK_SECONDS,
-            language: LanguagePreference::Auto,
-            allow_skip_break: false,
-            theme: Theme::Dark,
+/// 保存暂停状态，使其在重启后仍然生效
+pub fn save_pause(pause: Option<Pause>) {
+    let defaults = NSUserDefaults::standardUserDefaults();
+    let key = NSString::from_str(KEY_PAUSED_UNTIL);
+    match pause {
+        None => defaults.removeObjectForKey(&key),
+        Some(Pause::Indefinitely) => defaults.setDouble_forKey(PAUSED_INDEFINITELY, &key),
+        Some(Pause::Until(until)) => {
+            let secs = until
+                .duration_since(UNIX_EPOCH)
+                .map_or(0.0, |d| d.as_secs_f64());
+            defaults.setDouble_forKey(secs, &key);
         }
     }
 }
 
-/// 将值限制在指定范围内
-pub fn clamp_u64(v: u64, min: u64, max: u64) -> u64 {
-    v.max(min).min(max)
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_clamp_u64() {
-        assert_eq!(clamp_u64(5, 1, 10), 5);
-        assert_eq!(clamp_u64(0, 1, 10), 1);
-        assert_eq!(clamp_u64(15, 1, 10), 10);
-    }
-
-    #[test]
-    fn test_config_default() {
-        let config = Config::default();
-        assert_eq!(config.interval_minutes, Config::DEFAULT_INTERVAL_MINUTES);
-        assert_eq!(config.break_seconds, Config::DEFAULT_BREAK_SECONDS);
-        assert_eq!(config.language, LanguagePreference::Auto);
-        assert!(!config.allow_skip_break);
-        assert_eq!(config.theme, Theme::Dark);
-    }
-
-    #[test]
-    fn test_work_interval() {
-        let config = Config {
-            interval_minutes: 30,
-            break_seconds: 120,
-            language: LanguagePreference::Auto,
-            allow_skip_break: false,
-            theme: Theme::Dark,
-        };
-        assert_eq!(config.work_interval(), Duration::from_secs(1800));
-    }
-
-    #[test]
-    fn test_break_duration() {
-        let config = Config {
-            interval_minutes: 30,
-            break_seconds: 120,
-            language: LanguagePreference::Auto,
-            allow_skip_break: false,
-            theme: Theme::Dark,
-        };
-        assert_eq!(config.break_duration(), Duration::from_secs(120));
-    }
+/// 判断 `NSUserDefaults` 中是否存在指定键
+fn has_key(defaults: &NSUserDefaults, key: &NSString) -> bool {
+    let obj: Option<Retained<AnyObject>> = unsafe { msg_send![defaults, objectForKey: key] };
+    obj.is_some()
 }
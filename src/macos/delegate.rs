@@ -6,17 +6,21 @@ use objc2::rc::Retained;
 use objc2::runtime::{AnyObject, Bool, NSObject};
 use objc2::{MainThreadMarker, MainThreadOnly, define_class, msg_send};
 
-use objc2_app_kit::{NSApplication, NSApplicationDelegate, NSMenu, NSMenuDelegate};
+use objc2_app_kit::{NSApplication, NSApplicationDelegate, NSMenu, NSMenuDelegate, NSMenuItem};
 use objc2_foundation::{NSNotification, NSObjectProtocol, NSTimer};
 
 use super::state::{Phase, with_state, with_state_ref};
 use super::timer::{
-    schedule_phase, skip_break as skip_break_phase, start_break_now, transition_on_timer,
+    apply_settings, countdown_finished, observe_system_wake,
+    pause_reminders as pause_reminders_phase, postpone_break as postpone_break_phase,
+    reload_config, resume_reminders as resume_reminders_phase, schedule_phase,
+    skip_break as skip_break_phase, start_break_now, switch_profile as switch_profile_phase,
+    system_did_wake, transition_on_timer, watch_config_file, welcome_back_elapsed,
 };
 use super::ui::{
-    finish_countdown, open_settings_dialog, refresh_header_title, refresh_menu_info,
-    refresh_static_menu_titles, refresh_status_title, setup_status_item, show_about_dialog,
-    update_countdown,
+    close_countdown_window, open_settings_dialog, pause_choice, refresh_header_title,
+    refresh_menu_info, refresh_profile_menu, refresh_static_menu_titles, refresh_status_title,
+    setup_status_item, show_about_dialog, show_export_history_dialog, update_countdown,
 };
 
 define_class!(
@@ -32,6 +36,8 @@ define_class!(
         #[unsafe(method(applicationDidFinishLaunching:))]
         fn application_did_finish_launching(&self, _notification: &NSNotification) {
             setup_status_item(self);
+            observe_system_wake(self);
+            watch_config_file();
             schedule_phase(self, Phase::Working);
         }
 
@@ -64,9 +70,26 @@ define_class!(
             transition_on_timer(self);
         }
 
+        #[unsafe(method(welcomeBackElapsed:))]
+        fn welcome_back_elapsed(&self, _timer: &NSTimer) {
+            welcome_back_elapsed();
+        }
+
+        #[unsafe(method(celebrationElapsed:))]
+        fn celebration_elapsed(&self, _timer: &NSTimer) {
+            // 庆祝画面展示结束
+            close_countdown_window();
+        }
+
+        #[unsafe(method(systemDidWake:))]
+        fn system_did_wake(&self, _notification: &NSNotification) {
+            system_did_wake(self);
+        }
+
         #[unsafe(method(menuWillOpen:))]
         fn menu_will_open(&self, _menu: &NSMenu) {
             // 无轮询：仅在用户打开菜单时刷新显示。
+            refresh_profile_menu(self);
             refresh_status_title();
             refresh_header_title();
             refresh_static_menu_titles();
@@ -83,6 +106,11 @@ define_class!(
             open_settings_dialog(self);
         }
 
+        #[unsafe(method(exportHistory:))]
+        fn export_history(&self, _sender: Option<&AnyObject>) {
+            show_export_history_dialog(self);
+        }
+
         #[unsafe(method(about:))]
         fn about(&self, _sender: Option<&AnyObject>) {
             show_about_dialog(self);
@@ -111,8 +139,8 @@ define_class!(
             }
 
             if !update_countdown() {
-                // 倒计时结束
-                finish_countdown();
+                // 倒计时结束：由调度器判定是否真正休息
+                countdown_finished(self);
             }
         }
 
@@ -122,16 +150,67 @@ define_class!(
             skip_break_phase(self);
         }
 
+        #[unsafe(method(postponeBreak:))]
+        fn postpone_break(&self, _sender: Option<&AnyObject>) {
+            // 用户点击推迟按钮（菜单或休息界面）
+            postpone_break_phase(self);
+        }
+
+        #[unsafe(method(pauseReminders:))]
+        fn pause_reminders(&self, sender: Option<&AnyObject>) {
+            // 暂停子菜单中各选项的 tag 对应不同的暂停时长
+            let tag = sender
+                .and_then(|sender| sender.downcast_ref::<NSMenuItem>())
+                .map(NSMenuItem::tag);
+            if let Some(pause) = tag.and_then(pause_choice) {
+                pause_reminders_phase(self, pause);
+            }
+        }
+
+        #[unsafe(method(switchProfile:))]
+        fn switch_profile(&self, sender: Option<&AnyObject>) {
+            // 方案子菜单中各选项的 tag 为可切换方案列表中的下标
+            let tag = sender
+                .and_then(|sender| sender.downcast_ref::<NSMenuItem>())
+                .map(NSMenuItem::tag);
+            let name = tag
+                .and_then(|tag| usize::try_from(tag).ok())
+                .and_then(|index| {
+                    with_state_ref(|state| state.config.available_profiles().into_iter().nth(index))
+                })
+                .map(|profile| profile.name);
+            if let Some(name) = name {
+                switch_profile_phase(self, &name);
+            }
+        }
 
+        #[unsafe(method(resumeReminders:))]
+        fn resume_reminders(&self, _sender: Option<&AnyObject>) {
+            resume_reminders_phase(self);
+        }
 
         #[unsafe(method(settingsChanged))]
         fn settings_changed(&self) {
-            let (phase, config) = with_state(|state| (state.phase, state.config.clone()));
-            schedule_phase(self, phase);
-            if phase == Phase::Breaking {
-                super::ui::show_countdown_window(self, config.break_seconds, false);
+            apply_settings(self);
+        }
+
+        /// 由监听线程经 `performSelectorOnMainThread` 调用（类方法），转发给应用代理实例
+        #[unsafe(method(configFileChanged))]
+        fn config_file_changed() {
+            let Some(mtm) = MainThreadMarker::new() else {
+                return;
+            };
+            if let Some(delegate) = NSApplication::sharedApplication(mtm).delegate() {
+                unsafe {
+                    let _: () = msg_send![&delegate, reloadConfig];
+                }
             }
         }
+
+        #[unsafe(method(reloadConfig))]
+        fn reload_config(&self) {
+            reload_config(self);
+        }
     }
 );
 
@@ -8,7 +8,6 @@
 pub mod config;
 pub mod constants;
 pub mod delegate;
-pub mod error;
 pub mod locale;
 pub mod log;
 pub mod state;
@@ -22,15 +21,32 @@ use objc2::runtime::ProtocolObject;
 
 use objc2_app_kit::{NSApplication, NSApplicationActivationPolicy, NSApplicationDelegate};
 
-use config::Config;
+use crate::history::HistoryStore;
 use delegate::create_delegate;
-use state::init_state;
+use state::{init_state, with_state};
 
 /// 运行应用
 pub fn run() {
     autoreleasepool(|_| {
-        let config = Config::load();
+        let store = config::store();
+        match store.migrate() {
+            Ok(applied) if !applied.is_empty() => crate::log_info!("配置迁移: {applied:?}"),
+            Ok(_) => {}
+            Err(err) => crate::log_error!("配置迁移失败: {err}"),
+        }
+        let raw = store.load_raw().unwrap_or_else(|err| {
+            crate::log_error!("加载配置失败，使用默认配置: {err}");
+            config::RawConfig::default()
+        });
+        for rejected in raw.out_of_range() {
+            crate::log_warn!("配置值无效，已改用最接近的合法值: {rejected}");
+        }
+        let config = config::Config::from_raw(&raw);
         init_state(config);
+        with_state(|state| state.scheduler.restore_pause(config::load_pause()));
+        if let Some(history) = HistoryStore::default_location() {
+            load_history(&history);
+        }
 
         let mtm = MainThreadMarker::new().expect("must be on the main thread");
         let app = NSApplication::sharedApplication(mtm);
@@ -50,3 +66,12 @@ pub fn run() {
         app.run();
     });
 }
+
+/// 清理过期的休息历史并加载到状态中
+fn load_history(history: &HistoryStore) {
+    timer::prune_history(history);
+    match history.read() {
+        Ok(records) => with_state(|state| state.history = records),
+        Err(err) => crate::log_warn!("读取休息历史 {} 失败: {err}", history.path().display()),
+    }
+}
@@ -3,7 +3,7 @@
 //! 使用线程本地存储管理全局应用状态。
 
 use std::cell::RefCell;
-use std::time::{Instant, SystemTime};
+use std::time::Instant;
 
 use objc2::rc::Retained;
 use objc2::runtime::AnyObject;
@@ -12,36 +12,37 @@ use objc2_foundation::NSTimer;
 use objc2_web_kit::WKWebView;
 
 use super::config::Config;
-
-/// 工作阶段
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum Phase {
-    Working,
-    Breaking,
-}
-
-/// 通知事件类型
-#[derive(Clone, Copy, Debug)]
-pub enum NotifyEvent {
-    BreakStart,
-    BreakEnd,
-}
+use super::ui::CountdownAppearance;
+use crate::config::FileStore;
+use crate::goals::Milestone;
+use crate::history::Record;
+pub use crate::scheduler::Phase;
+use crate::scheduler::Scheduler;
+use crate::watch::WatchHandle;
 
 /// 应用状态
 #[derive(Debug)]
 pub struct AppState {
     pub config: Config,
-    pub phase: Phase,
-    pub phase_started_at_mono: Option<Instant>,
-    pub phase_deadline_mono: Option<Instant>,
-    pub phase_deadline_wall: Option<SystemTime>,
+    pub scheduler: Scheduler,
     pub timer: Option<Retained<NSTimer>>,
+    /// 用户回来后短暂展示“欢迎回来”，定时器到期后恢复常规标题
+    pub welcome_timer: Option<Retained<NSTimer>>,
     pub status_item: Option<Retained<NSStatusItem>>,
     pub header_item: Option<Retained<NSMenuItem>>,
     pub rest_now_item: Option<Retained<NSMenuItem>>,
+    pub postpone_item: Option<Retained<NSMenuItem>>,
+    pub pause_item: Option<Retained<NSMenuItem>>,
+    pub profile_item: Option<Retained<NSMenuItem>>,
+    /// “成就”子菜单，子项顺序与 [`crate::goals::Achievement::ALL`] 一致
+    pub achievements_item: Option<Retained<NSMenuItem>>,
+    pub resume_item: Option<Retained<NSMenuItem>>,
     pub next_break_item: Option<Retained<NSMenuItem>>,
+    /// “今日：已休息 7/9 次”
+    pub today_item: Option<Retained<NSMenuItem>>,
     pub remaining_break_item: Option<Retained<NSMenuItem>>,
     pub settings_item: Option<Retained<NSMenuItem>>,
+    pub export_history_item: Option<Retained<NSMenuItem>>,
     pub about_item: Option<Retained<NSMenuItem>>,
     pub quit_item: Option<Retained<NSMenuItem>>,
     // Countdown window state
@@ -50,27 +51,48 @@ pub struct AppState {
     pub countdown_timer: Option<Retained<NSTimer>>,
     pub countdown_end_time: Option<Instant>,
     pub countdown_nav_delegate: Option<Retained<AnyObject>>,
+    /// 当前倒计时窗口展示时使用的设置，窗口关闭时为 `None`
+    pub countdown_appearance: Option<CountdownAppearance>,
+    /// 休息达成目标或解锁成就时，庆祝画面展示结束后关闭倒计时窗口
+    pub celebration_timer: Option<Retained<NSTimer>>,
+    /// 尚未庆祝的里程碑（来自没有倒计时窗口的休息），在下一次休息结束时展示
+    pub pending_milestones: Vec<Milestone>,
     // Settings window state
     pub settings_window: Option<Retained<NSWindow>>,
     pub settings_webview: Option<Retained<WKWebView>>,
     pub settings_nav_delegate: Option<Retained<AnyObject>>,
+    /// 保留期限内的休息历史，供统计使用（启动时从文件加载，之后随记录追加）
+    pub history: Vec<Record>,
+    /// 上次清理过期历史的本地日期（天数）
+    pub history_pruned_day: Option<i64>,
+    /// 上次刷新菜单时的阶段；阶段没有变化的定时器重设不刷新菜单
+    pub menu_phase: Option<Phase>,
+    /// 正在监听的配置文件及监听句柄；丢弃句柄时停止监听线程
+    pub config_watch: Option<(FileStore, WatchHandle)>,
 }
 
 impl AppState {
-    pub const fn new(config: Config) -> Self {
+    pub fn new(config: Config) -> Self {
+        let mut scheduler = Scheduler::new(config.timings());
+        scheduler.set_work_hours(config.active_work_hours());
         Self {
+            scheduler,
             config,
-            phase: Phase::Working,
-            phase_started_at_mono: None,
-            phase_deadline_mono: None,
-            phase_deadline_wall: None,
             timer: None,
+            welcome_timer: None,
             status_item: None,
             header_item: None,
             rest_now_item: None,
+            postpone_item: None,
+            pause_item: None,
+            profile_item: None,
+            achievements_item: None,
+            resume_item: None,
             next_break_item: None,
+            today_item: None,
             remaining_break_item: None,
             settings_item: None,
+            export_history_item: None,
             about_item: None,
             quit_item: None,
             countdown_windows: Vec::new(),
@@ -78,9 +100,16 @@ impl AppState {
             countdown_timer: None,
             countdown_end_time: None,
             countdown_nav_delegate: None,
+            countdown_appearance: None,
+            celebration_timer: None,
+            pending_milestones: Vec::new(),
             settings_window: None,
             settings_webview: None,
             settings_nav_delegate: None,
+            history: Vec::new(),
+            history_pruned_day: None,
+            menu_phase: None,
+            config_watch: None,
         }
     }
 }
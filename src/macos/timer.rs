@@ -1,128 +1,378 @@
 //! 定时器和阶段管理模块
+//!
+//! 阶段转换规则由 [`crate::scheduler::Scheduler`] 决定，这里只负责执行其输出的指令。
 
-use std::time::{Instant, SystemTime};
+use std::time::{Duration, SystemTime};
 
-use objc2::sel;
+use objc2::runtime::AnyObject;
+use objc2::{ClassType, msg_send, sel};
+use objc2_app_kit::{NSWorkspace, NSWorkspaceDidWakeNotification};
 use objc2_foundation::NSTimer;
 
-use super::config::Config;
+use super::config::{file_store, save_pause, store};
 use super::delegate::RestGapDelegate;
-use super::state::{NotifyEvent, Phase, with_state};
+use super::state::{Phase, with_state, with_state_ref};
 use super::ui::{
-    finish_countdown, refresh_header_title, refresh_menu_info, refresh_static_menu_titles,
-    refresh_status_title, show_countdown_window, target_anyobject,
+    CountdownAppearance, close_countdown_window, finish_countdown, refresh_header_title,
+    refresh_menu_info, refresh_static_menu_titles, refresh_status_title, reset_countdown,
+    show_countdown_window, target_anyobject,
 };
+use crate::goals::{self, Milestone};
+use crate::history::{HistoryStore, Record};
+use crate::i18n::Texts;
+use crate::scheduler::{Clock, Command, Event, Pause, SystemClock, SystemIdle};
+use crate::work_hours::SECS_PER_DAY;
+
+/// 用户回来后状态栏展示“欢迎回来”的时长
+const WELCOME_BACK_DURATION: Duration = Duration::from_secs(60);
 
 /// 调度阶段定时器
 pub fn schedule_phase(delegate: &RestGapDelegate, phase: Phase) {
+    let commands = with_state(|state| state.scheduler.enter(phase, &SystemClock));
+    execute(delegate, commands);
+}
+
+/// 执行调度器输出的指令并刷新菜单
+fn execute(delegate: &RestGapDelegate, commands: Vec<Command>) {
+    // 休息结束的记录紧随 EndBreak 之后，等记录写入后再决定是否展示庆祝画面
+    let mut end_break = false;
+    let mut milestones = Vec::new();
+    // 只是重新设定定时器（如等待返回时的轮询、空闲采样）时历史和菜单状态都没有变化
+    let rearm_only = commands
+        .iter()
+        .all(|command| matches!(command, Command::ArmTimer { .. } | Command::CancelTimer));
+    for command in commands {
+        match command {
+            Command::ArmTimer { after, tolerance } => arm_timer(delegate, after, tolerance),
+            // 休息开始: 弹出原生倒计时窗口
+            Command::ShowBreak { seconds, kind } => {
+                let planned = Duration::from_secs(seconds);
+                record_history(&Record::started(kind, planned, SystemTime::now()));
+                show_countdown_window(delegate, seconds, kind, true);
+            }
+            // 休息结束: 关闭倒计时窗口并播放提示音
+            Command::EndBreak => end_break = true,
+            // 休息推迟: 静默关闭倒计时窗口
+            Command::HideBreak => close_countdown_window(),
+            // 休息延长或睡眠唤醒: 校正倒计时
+            Command::UpdateCountdown { remaining } => reset_countdown(delegate, remaining),
+            // 无需定时器: 取消当前定时器
+            Command::CancelTimer => with_state(|state| {
+                if let Some(timer) = state.timer.take() {
+                    timer.invalidate();
+                }
+            }),
+            Command::Record(event) => {
+                crate::log_info!("{event:?}");
+                let planned = |kind| with_state_ref(|state| state.scheduler.break_duration(kind));
+                if let Some(record) = Record::from_event(&event, planned, SystemTime::now()) {
+                    milestones.extend(with_state_ref(|state| {
+                        goals::milestones(
+                            &state.history,
+                            &record,
+                            state.config.daily_goal_breaks(),
+                            SystemClock.utc_offset(),
+                        )
+                    }));
+                    record_history(&record);
+                }
+                // 暂停状态需要跨重启保留
+                if matches!(event, Event::Paused { .. } | Event::Resumed) {
+                    save_pause(with_state_ref(|state| state.scheduler.paused()));
+                }
+                if matches!(event, Event::Returned { .. }) {
+                    show_welcome_back(delegate);
+                }
+            }
+        }
+    }
+    // 工作中离开或睡眠记下的休息没有倒计时窗口，里程碑留到下一次休息结束时一并庆祝
+    with_state(|state| state.pending_milestones.append(&mut milestones));
+    if end_break {
+        let milestones = with_state(|state| std::mem::take(&mut state.pending_milestones));
+        finish_countdown(delegate, celebration(&milestones));
+    }
+
+    let phase_changed = with_state(|state| {
+        let phase = Some(state.scheduler.phase());
+        std::mem::replace(&mut state.menu_phase, phase) != phase
+    });
+    if rearm_only && !phase_changed {
+        // 截止时间可能被顺延，状态栏标题仍需更新；菜单中的统计不必重新计算
+        refresh_status_title();
+    } else {
+        refresh_menus();
+    }
+}
+
+/// 刷新状态栏标题与菜单内容（包括由历史计算的统计和连续天数）
+fn refresh_menus() {
+    refresh_status_title();
+    refresh_header_title();
+    refresh_static_menu_titles();
+    refresh_menu_info();
+}
+
+/// 庆祝画面的标题与说明；没有里程碑时为 `None`
+fn celebration(milestones: &[Milestone]) -> Option<(String, String)> {
+    let first = *milestones.first()?;
+    let texts = Texts::new(with_state_ref(|state| state.config.effective_language()));
+    let hint = milestones
+        .iter()
+        .map(|milestone| texts.milestone_hint(*milestone))
+        .collect::<Vec<_>>()
+        .join(" · ");
+    Some((texts.milestone_title(first).to_string(), hint))
+}
+
+/// 追加一条休息历史；写入失败只记录日志，不影响调度
+fn record_history(record: &Record) {
+    with_state(|state| state.history.push(record.clone()));
+    let Some(history) = HistoryStore::default_location() else {
+        return;
+    };
+    if let Err(err) = history.append(record) {
+        crate::log_warn!("写入休息历史失败: {err}");
+    }
+    // 应用可能连续运行很多天，每天第一次写入时清理一次过期记录
+    let today = SystemClock.local_secs().div_euclid(SECS_PER_DAY);
+    if with_state_ref(|state| state.history_pruned_day) != Some(today) {
+        prune_history(&history);
+    }
+}
+
+/// 删除文件与内存中早于保留期限的休息历史
+pub fn prune_history(history: &HistoryStore) {
+    let now = SystemTime::now();
+    match history.prune(now) {
+        Ok(0) => {}
+        Ok(removed) => crate::log_info!("已删除 {removed} 条过期的休息历史"),
+        Err(err) => crate::log_warn!("清理休息历史 {} 失败: {err}", history.path().display()),
+    }
+    let today = SystemClock.local_secs().div_euclid(SECS_PER_DAY);
+    with_state(|state| {
+        history.retain_recent(&mut state.history, now);
+        state.history_pruned_day = Some(today);
+    });
+}
+
+/// 重新设置阶段定时器
+fn arm_timer(delegate: &RestGapDelegate, after: Duration, tolerance: Duration) {
     let target = target_anyobject(delegate);
 
-    let (seconds, tolerance) = with_state(|state| {
+    with_state(|state| {
         if let Some(timer) = state.timer.take() {
             timer.invalidate();
         }
-
-        state.phase = phase;
-        let started_at = Instant::now();
-        let (duration, tolerance) = match phase {
-            Phase::Working => (state.config.work_interval(), state.config.work_tolerance()),
-            Phase::Breaking => (
-                state.config.break_duration(),
-                state.config.break_tolerance(),
-            ),
-        };
-
-        state.phase_started_at_mono = Some(started_at);
-        state.phase_deadline_mono = Some(started_at + duration);
-        state.phase_deadline_wall = Some(SystemTime::now() + duration);
-
-        (duration.as_secs_f64(), tolerance.as_secs_f64())
     });
 
     let timer = unsafe {
         NSTimer::scheduledTimerWithTimeInterval_target_selector_userInfo_repeats(
-            seconds,
+            after.as_secs_f64(),
             target,
             sel!(timerFired:),
             None,
             false,
         )
     };
-    timer.setTolerance(tolerance);
+    timer.setTolerance(tolerance.as_secs_f64());
 
     with_state(|state| {
         state.timer = Some(timer);
     });
-
-    refresh_status_title();
-    refresh_header_title();
-    refresh_static_menu_titles();
-    refresh_menu_info();
 }
 
-/// 发送通知
-fn notify(event: NotifyEvent, config: &Config, delegate: &RestGapDelegate) {
-    match event {
-        NotifyEvent::BreakStart => {
-            // 休息开始: 弹出原生倒计时窗口
-            show_countdown_window(delegate, config.break_seconds, true);
-        }
-        NotifyEvent::BreakEnd => {
-            // 休息结束: 关闭倒计时窗口并显示通知
-            finish_countdown();
-        }
+/// 在状态栏短暂展示“欢迎回来”
+fn show_welcome_back(delegate: &RestGapDelegate) {
+    let timer = unsafe {
+        NSTimer::scheduledTimerWithTimeInterval_target_selector_userInfo_repeats(
+            WELCOME_BACK_DURATION.as_secs_f64(),
+            target_anyobject(delegate),
+            sel!(welcomeBackElapsed:),
+            None,
+            false,
+        )
+    };
+    if let Some(previous) = with_state(|state| state.welcome_timer.replace(timer)) {
+        previous.invalidate();
     }
 }
 
+/// “欢迎回来”展示结束，恢复常规状态栏标题
+pub fn welcome_back_elapsed() {
+    with_state(|state| state.welcome_timer = None);
+    refresh_status_title();
+}
+
 /// 定时器触发时的阶段转换
 pub fn transition_on_timer(delegate: &RestGapDelegate) {
-    let transition = with_state(|state| {
+    let commands = with_state(|state| {
         state.timer.take();
-        let config = state.config.clone();
-        match state.phase {
-            Phase::Working => {
-                let should_skip = state
-                    .phase_started_at_mono
-                    .is_some_and(|started_at| crate::idle::should_skip_break(started_at.elapsed()));
-                if should_skip {
-                    (Phase::Working, None, config)
-                } else {
-                    (Phase::Breaking, Some(NotifyEvent::BreakStart), config)
-                }
-            }
-            Phase::Breaking => (Phase::Working, Some(NotifyEvent::BreakEnd), config),
-        }
+        state.scheduler.on_timer(&SystemClock, &SystemIdle)
     });
+    execute(delegate, commands);
+}
 
-    let (next_phase, event, config) = transition;
-    if let Some(event) = event {
-        notify(event, &config, delegate);
+/// 应用已保存的新设置，保留当前阶段已经过去的时长
+///
+/// 休息中会按剩余时长重新渲染倒计时界面，使主题、语言等界面设置立即生效。
+pub fn apply_settings(delegate: &RestGapDelegate) {
+    let commands = with_state(|state| {
+        let timings = state.config.timings();
+        let work_hours = state.config.active_work_hours();
+        state
+            .scheduler
+            .apply_settings(timings, work_hours, &SystemClock)
+    });
+    execute(delegate, commands);
+    // 语言、每日目标等设置改变了菜单内容，即使阶段没有变化也要刷新
+    refresh_menus();
+
+    // 休息时长等变化已由调度器的指令更新倒计时；只有窗口展示的设置变化时才重建窗口，避免闪烁
+    let rebuild = with_state_ref(|state| {
+        let stale = state
+            .countdown_appearance
+            .is_some_and(|shown| shown != CountdownAppearance::of(state));
+        (stale && state.scheduler.phase() == Phase::Breaking).then(|| {
+            (
+                state.scheduler.remaining(&SystemClock),
+                state.scheduler.break_kind(),
+            )
+        })
+    });
+    if let Some((remaining, kind)) = rebuild
+        && !remaining.is_zero()
+    {
+        show_countdown_window(delegate, remaining.as_secs(), kind, false);
     }
-    schedule_phase(delegate, next_phase);
 }
 
-/// 立即开始休息
-pub fn start_break_now(delegate: &RestGapDelegate) {
-    let (should_start, config) = with_state(|state| {
-        let config = state.config.clone();
-        let should_start = state.phase == Phase::Working;
-        (should_start, config)
+/// 切换到指定方案，保存后按与保存设置相同的方式应用
+pub fn switch_profile(delegate: &RestGapDelegate, name: &str) {
+    let mut new_config = with_state_ref(|state| state.config.clone());
+    if !new_config.switch_profile(name) {
+        return;
+    }
+    if let Err(err) = store().save(&new_config) {
+        crate::log_error!("保存配置失败: {err}");
+    }
+    crate::log_info!("切换配置方案: {name}");
+    with_state(|state| state.config = new_config);
+    apply_settings(delegate);
+}
+
+/// 监听系统唤醒通知
+pub fn observe_system_wake(delegate: &RestGapDelegate) {
+    let center = NSWorkspace::sharedWorkspace().notificationCenter();
+    unsafe {
+        center.addObserver_selector_name_object(
+            target_anyobject(delegate),
+            sel!(systemDidWake:),
+            Some(NSWorkspaceDidWakeNotification),
+            None,
+        );
+    }
+}
+
+/// 监听配置文件，文件被脚本或同步工具修改后在主线程重新加载
+///
+/// 使用 `NSUserDefaults` 存储时不监听。
+pub fn watch_config_file() {
+    let Some(file) = file_store() else {
+        return;
+    };
+    let result = crate::watch::spawn(file.path(), || unsafe {
+        // 监听线程不能直接访问应用状态，转到主线程处理
+        let _: () = msg_send![
+            RestGapDelegate::class(),
+            performSelectorOnMainThread: sel!(configFileChanged),
+            withObject: Option::<&AnyObject>::None,
+            waitUntilDone: false
+        ];
     });
+    match result {
+        Ok(handle) => {
+            crate::log_info!("监听配置文件: {}", file.path().display());
+            with_state(|state| state.config_watch = Some((file, handle)));
+        }
+        Err(err) => crate::log_warn!("无法监听配置文件 {}: {err}", file.path().display()),
+    }
+}
 
-    if should_start {
-        notify(NotifyEvent::BreakStart, &config, delegate);
-        schedule_phase(delegate, Phase::Breaking);
+/// 配置文件变化后重新加载；校验通过且内容有变化时按“设置已更改”应用
+pub fn reload_config(delegate: &RestGapDelegate) {
+    // 读取正在监听的文件，而不是重新查找位置
+    let Some((file, current)) = with_state_ref(|state| {
+        let (file, _) = state.config_watch.as_ref()?;
+        Some((file.clone(), state.config.clone()))
+    }) else {
+        return;
+    };
+    match crate::watch::reload(&file, &current) {
+        Ok(Some(reloaded)) => {
+            crate::log_info!("配置文件已变化: {}", reloaded.changes.join(", "));
+            with_state(|state| state.config = reloaded.config);
+            apply_settings(delegate);
+        }
+        Ok(None) => {}
+        Err(errors) => {
+            for err in errors {
+                crate::log_warn!("配置文件的修改未生效: {err}");
+            }
+        }
+    }
+}
+
+/// 系统唤醒后核对睡眠时长，校正或重新开始当前阶段
+pub fn system_did_wake(delegate: &RestGapDelegate) {
+    let commands = with_state(|state| state.scheduler.on_wake(&SystemClock));
+    execute(delegate, commands);
+}
+
+/// 倒计时归零时立即结算休息，而不是等待带容差的阶段定时器
+pub fn countdown_finished(delegate: &RestGapDelegate) {
+    let breaking = with_state(|state| {
+        if state.scheduler.phase() != Phase::Breaking {
+            return false;
+        }
+        if let Some(timer) = state.timer.take() {
+            timer.invalidate();
+        }
+        true
+    });
+    if breaking {
+        transition_on_timer(delegate);
+    } else {
+        finish_countdown(delegate, None);
     }
 }
 
+/// 立即开始休息
+pub fn start_break_now(delegate: &RestGapDelegate) {
+    let commands = with_state(|state| state.scheduler.start_break_now(&SystemClock));
+    execute(delegate, commands);
+}
+
 /// 跳过当前休息（仅在 Breaking 阶段生效）
 pub fn skip_break(delegate: &RestGapDelegate) {
-    let should_skip = with_state(|state| state.phase == Phase::Breaking);
-    if !should_skip {
-        return;
-    }
+    let commands = with_state(|state| state.scheduler.skip_break(&SystemClock));
+    execute(delegate, commands);
+}
+
+/// 推迟即将开始或正在进行的休息（PreBreak 或 Breaking 阶段且仍有剩余次数时生效）
+pub fn postpone_break(delegate: &RestGapDelegate) {
+    let commands = with_state(|state| state.scheduler.postpone(&SystemClock));
+    execute(delegate, commands);
+}
+
+/// 暂停提醒
+pub fn pause_reminders(delegate: &RestGapDelegate, pause: Pause) {
+    let commands = with_state(|state| state.scheduler.pause(pause, &SystemClock));
+    execute(delegate, commands);
+}
 
-    // 提前结束休息：关闭倒计时窗口并回到工作阶段
-    finish_countdown();
-    schedule_phase(delegate, Phase::Working);
+/// 结束暂停并恢复提醒
+pub fn resume_reminders(delegate: &RestGapDelegate) {
+    let commands = with_state(|state| state.scheduler.resume(&SystemClock));
+    execute(delegate, commands);
 }
@@ -15,11 +15,17 @@ use objc2_web_kit::{
     WKNavigationAction, WKNavigationActionPolicy, WKNavigationDelegate, WKWebView,
 };
 
+use super::super::config::Theme;
 use super::super::delegate::RestGapDelegate;
-use super::super::state::with_state;
+use super::super::state::{AppState, with_state, with_state_ref};
 use super::super::utils::{format_countdown, play_sound};
 use super::status_bar::target_anyobject;
-use crate::i18n::Texts;
+use crate::bridge::{self, BridgeCommand};
+use crate::i18n::{Language, Texts};
+use crate::scheduler::BreakKind;
+
+/// 庆祝画面的展示时长
+const CELEBRATION_DURATION: Duration = Duration::from_secs(4);
 
 const COUNTDOWN_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
 <html class="__THEME_CLASS__">
@@ -319,6 +325,10 @@ const COUNTDOWN_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
             z-index: 100;
             display: none;
         }
+        .postpone-btn {
+            right: auto;
+            left: 32px;
+        }
         .skip-btn:hover {
             color: var(--text);
             border-color: var(--skip-btn-hover-border);
@@ -446,6 +456,21 @@ const COUNTDOWN_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
             transform: translateY(-1px);
         }
 
+        /* 达成目标或解锁成就时的庆祝画面 */
+        body.celebrating .skip-btn,
+        body.celebrating .exercise-card,
+        body.celebrating .skip-modal {
+            display: none !important;
+        }
+        body.celebrating .countdown {
+            animation: celebrate-pop 0.6s ease-out;
+        }
+        @keyframes celebrate-pop {
+            0% { transform: scale(0.6); opacity: 0; }
+            70% { transform: scale(1.1); opacity: 1; }
+            100% { transform: scale(1); }
+        }
+
         /* 响应式适配 */
         @media (max-width: 768px) {
             .exercise-card {
@@ -468,6 +493,7 @@ const COUNTDOWN_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
 <body>
 
     <div class="skip-btn" id="skip-btn" onclick="openSkipModal()">Skip</div>
+    <div class="skip-btn postpone-btn" id="postpone-btn" onclick="postponeBreak()">__POSTPONE_LABEL__</div>
 
     <div class="container">
         <div class="timer-group">
@@ -509,6 +535,7 @@ const COUNTDOWN_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
 
 <script>
     const skipEnabled = __SKIP_ENABLED__;
+    const postponeEnabled = __POSTPONE_ENABLED__;
     const currentLang = "__LANG__";
 
     window.addEventListener('contextmenu', (e) => e.preventDefault());
@@ -524,6 +551,12 @@ const COUNTDOWN_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
         const el = document.getElementById('hint');
         if (el) el.textContent = v;
     };
+    window.celebrate = (title, hint) => {
+        document.body.classList.add('celebrating');
+        window.setCountdown('🎉');
+        window.setTitle(title);
+        window.setHint(hint);
+    };
 
     // 配置跳过按钮
     if (skipEnabled) {
@@ -534,6 +567,18 @@ const COUNTDOWN_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
         }
     }
 
+    // 配置推迟按钮
+    if (postponeEnabled) {
+        const postponeBtn = document.getElementById('postpone-btn');
+        if (postponeBtn) {
+            postponeBtn.style.display = 'block';
+        }
+    }
+
+    window.postponeBreak = () => {
+        window.location.href = '__POSTPONE_URL__';
+    };
+
     // 训练动作数据
     const exercises = [
         {
@@ -752,7 +797,7 @@ const COUNTDOWN_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
     };
 
     window.confirmSkip = () => {
-        window.location.href = 'restgap://skip';
+        window.location.href = '__SKIP_URL__';
     };
 </script>
 </body>
@@ -779,6 +824,7 @@ fn build_countdown_html(
     countdown: &str,
     hint: &str,
     skip_enabled: bool,
+    postpone_label: Option<&str>,
     lang: &str,
     theme: crate::macos::config::Theme,
 ) -> String {
@@ -790,6 +836,20 @@ fn build_countdown_html(
         "__SKIP_ENABLED__",
         if skip_enabled { "true" } else { "false" },
     );
+    html = html.replace(
+        "__POSTPONE_ENABLED__",
+        if postpone_label.is_some() {
+            "true"
+        } else {
+            "false"
+        },
+    );
+    html = html.replace(
+        "__POSTPONE_LABEL__",
+        &escape_html(postpone_label.unwrap_or_default()),
+    );
+    html = html.replace("__SKIP_URL__", &BridgeCommand::Skip.to_url());
+    html = html.replace("__POSTPONE_URL__", &BridgeCommand::Postpone.to_url());
     html = html.replace("__LANG__", lang);
     html.replace(
         "__THEME_CLASS__",
@@ -867,14 +927,13 @@ define_class!(
             };
 
             if let Some(url_str) = url_str {
-                if url_str == "restgap://skip" {
-                    if let Some(mtm) = MainThreadMarker::new() {
-                        let app = NSApplication::sharedApplication(mtm);
-                        if let Some(delegate) = app.delegate() {
-                            unsafe {
-                                let _: () = msg_send![&delegate, skipBreak: Option::<&objc2::runtime::AnyObject>::None];
-                            }
+                if bridge::is_bridge_url(&url_str) {
+                    match BridgeCommand::parse(&url_str) {
+                        Ok(command @ (BridgeCommand::Skip | BridgeCommand::Postpone)) => {
+                            send_to_delegate(&command);
                         }
+                        Ok(other) => crate::log_warn!("倒计时页不支持指令 {}", other.name()),
+                        Err(err) => crate::log_warn!("忽略倒计时页指令 {url_str}: {err}"),
                     }
                     decision_handler.call((WKNavigationActionPolicy::Cancel,));
                     return;
@@ -886,18 +945,74 @@ define_class!(
     }
 );
 
+/// 把页面指令转发给应用代理对应的菜单动作
+fn send_to_delegate(command: &BridgeCommand) {
+    let Some(mtm) = MainThreadMarker::new() else {
+        return;
+    };
+    let app = NSApplication::sharedApplication(mtm);
+    let Some(delegate) = app.delegate() else {
+        return;
+    };
+    let sender = Option::<&objc2::runtime::AnyObject>::None;
+    unsafe {
+        match command {
+            BridgeCommand::Skip => {
+                let _: () = msg_send![&delegate, skipBreak: sender];
+            }
+            BridgeCommand::Postpone => {
+                let _: () = msg_send![&delegate, postponeBreak: sender];
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 倒计时窗口中取决于设置的内容；设置变化后只有这些内容不同时才需要重建窗口
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CountdownAppearance {
+    language: Language,
+    allow_skip_break: bool,
+    theme: Theme,
+    /// 可推迟时为（推迟分钟数, 剩余次数）
+    postpone: Option<(u64, u32)>,
+}
+
+impl CountdownAppearance {
+    /// 按当前配置与调度状态计算
+    pub fn of(state: &AppState) -> Self {
+        let postpone = state.scheduler.can_postpone().then(|| {
+            (
+                state.config.postpone_minutes,
+                state.scheduler.postpones_left(),
+            )
+        });
+        Self {
+            language: state.config.effective_language(),
+            allow_skip_break: state.config.allow_skip_break,
+            theme: state.config.theme,
+            postpone,
+        }
+    }
+}
+
 /// 显示倒计时窗口
 #[allow(clippy::too_many_lines)]
-pub fn show_countdown_window(delegate: &RestGapDelegate, seconds: u64, play_start_sound: bool) {
+pub fn show_countdown_window(
+    delegate: &RestGapDelegate,
+    seconds: u64,
+    kind: BreakKind,
+    play_start_sound: bool,
+) {
     let mtm = delegate.mtm();
-    let (texts, allow_skip_break, theme) = with_state(|state| {
-        let language = state.config.effective_language();
-        (
-            Texts::new(language),
-            state.config.allow_skip_break,
-            state.config.theme,
-        )
-    });
+    let appearance = with_state_ref(CountdownAppearance::of);
+    let CountdownAppearance {
+        language,
+        allow_skip_break,
+        theme,
+        postpone,
+    } = appearance;
+    let texts = Texts::new(language);
     let lang_str = match texts.language() {
         crate::i18n::Language::En => "en",
         crate::i18n::Language::Zh => "zh",
@@ -905,6 +1020,7 @@ pub fn show_countdown_window(delegate: &RestGapDelegate, seconds: u64, play_star
 
     // 关闭已存在的倒计时窗口
     close_countdown_window();
+    with_state(|state| state.countdown_appearance = Some(appearance));
 
     // 播放开始声音
     if play_start_sound {
@@ -939,11 +1055,17 @@ pub fn show_countdown_window(delegate: &RestGapDelegate, seconds: u64, play_star
 
     let mut windows = Vec::with_capacity(frames.len());
     let mut webviews = Vec::with_capacity(frames.len());
+    let title = match kind {
+        BreakKind::Short => texts.countdown_title(),
+        BreakKind::Long => texts.long_countdown_title(),
+    };
+    let postpone_label = postpone.map(|(minutes, left)| texts.postpone_title(minutes, left));
     let html = build_countdown_html(
-        &texts.countdown_title(),
+        &title,
         &format_countdown(seconds),
         texts.countdown_hint(),
         allow_skip_break,
+        postpone_label.as_deref(),
         lang_str,
         theme,
     );
@@ -1058,6 +1180,22 @@ pub fn update_countdown() -> bool {
     })
 }
 
+/// 将倒计时重设为剩余 `remaining`；倒计时窗口已关闭时重新弹出
+///
+/// `countdown_end_time` 基于单调时钟，系统睡眠期间不会前进，唤醒后需要由调度器校正。
+pub fn reset_countdown(delegate: &RestGapDelegate, remaining: Duration) {
+    let (reset, kind) = with_state(|state| {
+        let reset = state.countdown_end_time.is_some() && !state.countdown_windows.is_empty();
+        if reset {
+            state.countdown_end_time = Some(Instant::now() + remaining);
+        }
+        (reset, state.scheduler.break_kind())
+    });
+    if !reset {
+        show_countdown_window(delegate, remaining.as_secs(), kind, false);
+    }
+}
+
 /// 关闭倒计时窗口
 pub fn close_countdown_window() {
     with_state(|state| {
@@ -1065,6 +1203,9 @@ pub fn close_countdown_window() {
         if let Some(timer) = state.countdown_timer.take() {
             timer.invalidate();
         }
+        if let Some(timer) = state.celebration_timer.take() {
+            timer.invalidate();
+        }
         // 使用 orderOut 而不是 close，避免触发窗口关闭事件导致应用退出
         for window in state.countdown_windows.drain(..) {
             window.orderOut(None);
@@ -1072,11 +1213,50 @@ pub fn close_countdown_window() {
         state.countdown_webviews.clear();
         state.countdown_end_time = None;
         state.countdown_nav_delegate = None;
+        state.countdown_appearance = None;
     });
 }
 
 /// 完成倒计时
-pub fn finish_countdown() {
-    close_countdown_window();
-    play_sound("Tink");
+///
+/// `celebration` 为庆祝画面的标题与说明：倒计时窗口仍在时先展示一会儿再关闭。
+pub fn finish_countdown(delegate: &RestGapDelegate, celebration: Option<(String, String)>) {
+    let Some((title, hint)) = celebration else {
+        close_countdown_window();
+        play_sound("Tink");
+        return;
+    };
+    let shown = with_state(|state| {
+        if state.countdown_windows.is_empty() {
+            return false;
+        }
+        if let Some(timer) = state.countdown_timer.take() {
+            timer.invalidate();
+        }
+        state.countdown_end_time = None;
+        let title = serde_json::to_string(&title).unwrap_or_else(|_| "\"\"".to_string());
+        let hint = serde_json::to_string(&hint).unwrap_or_else(|_| "\"\"".to_string());
+        let script = NSString::from_str(&format!("window.celebrate({title}, {hint});"));
+        for webview in &state.countdown_webviews {
+            unsafe {
+                webview.evaluateJavaScript_completionHandler(&script, None);
+            }
+        }
+        true
+    });
+    play_sound("Hero");
+    if !shown {
+        return;
+    }
+
+    let timer = unsafe {
+        NSTimer::scheduledTimerWithTimeInterval_target_selector_userInfo_repeats(
+            CELEBRATION_DURATION.as_secs_f64(),
+            target_anyobject(delegate),
+            sel!(celebrationElapsed:),
+            None,
+            false,
+        )
+    };
+    with_state(|state| state.celebration_timer = Some(timer));
 }
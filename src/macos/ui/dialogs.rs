@@ -3,14 +3,23 @@
 use std::process::Command;
 
 use objc2::rc::Retained;
-use objc2::{MainThreadOnly, msg_send};
-use objc2_app_kit::{NSAlert, NSAlertSecondButtonReturn};
-use objc2_foundation::NSString;
+use objc2::runtime::{AnyClass, AnyObject};
+use objc2::{MainThreadMarker, MainThreadOnly, msg_send};
+use objc2_app_kit::{
+    NSAlert, NSAlertFirstButtonReturn, NSAlertSecondButtonReturn, NSModalResponseOK, NSPopUpButton,
+    NSSavePanel, NSTextField, NSView,
+};
+use objc2_foundation::{NSArray, NSPoint, NSRect, NSSize, NSString};
 
 use super::super::constants::APP_NAME_DISPLAY;
 use super::super::delegate::RestGapDelegate;
 use super::super::state::with_state_ref;
+use crate::export::{DEFAULT_DAYS, DateRange, ExportFormat};
+use crate::history::HistoryStore;
 use crate::i18n::Texts;
+use crate::scheduler::{Clock, SystemClock};
+use crate::stats;
+use crate::work_hours::SECS_PER_DAY;
 
 /// 显示关于对话框
 pub fn show_about_dialog(delegate: &RestGapDelegate) {
@@ -30,3 +39,119 @@ pub fn show_about_dialog(delegate: &RestGapDelegate) {
             .spawn();
     }
 }
+
+/// 显示只有“好”按钮的提示框
+pub fn show_message(mtm: MainThreadMarker, title: &str, message: &str) {
+    let texts = Texts::new(with_state_ref(|s| s.config.effective_language()));
+    let alert: Retained<NSAlert> = unsafe { msg_send![NSAlert::alloc(mtm), init] };
+    alert.setMessageText(&NSString::from_str(title));
+    alert.setInformativeText(&NSString::from_str(message));
+    let _ = alert.addButtonWithTitle(&NSString::from_str(texts.ok_button()));
+    let _ = alert.runModal();
+}
+
+/// 选择日期范围和保存位置，把休息历史导出为 CSV 或 iCalendar 文件
+pub fn show_export_history_dialog(delegate: &RestGapDelegate) {
+    let mtm = delegate.mtm();
+    let texts = Texts::new(with_state_ref(|s| s.config.effective_language()));
+    let Some(history) = HistoryStore::default_location() else {
+        crate::log_warn!("无法确定休息历史的位置，跳过导出");
+        return;
+    };
+    let utc_offset = SystemClock.utc_offset();
+    let today = SystemClock.local_secs().div_euclid(SECS_PER_DAY);
+    let default = DateRange::last_days(today, DEFAULT_DAYS);
+
+    // 两个日期输入框并排放在提示框的附加视图中
+    let field = |day: i64, x: f64| {
+        let field =
+            NSTextField::textFieldWithString(&NSString::from_str(&stats::format_date(day)), mtm);
+        field.setFrame(NSRect::new(NSPoint::new(x, 0.0), NSSize::new(110.0, 24.0)));
+        field
+    };
+    let from_field = field(default.from, 0.0);
+    let to_field = field(default.to, 120.0);
+    let format_popup = NSPopUpButton::initWithFrame_pullsDown(
+        NSPopUpButton::alloc(mtm),
+        NSRect::new(NSPoint::new(240.0, -2.0), NSSize::new(190.0, 26.0)),
+        false,
+    );
+    for format in ExportFormat::ALL {
+        format_popup.addItemWithTitle(&NSString::from_str(texts.export_format_name(format)));
+    }
+    let accessory = NSView::initWithFrame(
+        NSView::alloc(mtm),
+        NSRect::new(NSPoint::new(0.0, 0.0), NSSize::new(430.0, 24.0)),
+    );
+    accessory.addSubview(&from_field);
+    accessory.addSubview(&to_field);
+    accessory.addSubview(&format_popup);
+
+    let alert: Retained<NSAlert> = unsafe { msg_send![NSAlert::alloc(mtm), init] };
+    alert.setMessageText(&NSString::from_str(texts.export_history_title()));
+    alert.setInformativeText(&NSString::from_str(texts.export_history_message()));
+    alert.setAccessoryView(Some(&accessory));
+    let _ = alert.addButtonWithTitle(&NSString::from_str(texts.settings_export_button()));
+    let _ = alert.addButtonWithTitle(&NSString::from_str(texts.settings_cancel_button()));
+    if alert.runModal() != NSAlertFirstButtonReturn {
+        return;
+    }
+
+    let range = match DateRange::parse(
+        &from_field.stringValue().to_string(),
+        &to_field.stringValue().to_string(),
+    ) {
+        Ok(range) => range,
+        Err(err) => {
+            show_message(mtm, texts.export_history_failed_title(), &err.to_string());
+            return;
+        }
+    };
+    let format = usize::try_from(format_popup.indexOfSelectedItem())
+        .ok()
+        .and_then(|index| ExportFormat::ALL.get(index).copied())
+        .unwrap_or(ExportFormat::Csv);
+
+    let panel = NSSavePanel::savePanel(mtm);
+    panel.setNameFieldStringValue(&NSString::from_str(&format!(
+        "restgap-history-{}-{}.{}",
+        stats::format_date(range.from),
+        stats::format_date(range.to),
+        format.extension()
+    )));
+    allow_only_extension(&panel, format.extension());
+    if panel.runModal() != NSModalResponseOK {
+        return;
+    }
+    let Some(path) = panel.URL().and_then(|url| url.path()) else {
+        return;
+    };
+    let path = path.to_string();
+
+    let (start, end) = range.bounds(utc_offset);
+    let result = history
+        .read_range(start, end)
+        .and_then(|records| std::fs::write(&path, format.render(&records, utc_offset, &texts)));
+    match result {
+        Ok(()) => crate::log_info!("已导出休息历史: {path}"),
+        Err(err) => {
+            crate::log_error!("导出休息历史失败: {err}");
+            show_message(mtm, texts.export_history_failed_title(), &err.to_string());
+        }
+    }
+}
+
+/// 只允许保存为 `extension` 对应的文件类型；用户改动扩展名时由保存面板纠正
+fn allow_only_extension(panel: &NSSavePanel, extension: &str) {
+    // 未引入 UniformTypeIdentifiers 的绑定，按类名取得 UTType
+    let Some(class) = AnyClass::get(c"UTType") else {
+        return;
+    };
+    let extension = NSString::from_str(extension);
+    let content_type: Option<Retained<AnyObject>> =
+        unsafe { msg_send![class, typeWithFilenameExtension: &*extension] };
+    if let Some(content_type) = content_type {
+        let types = NSArray::from_retained_slice(&[content_type]);
+        let _: () = unsafe { msg_send![panel, setAllowedContentTypes: &*types] };
+    }
+}
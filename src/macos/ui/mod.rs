@@ -7,10 +7,13 @@ pub mod dialogs;
 pub mod settings;
 pub mod status_bar;
 
-pub use countdown::{finish_countdown, show_countdown_window, update_countdown};
-pub use dialogs::show_about_dialog;
+pub use countdown::{
+    CountdownAppearance, close_countdown_window, finish_countdown, reset_countdown,
+    show_countdown_window, update_countdown,
+};
+pub use dialogs::{show_about_dialog, show_export_history_dialog};
 pub use settings::open_settings_dialog;
 pub use status_bar::{
-    refresh_header_title, refresh_menu_info, refresh_static_menu_titles, refresh_status_title,
-    setup_status_item, target_anyobject,
+    pause_choice, refresh_header_title, refresh_menu_info, refresh_profile_menu,
+    refresh_static_menu_titles, refresh_status_title, setup_status_item, target_anyobject,
 };
@@ -2,20 +2,31 @@
 //!
 //! 使用 `WKWebView` 实现现代化的设置界面。
 
+use std::time::Duration;
+
 use objc2::rc::Retained;
 use objc2::runtime::ProtocolObject;
 use objc2::{MainThreadMarker, MainThreadOnly, define_class, msg_send};
-use objc2_app_kit::{NSApplication, NSBackingStoreType, NSColor, NSWindow, NSWindowStyleMask};
+use objc2_app_kit::{
+    NSApplication, NSBackingStoreType, NSColor, NSModalResponseOK, NSOpenPanel, NSSavePanel,
+    NSWindow, NSWindowStyleMask,
+};
 use objc2_foundation::{NSObject, NSObjectProtocol, NSPoint, NSRect, NSSize, NSString};
 use objc2_web_kit::{
     WKNavigationAction, WKNavigationActionPolicy, WKNavigationDelegate, WKWebView,
     WKWebViewConfiguration,
 };
 
-use super::super::config::{Config, Theme, clamp_u64};
+use super::super::config::{self, Config, ImportError, Theme};
 use super::super::delegate::RestGapDelegate;
 use super::super::state::{with_state, with_state_ref};
+use super::dialogs::show_message;
+use crate::bridge::{self, BridgeCommand};
+use crate::config::{IdlePolicyKind, RejectReason, Rejected};
+use crate::error::AppError;
 use crate::i18n::{LanguagePreference, Texts};
+use crate::idle::IdlePolicy;
+use crate::work_hours::WorkHours;
 
 define_class!(
     #[unsafe(super(NSWindow))]
@@ -61,8 +72,11 @@ define_class!(
             };
 
             if let Some(url_str) = url_str {
-                if url_str.starts_with("restgap://") {
-                    handle_callback(&url_str);
+                if bridge::is_bridge_url(&url_str) {
+                    match BridgeCommand::parse(&url_str) {
+                        Ok(command) => handle_command(command),
+                        Err(err) => crate::log_warn!("忽略设置页指令 {url_str}: {err}"),
+                    }
                     decision_handler.call((WKNavigationActionPolicy::Cancel,));
                     return;
                 }
@@ -73,77 +87,319 @@ define_class!(
     }
 );
 
-fn handle_callback(url: &str) {
-    if let Some(query) = url.strip_prefix("restgap://save?") {
-        let mut interval_minutes = None;
-        let mut break_seconds = None;
-        let mut language = None;
-        let mut allow_skip_break = None;
-        let mut theme = None;
-
-        for pair in query.split('&') {
-            let mut parts = pair.split('=');
-            let key = parts.next().unwrap_or("");
-            let val = parts.next().unwrap_or("");
-            match key {
-                "interval" => interval_minutes = val.parse::<u64>().ok(),
-                "break" => break_seconds = val.parse::<u64>().ok(),
-                "language" => {
-                    language = match val {
-                        "0" => Some(LanguagePreference::Auto),
-                        "1" => Some(LanguagePreference::En),
-                        "2" => Some(LanguagePreference::Zh),
-                        _ => None,
-                    };
-                }
-                "allow_skip" => allow_skip_break = Some(val == "true"),
-                "theme" => {
-                    theme = match val {
-                        "0" => Some(Theme::Dark),
-                        "1" => Some(Theme::Light),
-                        _ => None,
-                    };
-                }
-                _ => {}
+fn handle_command(command: BridgeCommand) {
+    match command {
+        BridgeCommand::Save(params) => save_settings(&params),
+        BridgeCommand::Cancel => close_settings_window(),
+        BridgeCommand::Export => {
+            if let Some(mtm) = MainThreadMarker::new() {
+                export_config(mtm);
+            }
+        }
+        BridgeCommand::Import => {
+            if let Some(mtm) = MainThreadMarker::new() {
+                import_config(mtm);
+            }
+        }
+        other => crate::log_warn!("设置页不支持指令 {}", other.name()),
+    }
+}
+
+fn save_settings(params: &[(String, String)]) {
+    let current = with_state_ref(|state| state.config.clone());
+    let texts = Texts::new(current.effective_language());
+    match parse_settings(params, current, &texts) {
+        Ok(new_config) => {
+            apply_config(new_config);
+            close_settings_window();
+        }
+        // 有不合法的输入时不保存，在设置页中逐项提示
+        Err(errors) => {
+            for err in &errors {
+                crate::log_warn!("{err}");
             }
+            show_field_errors(&errors, &texts);
         }
+    }
+}
+
+/// 把设置页提交的参数应用到当前配置上
+///
+/// 不做范围限制：格式错误、无法识别和超出范围的值都作为错误返回，由调用方拒绝保存。
+fn parse_settings(
+    params: &[(String, String)],
+    current: Config,
+    texts: &Texts,
+) -> Result<Config, Vec<AppError>> {
+    let mut config = current;
+    let mut errors = Vec::new();
+    let mut idle_policy_kind = None;
+    let mut idle_tolerance = config.idle_tolerance_seconds();
+    let mut idle_percent = config.idle_percent();
+    let mut profile_name = None;
 
-        if let (Some(interval), Some(brk), Some(lang), Some(skip)) =
-            (interval_minutes, break_seconds, language, allow_skip_break)
-        {
-            let new_config = Config {
-                interval_minutes: clamp_u64(
-                    interval,
-                    Config::MIN_INTERVAL_MINUTES,
-                    Config::MAX_INTERVAL_MINUTES,
-                ),
-                break_seconds: clamp_u64(brk, Config::MIN_BREAK_SECONDS, Config::MAX_BREAK_SECONDS),
-                language: lang,
-                allow_skip_break: skip,
-                theme: theme.unwrap_or(Theme::Dark),
+    for (key, val) in params {
+        let key = key.as_str();
+        let reject = |field: &str, reason| {
+            AppError::Config(Rejected {
+                field: field.to_string(),
+                value: format!("{val:?}"),
+                reason,
+            })
+        };
+
+        let number = match key {
+            "interval" => Some(("interval_minutes", &mut config.interval_minutes)),
+            "break" => Some(("break_seconds", &mut config.break_seconds)),
+            "long_every" => Some(("long_break_every", &mut config.long_break_every)),
+            "long_break" => Some(("long_break_seconds", &mut config.long_break_seconds)),
+            "postpone" => Some(("postpone_minutes", &mut config.postpone_minutes)),
+            "max_postpones" => Some(("max_postpones", &mut config.max_postpones)),
+            "pre_break" => Some(("pre_break_seconds", &mut config.pre_break_seconds)),
+            "daily_goal" => Some(("daily_goal", &mut config.daily_goal)),
+            "active_threshold" => Some((
+                "active_idle_threshold_seconds",
+                &mut config.active_idle_threshold_seconds,
+            )),
+            "idle_tolerance" => Some(("idle_tolerance_seconds", &mut idle_tolerance)),
+            "idle_percent" => Some(("idle_percent", &mut idle_percent)),
+            _ => None,
+        };
+        if let Some((field, target)) = number {
+            match val.trim().parse() {
+                Ok(value) => *target = value,
+                Err(_) => errors.push(reject(
+                    field,
+                    RejectReason::Invalid(texts.settings_not_a_number().to_string()),
+                )),
+            }
+            continue;
+        }
+
+        match key {
+            "idle_policy" => match val.parse::<i64>() {
+                Ok(kind @ 0..=3) => idle_policy_kind = Some(IdlePolicyKind::from_code(kind)),
+                _ => errors.push(reject(
+                    "idle_policy",
+                    RejectReason::OutOfRange { min: 0, max: 3 },
+                )),
+            },
+            "language" => match val.as_str() {
+                "0" => config.language = LanguagePreference::Auto,
+                "1" => config.language = LanguagePreference::En,
+                "2" => config.language = LanguagePreference::Zh,
+                _ => errors.push(reject(
+                    "language",
+                    RejectReason::OutOfRange { min: 0, max: 2 },
+                )),
+            },
+            "theme" => match val.as_str() {
+                "0" => config.theme = Theme::Dark,
+                "1" => config.theme = Theme::Light,
+                _ => errors.push(reject("theme", RejectReason::OutOfRange { min: 0, max: 1 })),
+            },
+            "work_hours" => match WorkHours::parse(val) {
+                Ok(hours) => config.work_hours = hours,
+                Err(msg) => errors.push(reject("work_hours", RejectReason::Invalid(msg))),
+            },
+            "allow_skip" => config.allow_skip_break = val == "true",
+            "work_hours_enabled" => config.work_hours_enabled = val == "true",
+            "active_time" => config.active_time = val == "true",
+            "extend_breaks" => config.extend_active_breaks = val == "true",
+            "await_return" => config.await_return = val == "true",
+            "natural_break" => config.natural_break = val == "true",
+            "profile" => profile_name = Some(val.clone()),
+            _ => errors.push(reject(key, RejectReason::UnknownField)),
+        }
+    }
+
+    if let Some(kind) = idle_policy_kind {
+        config.idle_policy = match kind {
+            IdlePolicyKind::Disabled => IdlePolicy::Disabled,
+            IdlePolicyKind::BreakLength => IdlePolicy::BreakLength,
+            IdlePolicyKind::Tolerance => IdlePolicy::Tolerance(Duration::from_secs(idle_tolerance)),
+            // 超出 u8 的值交给 validate 按上限报告
+            IdlePolicyKind::Percent => {
+                IdlePolicy::Percent(u8::try_from(idle_percent).unwrap_or(u8::MAX))
+            }
+        };
+    }
+
+    if let Err(invalid) = config.validate() {
+        errors.extend(invalid);
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    // 以方案名称保存当前时长；清空名称则不再使用任何方案
+    if let Some(name) = profile_name {
+        config.save_profile(&name);
+    }
+    Ok(config)
+}
+
+/// 配置字段在设置页中对应的输入框 id；没有对应输入框的错误显示在页脚
+fn input_id(field: &str) -> Option<&'static str> {
+    Some(match field {
+        "interval_minutes" => "interval",
+        "break_seconds" => "break",
+        "long_break_every" => "long_every",
+        "long_break_seconds" => "long_break",
+        "postpone_minutes" => "postpone",
+        "max_postpones" => "max_postpones",
+        "pre_break_seconds" => "pre_break",
+        "daily_goal" => "daily_goal",
+        "active_idle_threshold_seconds" => "active_threshold",
+        "idle_tolerance_seconds" => "idle_tolerance",
+        "idle_percent" => "idle_percent",
+        "idle_policy" => "idle_policy",
+        "work_hours" => "work_hours",
+        "language" => "language",
+        "theme" => "theme",
+        _ => return None,
+    })
+}
+
+/// 在设置页中标出不合法的输入并显示原因
+fn show_field_errors(errors: &[AppError], texts: &Texts) {
+    let items: Vec<serde_json::Value> = errors
+        .iter()
+        .filter_map(|err| match err {
+            AppError::Config(rejected) => Some(rejected),
+            _ => None,
+        })
+        .map(|rejected| {
+            let message = match input_id(&rejected.field) {
+                Some(_) => texts.rejected_reason(&rejected.reason),
+                None => texts.import_rejected_line(rejected),
             };
-            new_config.save();
+            serde_json::json!({ "id": input_id(&rejected.field), "message": message })
+        })
+        .collect();
+    let script = format!("showErrors({});", serde_json::Value::Array(items));
+    with_state_ref(|state| {
+        if let Some(webview) = &state.settings_webview {
+            unsafe {
+                webview.evaluateJavaScript_completionHandler(&NSString::from_str(&script), None);
+            }
+        }
+    });
+}
 
-            with_state(|state| {
-                state.config = new_config;
-            });
+/// 保存新配置并通知 delegate 应用
+fn apply_config(new_config: Config) {
+    if let Err(err) = config::store().save(&new_config) {
+        crate::log_error!("保存配置失败: {err}");
+    }
 
-            // 通知 delegate 更新
-            if let Some(mtm) = MainThreadMarker::new() {
-                let app = NSApplication::sharedApplication(mtm);
-                if let Some(delegate) = app.delegate() {
-                    unsafe {
-                        let _: () = msg_send![&delegate, settingsChanged];
-                    }
-                }
+    with_state(|state| {
+        state.config = new_config;
+    });
+
+    // 通知 delegate 更新
+    if let Some(mtm) = MainThreadMarker::new() {
+        let app = NSApplication::sharedApplication(mtm);
+        if let Some(delegate) = app.delegate() {
+            unsafe {
+                let _: () = msg_send![&delegate, settingsChanged];
+            }
+        }
+    }
+}
+
+/// 导出文件的默认文件名
+const EXPORT_FILE_NAME: &str = "restgap-config.json";
+
+/// 把已保存的配置导出为 JSON 文件
+fn export_config(mtm: MainThreadMarker) {
+    let config = with_state_ref(|state| state.config.clone());
+    let texts = Texts::new(config.effective_language());
+
+    let panel = NSSavePanel::savePanel(mtm);
+    panel.setNameFieldStringValue(&NSString::from_str(EXPORT_FILE_NAME));
+    if panel.runModal() != NSModalResponseOK {
+        return;
+    }
+    let Some(path) = panel.URL().and_then(|url| url.path()) else {
+        return;
+    };
+    if let Err(err) = std::fs::write(path.to_string(), config.to_json()) {
+        crate::log_error!("导出配置失败: {err}");
+        show_message(mtm, texts.export_failed_title(), &err.to_string());
+    }
+}
+
+/// 从 JSON 文件导入配置；有值被拒绝时逐条列出，不做任何修改
+fn import_config(mtm: MainThreadMarker) {
+    let texts = Texts::new(with_state_ref(|state| state.config.effective_language()));
+
+    let panel = NSOpenPanel::openPanel(mtm);
+    panel.setCanChooseDirectories(false);
+    panel.setAllowsMultipleSelection(false);
+    if panel.runModal() != NSModalResponseOK {
+        return;
+    }
+    let Some(path) = panel.URL().and_then(|url| url.path()) else {
+        return;
+    };
+
+    let imported = std::fs::read_to_string(path.to_string())
+        .map_err(|err| err.to_string())
+        .and_then(|text| {
+            Config::from_json(&text).map_err(|err| match err {
+                ImportError::Parse(err) => err.to_string(),
+                ImportError::Rejected(rejected) => rejected
+                    .iter()
+                    .map(|r| texts.import_rejected_line(r))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            })
+        });
+    match imported {
+        Ok(new_config) => {
+            crate::log_info!("已导入配置: {path}");
+            apply_config(new_config);
+            reload_settings_page();
+        }
+        Err(msg) => {
+            crate::log_warn!("导入配置被拒绝: {msg}");
+            show_message(mtm, texts.import_failed_title(), &msg);
+        }
+    }
+}
+
+/// 按当前配置重新渲染设置页
+fn reload_settings_page() {
+    let config = with_state_ref(|state| state.config.clone());
+    let html = settings_html(&config);
+    with_state_ref(|state| {
+        if let Some(window) = &state.settings_window {
+            window.setBackgroundColor(Some(&background_color(config.theme)));
+        }
+        if let Some(webview) = &state.settings_webview {
+            unsafe {
+                let _ = webview.loadHTMLString_baseURL(&NSString::from_str(&html), None);
             }
         }
-        close_settings_window();
-    } else if url == "restgap://cancel" {
-        close_settings_window();
+    });
+}
+
+fn background_color(theme: Theme) -> Retained<NSColor> {
+    match theme {
+        Theme::Dark => NSColor::colorWithSRGBRed_green_blue_alpha(0.11, 0.11, 0.12, 1.0),
+        Theme::Light => NSColor::colorWithSRGBRed_green_blue_alpha(0.95, 0.95, 0.97, 1.0),
     }
 }
 
+/// 转义插入到 HTML 属性中的文本
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
 pub fn close_settings_window() {
     with_state(|state| {
         if let Some(window) = state.settings_window.take() {
@@ -195,7 +451,8 @@ const SETTINGS_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
             color: var(--text);
             font-family: var(--font-sans);
             user-select: none;
-            overflow: hidden;
+            overflow-x: hidden;
+            overflow-y: auto;
             -webkit-font-smoothing: antialiased;
         }
         .header {
@@ -315,7 +572,20 @@ const SETTINGS_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
             outline: none;
             transition: all 0.2s;
         }
-        input[type="number"]:focus {
+        input[type="text"] {
+            background: var(--input-bg);
+            border: 1px solid var(--input-border);
+            border-radius: 8px;
+            color: var(--text);
+            padding: 6px 10px;
+            width: 100%;
+            font-size: 13px;
+            font-variant-numeric: tabular-nums;
+            outline: none;
+            transition: all 0.2s;
+        }
+        input[type="number"]:focus,
+        input[type="text"]:focus {
             background: var(--card-hover);
             border-color: var(--accent);
             box-shadow: 0 0 0 3px rgba(10, 132, 255, 0.2);
@@ -370,6 +640,15 @@ const SETTINGS_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
             gap: 12px;
             margin-top: 12px;
         }
+        .footer .spacer { flex: 1; }
+        .error {
+            color: #FF453A;
+            font-size: 12px;
+            padding: 0 14px 10px;
+        }
+        .invalid {
+            border-color: #FF453A !important;
+        }
         button {
             padding: 10px 20px;
             border-radius: 10px;
@@ -427,10 +706,184 @@ const SETTINGS_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
                 <span class="hint">sec</span>
             </div>
         </div>
+        <div class="row">
+            <div class="row-info">
+                <div class="label" id="t-long-every-label">__LONG_EVERY_LABEL__</div>
+                <div class="hint">0 disables long breaks</div>
+            </div>
+            <div class="control">
+                <input type="number" id="long_every" value="__LONG_EVERY_VAL__" min="0" max="12">
+                <span class="hint">×</span>
+            </div>
+        </div>
+        <div class="row">
+            <div class="row-info">
+                <div class="label" id="t-long-break-label">__LONG_BREAK_LABEL__</div>
+                <div class="hint">Duration of each long break</div>
+            </div>
+            <div class="control">
+                <input type="number" id="long_break" value="__LONG_BREAK_VAL__" min="5" max="3600">
+                <span class="hint">sec</span>
+            </div>
+        </div>
+        <div class="row">
+            <div class="row-info">
+                <div class="label" id="t-profile-label">__PROFILE_LABEL__</div>
+                <div class="hint">__PROFILE_HINT__</div>
+            </div>
+        </div>
+        <div class="row">
+            <input type="text" id="profile" value="__PROFILE_VAL__" placeholder="Deep work" spellcheck="false">
+        </div>
+    </div>
+
+    <div class="section-label">Postpone</div>
+    <div class="group">
+        <div class="row">
+            <div class="row-info">
+                <div class="label" id="t-pre-break-label">__PRE_BREAK_LABEL__</div>
+                <div class="hint">0 starts breaks without warning</div>
+            </div>
+            <div class="control">
+                <input type="number" id="pre_break" value="__PRE_BREAK_VAL__" min="0" max="300">
+                <span class="hint">sec</span>
+            </div>
+        </div>
+        <div class="row">
+            <div class="row-info">
+                <div class="label" id="t-postpone-label">__POSTPONE_LABEL__</div>
+                <div class="hint">Delay a break that fires at a bad moment</div>
+            </div>
+            <div class="control">
+                <input type="number" id="postpone" value="__POSTPONE_VAL__" min="1" max="60">
+                <span class="hint">min</span>
+            </div>
+        </div>
+        <div class="row">
+            <div class="row-info">
+                <div class="label" id="t-max-postpones-label">__MAX_POSTPONES_LABEL__</div>
+                <div class="hint">0 disables postponing</div>
+            </div>
+            <div class="control">
+                <input type="number" id="max_postpones" value="__MAX_POSTPONES_VAL__" min="0" max="10">
+                <span class="hint">×</span>
+            </div>
+        </div>
+    </div>
+
+    <div class="section-label">Schedule</div>
+    <div class="group">
+        <div class="row">
+            <div class="row-info">
+                <div class="label" id="t-work-hours-label">__WORK_HOURS_LABEL__</div>
+                <div class="hint">Outside these hours the timer stays paused</div>
+            </div>
+            <label class="switch">
+                <input type="checkbox" id="work_hours_enabled" __WORK_HOURS_CHECKED__>
+                <span class="slider"></span>
+            </label>
+        </div>
+        <div class="row">
+            <input type="text" id="work_hours" value="__WORK_HOURS_VAL__" placeholder="__WORK_HOURS_HINT__" spellcheck="false">
+        </div>
+    </div>
+
+    <div class="section-label">Idle</div>
+    <div class="group">
+        <div class="row">
+            <div class="row-info">
+                <div class="label" id="t-active-time-label">__ACTIVE_TIME_LABEL__</div>
+                <div class="hint">Reading on paper won't count as screen time</div>
+            </div>
+            <label class="switch">
+                <input type="checkbox" id="active_time" __ACTIVE_TIME_CHECKED__>
+                <span class="slider"></span>
+            </label>
+        </div>
+        <div class="row">
+            <div class="row-info">
+                <div class="label" id="t-active-threshold-label">__ACTIVE_THRESHOLD_LABEL__</div>
+            </div>
+            <div class="control">
+                <input type="number" id="active_threshold" value="__ACTIVE_THRESHOLD_VAL__" min="30" max="1800">
+                <span class="hint">sec</span>
+            </div>
+        </div>
+        <div class="row">
+            <div class="row-info">
+                <div class="label" id="t-extend-breaks-label">__EXTEND_BREAKS_LABEL__</div>
+                <div class="hint">Adds the time spent at the keyboard once</div>
+            </div>
+            <label class="switch">
+                <input type="checkbox" id="extend_breaks" __EXTEND_BREAKS_CHECKED__>
+                <span class="slider"></span>
+            </label>
+        </div>
+        <div class="row">
+            <div class="row-info">
+                <div class="label" id="t-natural-break-label">__NATURAL_BREAK_LABEL__</div>
+                <div class="hint">__NATURAL_BREAK_HINT__</div>
+            </div>
+            <label class="switch">
+                <input type="checkbox" id="natural_break" __NATURAL_BREAK_CHECKED__>
+                <span class="slider"></span>
+            </label>
+        </div>
+        <div class="row">
+            <div class="row-info">
+                <div class="label" id="t-await-return-label">__AWAIT_RETURN_LABEL__</div>
+                <div class="hint">__AWAIT_RETURN_HINT__</div>
+            </div>
+            <label class="switch">
+                <input type="checkbox" id="await_return" __AWAIT_RETURN_CHECKED__>
+                <span class="slider"></span>
+            </label>
+        </div>
+        <div class="row">
+            <div class="row-info">
+                <div class="label" id="t-idle-policy-label">__IDLE_POLICY_LABEL__</div>
+            </div>
+            <select id="idle_policy">
+                <option value="0" __IDLE_POLICY_0_SELECTED__>__IDLE_POLICY_0__</option>
+                <option value="1" __IDLE_POLICY_1_SELECTED__>__IDLE_POLICY_1__</option>
+                <option value="2" __IDLE_POLICY_2_SELECTED__>__IDLE_POLICY_2__</option>
+                <option value="3" __IDLE_POLICY_3_SELECTED__>__IDLE_POLICY_3__</option>
+            </select>
+        </div>
+        <div class="row">
+            <div class="row-info">
+                <div class="label" id="t-idle-tolerance-label">__IDLE_TOLERANCE_LABEL__</div>
+                <div class="hint">Used by the tolerance rule</div>
+            </div>
+            <div class="control">
+                <input type="number" id="idle_tolerance" value="__IDLE_TOLERANCE_VAL__" min="1" max="300">
+                <span class="hint">sec</span>
+            </div>
+        </div>
+        <div class="row">
+            <div class="row-info">
+                <div class="label" id="t-idle-percent-label">__IDLE_PERCENT_LABEL__</div>
+                <div class="hint">Used by the share rule</div>
+            </div>
+            <div class="control">
+                <input type="number" id="idle_percent" value="__IDLE_PERCENT_VAL__" min="50" max="100">
+                <span class="hint">%</span>
+            </div>
+        </div>
     </div>
 
     <div class="section-label">Options</div>
     <div class="group">
+        <div class="row">
+            <div class="row-info">
+                <div class="label" id="t-daily-goal-label">__DAILY_GOAL_LABEL__</div>
+                <div class="hint">0 disables the goal and streak</div>
+            </div>
+            <div class="control">
+                <input type="number" id="daily_goal" value="__DAILY_GOAL_VAL__" min="0" max="48">
+                <span class="hint">×</span>
+            </div>
+        </div>
         <div class="row">
             <div class="row-info">
                 <div class="label" id="t-skip-label">__SKIP_LABEL__</div>
@@ -465,6 +918,9 @@ const SETTINGS_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
     </div>
 
     <div class="footer">
+        <button class="btn-secondary" onclick="importConfig()" id="t-import">__IMPORT__</button>
+        <button class="btn-secondary" onclick="exportConfig()" id="t-export">__EXPORT__</button>
+        <div class="spacer"></div>
         <button class="btn-secondary" onclick="cancel()" id="t-cancel">__CANCEL__</button>
         <button class="btn-primary" onclick="save()" id="t-save">__SAVE__</button>
     </div>
@@ -473,13 +929,52 @@ const SETTINGS_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
         function save() {
             const interval = document.getElementById('interval').value;
             const breakVal = document.getElementById('break').value;
+            const longEvery = document.getElementById('long_every').value;
+            const longBreak = document.getElementById('long_break').value;
+            const postpone = document.getElementById('postpone').value;
+            const maxPostpones = document.getElementById('max_postpones').value;
+            const preBreak = document.getElementById('pre_break').value;
             const language = document.getElementById('language').value;
             const allowSkip = document.getElementById('allow_skip').checked;
             const theme = document.getElementById('theme').value;
-            window.location.href = `restgap://save?interval=${interval}&break=${breakVal}&language=${language}&allow_skip=${allowSkip}&theme=${theme}`;
+            const workHoursEnabled = document.getElementById('work_hours_enabled').checked;
+            const workHours = encodeURIComponent(document.getElementById('work_hours').value);
+            const idlePolicy = document.getElementById('idle_policy').value;
+            const idleTolerance = document.getElementById('idle_tolerance').value;
+            const idlePercent = document.getElementById('idle_percent').value;
+            const activeTime = document.getElementById('active_time').checked;
+            const activeThreshold = document.getElementById('active_threshold').value;
+            const extendBreaks = document.getElementById('extend_breaks').checked;
+            const awaitReturn = document.getElementById('await_return').checked;
+            const naturalBreak = document.getElementById('natural_break').checked;
+            const dailyGoal = document.getElementById('daily_goal').value;
+            const profile = encodeURIComponent(document.getElementById('profile').value);
+            window.location.href = `__SAVE_URL__&interval=${interval}&break=${breakVal}&long_every=${longEvery}&long_break=${longBreak}&postpone=${postpone}&max_postpones=${maxPostpones}&pre_break=${preBreak}&language=${language}&allow_skip=${allowSkip}&theme=${theme}&work_hours_enabled=${workHoursEnabled}&work_hours=${workHours}&idle_policy=${idlePolicy}&idle_tolerance=${idleTolerance}&idle_percent=${idlePercent}&active_time=${activeTime}&active_threshold=${activeThreshold}&extend_breaks=${extendBreaks}&await_return=${awaitReturn}&natural_break=${naturalBreak}&daily_goal=${dailyGoal}&profile=${profile}`;
         }
         function cancel() {
-            window.location.href = 'restgap://cancel';
+            window.location.href = '__CANCEL_URL__';
+        }
+        function showErrors(errors) {
+            document.querySelectorAll('.error').forEach(e => e.remove());
+            document.querySelectorAll('.invalid').forEach(e => e.classList.remove('invalid'));
+            let first = null;
+            for (const err of errors) {
+                const input = err.id ? document.getElementById(err.id) : null;
+                const anchor = input ? input.closest('.row') : document.querySelector('.footer');
+                if (input) input.classList.add('invalid');
+                const msg = document.createElement('div');
+                msg.className = 'error';
+                msg.textContent = err.message;
+                anchor.insertAdjacentElement(input ? 'afterend' : 'beforebegin', msg);
+                first = first || msg;
+            }
+            if (first) first.scrollIntoView({ behavior: 'smooth', block: 'center' });
+        }
+        function importConfig() {
+            window.location.href = '__IMPORT_URL__';
+        }
+        function exportConfig() {
+            window.location.href = '__EXPORT_URL__';
         }
     </script>
 </body>
@@ -504,7 +999,7 @@ pub fn open_settings_dialog(delegate: &RestGapDelegate) {
     let config = with_state_ref(|s| s.config.clone());
     let texts = Texts::new(config.effective_language());
 
-    let window_size = NSSize::new(420.0, 580.0);
+    let window_size = NSSize::new(420.0, 700.0);
     let screen_frame = objc2_app_kit::NSScreen::mainScreen(mtm).map_or(
         NSRect::new(NSPoint::new(0.0, 0.0), NSSize::new(1920.0, 1080.0)),
         |s| s.frame(),
@@ -533,11 +1028,7 @@ pub fn open_settings_dialog(delegate: &RestGapDelegate) {
     window.setTitle(&NSString::from_str(texts.settings_title()));
     window.setTitlebarAppearsTransparent(true);
     window.setTitleVisibility(objc2_app_kit::NSWindowTitleVisibility::Hidden);
-    let bg_color = match config.theme {
-        Theme::Dark => NSColor::colorWithSRGBRed_green_blue_alpha(0.11, 0.11, 0.12, 1.0),
-        Theme::Light => NSColor::colorWithSRGBRed_green_blue_alpha(0.95, 0.95, 0.97, 1.0),
-    };
-    window.setBackgroundColor(Some(&bg_color));
+    window.setBackgroundColor(Some(&background_color(config.theme)));
 
     let config_webview = unsafe { WKWebViewConfiguration::new(mtm) };
     let webview: Retained<WKWebView> = unsafe {
@@ -554,12 +1045,130 @@ pub fn open_settings_dialog(delegate: &RestGapDelegate) {
         webview.setNavigationDelegate(Some(ProtocolObject::from_ref(&*nav_delegate)));
     }
 
+    let html = settings_html(&config);
+    unsafe {
+        let _ = webview.loadHTMLString_baseURL(&NSString::from_str(&html), None);
+    }
+
+    if let Some(content_view) = window.contentView() {
+        content_view.addSubview(&webview);
+    }
+
+    window.makeKeyAndOrderFront(None);
+    NSApplication::sharedApplication(mtm).activateIgnoringOtherApps(true);
+
+    with_state(|state| {
+        state.settings_window = Some(window);
+        state.settings_webview = Some(webview);
+        state.settings_nav_delegate = Some(nav_delegate.into_super().into());
+    });
+}
+
+/// 按配置填充设置页模板
+#[allow(clippy::too_many_lines)]
+fn settings_html(config: &Config) -> String {
+    let texts = Texts::new(config.effective_language());
     let mut html = SETTINGS_HTML_TEMPLATE
         .replace("__TITLE__", texts.settings_title())
         .replace("__INTERVAL_LABEL__", texts.settings_interval_label())
         .replace("__INTERVAL_VAL__", &config.interval_minutes.to_string())
         .replace("__BREAK_LABEL__", texts.settings_break_label())
         .replace("__BREAK_VAL__", &config.break_seconds.to_string())
+        .replace(
+            "__LONG_EVERY_LABEL__",
+            texts.settings_long_break_every_label(),
+        )
+        .replace("__LONG_EVERY_VAL__", &config.long_break_every.to_string())
+        .replace("__LONG_BREAK_LABEL__", texts.settings_long_break_label())
+        .replace("__LONG_BREAK_VAL__", &config.long_break_seconds.to_string())
+        .replace("__PROFILE_LABEL__", texts.settings_profile_label())
+        .replace("__PROFILE_HINT__", texts.settings_profile_hint())
+        .replace(
+            "__PROFILE_VAL__",
+            &html_escape(config.active_profile.as_deref().unwrap_or_default()),
+        )
+        .replace("__PRE_BREAK_LABEL__", texts.settings_pre_break_label())
+        .replace("__PRE_BREAK_VAL__", &config.pre_break_seconds.to_string())
+        .replace("__POSTPONE_LABEL__", texts.settings_postpone_label())
+        .replace("__POSTPONE_VAL__", &config.postpone_minutes.to_string())
+        .replace(
+            "__MAX_POSTPONES_LABEL__",
+            texts.settings_max_postpones_label(),
+        )
+        .replace("__MAX_POSTPONES_VAL__", &config.max_postpones.to_string())
+        .replace("__WORK_HOURS_LABEL__", texts.settings_work_hours_label())
+        .replace("__WORK_HOURS_HINT__", texts.settings_work_hours_hint())
+        .replace("__WORK_HOURS_VAL__", &config.work_hours.to_string())
+        .replace(
+            "__WORK_HOURS_CHECKED__",
+            if config.work_hours_enabled {
+                "checked"
+            } else {
+                ""
+            },
+        )
+        .replace("__ACTIVE_TIME_LABEL__", texts.settings_active_time_label())
+        .replace(
+            "__ACTIVE_TIME_CHECKED__",
+            if config.active_time { "checked" } else { "" },
+        )
+        .replace(
+            "__ACTIVE_THRESHOLD_LABEL__",
+            texts.settings_active_threshold_label(),
+        )
+        .replace(
+            "__ACTIVE_THRESHOLD_VAL__",
+            &config.active_idle_threshold_seconds.to_string(),
+        )
+        .replace(
+            "__EXTEND_BREAKS_LABEL__",
+            texts.settings_extend_breaks_label(),
+        )
+        .replace(
+            "__EXTEND_BREAKS_CHECKED__",
+            if config.extend_active_breaks {
+                "checked"
+            } else {
+                ""
+            },
+        )
+        .replace(
+            "__AWAIT_RETURN_LABEL__",
+            texts.settings_await_return_label(),
+        )
+        .replace("__AWAIT_RETURN_HINT__", texts.settings_await_return_hint())
+        .replace(
+            "__NATURAL_BREAK_LABEL__",
+            texts.settings_natural_break_label(),
+        )
+        .replace(
+            "__NATURAL_BREAK_HINT__",
+            texts.settings_natural_break_hint(),
+        )
+        .replace(
+            "__NATURAL_BREAK_CHECKED__",
+            if config.natural_break { "checked" } else { "" },
+        )
+        .replace("__DAILY_GOAL_LABEL__", texts.settings_daily_goal_label())
+        .replace("__DAILY_GOAL_VAL__", &config.daily_goal.to_string())
+        .replace(
+            "__AWAIT_RETURN_CHECKED__",
+            if config.await_return { "checked" } else { "" },
+        )
+        .replace("__IDLE_POLICY_LABEL__", texts.settings_idle_policy_label())
+        .replace(
+            "__IDLE_TOLERANCE_LABEL__",
+            texts.settings_idle_tolerance_label(),
+        )
+        .replace(
+            "__IDLE_TOLERANCE_VAL__",
+            &config.idle_tolerance_seconds().to_string(),
+        )
+        .replace(
+            "__IDLE_PERCENT_LABEL__",
+            texts.settings_idle_percent_label(),
+        )
+        .replace("__IDLE_PERCENT_VAL__", &config.idle_percent().to_string())
         .replace("__SKIP_LABEL__", texts.settings_skip_break_label())
         .replace("__SKIP_HINT__", texts.settings_skip_break_hint())
         .replace(
@@ -577,8 +1186,28 @@ pub fn open_settings_dialog(delegate: &RestGapDelegate) {
         .replace("__THEME_LABEL__", texts.settings_theme_label())
         .replace("__THEME_DARK__", texts.theme_dark())
         .replace("__THEME_LIGHT__", texts.theme_light())
+        .replace("__IMPORT__", texts.settings_import_button())
+        .replace("__EXPORT__", texts.settings_export_button())
         .replace("__CANCEL__", texts.settings_cancel_button())
-        .replace("__SAVE__", texts.settings_save_button());
+        .replace("__SAVE__", texts.settings_save_button())
+        .replace("__SAVE_URL__", &BridgeCommand::Save(Vec::new()).to_url())
+        .replace("__CANCEL_URL__", &BridgeCommand::Cancel.to_url())
+        .replace("__IMPORT_URL__", &BridgeCommand::Import.to_url())
+        .replace("__EXPORT_URL__", &BridgeCommand::Export.to_url());
+
+    for kind in 0..4 {
+        let selected = if config.idle_policy_kind() == i64::try_from(kind).unwrap_or(-1) {
+            "selected"
+        } else {
+            ""
+        };
+        html = html
+            .replace(
+                &format!("__IDLE_POLICY_{kind}__"),
+                texts.idle_policy_option(kind),
+            )
+            .replace(&format!("__IDLE_POLICY_{kind}_SELECTED__"), selected);
+    }
 
     html = html.replace(
         "__THEME_CLASS__",
@@ -630,20 +1259,5 @@ pub fn open_settings_dialog(delegate: &RestGapDelegate) {
         },
     );
 
-    unsafe {
-        let _ = webview.loadHTMLString_baseURL(&NSString::from_str(&html), None);
-    }
-
-    if let Some(content_view) = window.contentView() {
-        content_view.addSubview(&webview);
-    }
-
-    window.makeKeyAndOrderFront(None);
-    NSApplication::sharedApplication(mtm).activateIgnoringOtherApps(true);
-
-    with_state(|state| {
-        state.settings_window = Some(window);
-        state.settings_webview = Some(webview);
-        state.settings_nav_delegate = Some(nav_delegate.into_super().into());
-    });
+    html
 }
@@ -1,18 +1,57 @@
 //! 状态栏 UI 模块
 
-use std::time::{Duration, Instant};
+use std::time::{Duration, SystemTime};
 
+use objc2::ffi::NSInteger;
 use objc2::runtime::{AnyObject, ProtocolObject};
 use objc2::{ClassType, MainThreadMarker, MainThreadOnly, sel};
 use objc2_app_kit::{
-    NSMenu, NSMenuDelegate, NSMenuItem, NSSquareStatusItemLength, NSStatusBar, NSStatusItem,
+    NSControlStateValueOff, NSControlStateValueOn, NSMenu, NSMenuDelegate, NSMenuItem,
+    NSSquareStatusItemLength, NSStatusBar, NSStatusItem,
 };
 use objc2_foundation::NSString;
 
 use super::super::delegate::RestGapDelegate;
 use super::super::state::{Phase, with_state, with_state_ref};
 use super::super::utils::{approx_duration, format_hhmm};
+use crate::goals::{self, Achievement};
+use crate::history::Record;
 use crate::i18n::Texts;
+use crate::idle::IdleReason;
+use crate::scheduler::{BreakKind, Clock, Pause, SystemClock};
+use crate::stats;
+use crate::work_hours::{SECS_PER_DAY, local_secs, weekday};
+
+/// 暂停子菜单中按时长暂停的选项（分钟），tag 为下标
+const PAUSE_MINUTES: [u64; 3] = [30, 60, 120];
+const PAUSE_TAG_UNTIL_TOMORROW: NSInteger = 3;
+const PAUSE_TAG_INDEFINITELY: NSInteger = 4;
+
+/// 根据暂停子菜单项的 tag 得到暂停方式
+pub fn pause_choice(tag: NSInteger) -> Option<Pause> {
+    match tag {
+        PAUSE_TAG_UNTIL_TOMORROW => Some(Pause::until_tomorrow(&SystemClock)),
+        PAUSE_TAG_INDEFINITELY => Some(Pause::Indefinitely),
+        _ => {
+            let minutes = PAUSE_MINUTES.get(usize::try_from(tag).ok()?)?;
+            Some(Pause::for_duration(
+                Duration::from_secs(minutes * 60),
+                &SystemClock,
+            ))
+        }
+    }
+}
+
+fn pause_choice_title(texts: &Texts, tag: NSInteger) -> String {
+    match tag {
+        PAUSE_TAG_UNTIL_TOMORROW => texts.pause_until_tomorrow().to_string(),
+        PAUSE_TAG_INDEFINITELY => texts.pause_indefinitely().to_string(),
+        _ => usize::try_from(tag)
+            .ok()
+            .and_then(|i| PAUSE_MINUTES.get(i))
+            .map_or_else(String::new, |&minutes| texts.pause_for_minutes(minutes)),
+    }
+}
 
 /// 获取 delegate 的 `AnyObject` 引用
 pub fn target_anyobject(delegate: &RestGapDelegate) -> &AnyObject {
@@ -33,26 +72,62 @@ pub fn refresh_status_title() {
             return;
         };
 
-        let title = match state.phase {
+        let scheduler = &state.scheduler;
+        if let Some(pause) = scheduler.paused() {
+            let texts = Texts::new(state.config.effective_language());
+            status_item.setTitle(Some(&NSString::from_str("‖")));
+            if let Some(mtm) = MainThreadMarker::new()
+                && let Some(button) = status_item.button(mtm)
+            {
+                let title = format!("‖ {}", paused_title(&texts, pause));
+                button.setToolTip(Some(&NSString::from_str(&title)));
+            }
+            return;
+        }
+
+        let title = match scheduler.phase() {
             Phase::Working => {
-                let hm = state
-                    .phase_deadline_wall
+                let hm = scheduler
+                    .deadline_wall()
                     .map_or_else(|| "--:--".to_string(), format_hhmm);
                 format!("● {hm}")
             }
+            Phase::PreBreak => {
+                let texts = Texts::new(state.config.effective_language());
+                let remaining = scheduler.remaining(&SystemClock);
+                format!(
+                    "◔ {}",
+                    texts.break_imminent_title(&approx_duration(remaining))
+                )
+            }
             Phase::Breaking => {
-                let remaining = state
-                    .phase_deadline_mono
-                    .and_then(|t| t.checked_duration_since(Instant::now()))
-                    .unwrap_or(Duration::from_secs(0));
+                let remaining = scheduler.remaining(&SystemClock);
                 format!("○ {}", approx_duration(remaining))
             }
+            Phase::OffHours => {
+                let texts = Texts::new(state.config.effective_language());
+                format!("◌ {}", off_hours_title(&texts, scheduler.deadline_wall()))
+            }
+            Phase::AwaitingReturn => {
+                let texts = Texts::new(state.config.effective_language());
+                format!("◎ {}", texts.awaiting_return_title())
+            }
         };
-        let marker = match state.phase {
+        let marker = match scheduler.phase() {
             Phase::Working => "●",
+            Phase::PreBreak => "◔",
             Phase::Breaking => "○",
+            Phase::OffHours => "◌",
+            Phase::AwaitingReturn => "◎",
         };
-        status_item.setTitle(Some(&NSString::from_str(marker)));
+        if state.welcome_timer.is_some() && scheduler.phase() == Phase::Working {
+            // 用户刚回来：短暂在状态栏展示欢迎语
+            let texts = Texts::new(state.config.effective_language());
+            let welcome = format!("{marker} {}", texts.welcome_back_title());
+            status_item.setTitle(Some(&NSString::from_str(&welcome)));
+        } else {
+            status_item.setTitle(Some(&NSString::from_str(marker)));
+        }
 
         if let Some(mtm) = MainThreadMarker::new()
             && let Some(button) = status_item.button(mtm)
@@ -64,7 +139,6 @@ pub fn refresh_status_title() {
 
 /// 刷新菜单信息
 pub fn refresh_menu_info() {
-    let now = Instant::now();
     with_state(|state| {
         let texts = Texts::new(state.config.effective_language());
         let Some(next_item) = state.next_break_item.as_ref() else {
@@ -74,36 +148,136 @@ pub fn refresh_menu_info() {
             return;
         };
 
-        let phase_deadline_mono = state.phase_deadline_mono;
-        let phase_deadline_wall = state.phase_deadline_wall;
+        if let Some(item) = state.today_item.as_ref() {
+            let today = SystemClock.local_secs().div_euclid(SECS_PER_DAY);
+            let offset = SystemClock.utc_offset();
+            let tally = stats::day_tally(&state.history, today, offset);
+            let week = stats::week_tally(&state.history, today, offset);
+            let title =
+                texts.today_breaks_title(tally.taken, tally.due(), week.compliance_percent());
+            item.setTitle(&NSString::from_str(&title));
+        }
+
+        if let Some(item) = state.achievements_item.as_ref() {
+            let days = stats::daily(&state.history, SystemClock.utc_offset());
+            let unlocked = goals::achievements(&days, state.config.daily_goal_breaks());
+            refresh_achievements_item(&texts, item, &unlocked);
+        }
+
+        if let Some(item) = state.postpone_item.as_ref() {
+            item.setHidden(!state.scheduler.can_postpone());
+            let title = texts.postpone_title(
+                state.config.postpone_minutes,
+                state.scheduler.postpones_left(),
+            );
+            item.setTitle(&NSString::from_str(&title));
+        }
 
-        match state.phase {
+        let paused = state.scheduler.paused();
+        if let Some(item) = state.pause_item.as_ref() {
+            item.setHidden(paused.is_some());
+        }
+        if let Some(item) = state.resume_item.as_ref() {
+            item.setHidden(paused.is_none());
+        }
+        if let Some(pause) = paused {
+            next_item.setHidden(false);
+            remaining_item.setHidden(true);
+            next_item.setTitle(&NSString::from_str(&paused_title(&texts, pause)));
+            return;
+        }
+
+        let remaining = state.scheduler.remaining(&SystemClock);
+        let phase_deadline_wall = state.scheduler.deadline_wall();
+
+        match state.scheduler.phase() {
             Phase::Working => {
                 next_item.setHidden(false);
-                remaining_item.setHidden(true);
+                // 上一轮因闲置跳过休息时，在下方展示原因
+                let idle_skip = state.scheduler.last_idle_skip();
+                remaining_item.setHidden(idle_skip.is_none());
+                if let Some(reason) = idle_skip {
+                    let title = texts.idle_skipped_title(&idle_reason_text(&texts, reason));
+                    remaining_item.setTitle(&NSString::from_str(&title));
+                }
 
-                let next_break_in = phase_deadline_mono
-                    .and_then(|t| t.checked_duration_since(now))
-                    .unwrap_or(Duration::from_secs(0));
+                let next_break_in = remaining;
                 let next_hm = phase_deadline_wall.map_or_else(|| "--:--".to_string(), format_hhmm);
-                let next_title = texts.next_break_title(&next_hm, &approx_duration(next_break_in));
+                let next_approx = approx_duration(next_break_in);
+                let next_title = match state.scheduler.next_break_kind() {
+                    BreakKind::Short => texts.next_break_title(&next_hm, &next_approx),
+                    BreakKind::Long => texts.next_long_break_title(&next_hm, &next_approx),
+                };
                 next_item.setTitle(&NSString::from_str(&next_title));
             }
+            Phase::PreBreak => {
+                next_item.setHidden(false);
+                remaining_item.setHidden(true);
+
+                let title = texts.break_imminent_title(&approx_duration(remaining));
+                next_item.setTitle(&NSString::from_str(&title));
+            }
             Phase::Breaking => {
                 next_item.setHidden(true);
                 remaining_item.setHidden(false);
 
-                let remaining = phase_deadline_mono
-                    .and_then(|t| t.checked_duration_since(now))
-                    .unwrap_or(Duration::from_secs(0));
                 let end_hm = phase_deadline_wall.map_or_else(|| "--:--".to_string(), format_hhmm);
                 let title = texts.remaining_title_breaking(&approx_duration(remaining), &end_hm);
                 remaining_item.setTitle(&NSString::from_str(&title));
             }
+            Phase::OffHours => {
+                next_item.setHidden(false);
+                remaining_item.setHidden(true);
+
+                let title = off_hours_title(&texts, phase_deadline_wall);
+                next_item.setTitle(&NSString::from_str(&title));
+            }
+            Phase::AwaitingReturn => {
+                next_item.setHidden(false);
+                remaining_item.setHidden(true);
+                next_item.setTitle(&NSString::from_str(texts.awaiting_return_title()));
+            }
         }
     });
 }
 
+/// 工作时段外的提示，例如“Paused until Mon 09:00”
+fn off_hours_title(texts: &Texts, resume_at: Option<SystemTime>) -> String {
+    resume_at.map_or_else(
+        || texts.off_hours_title().to_string(),
+        |resume_at| paused_until_title(texts, resume_at),
+    )
+}
+
+/// 闲置判定原因的展示文本
+fn idle_reason_text(texts: &Texts, reason: IdleReason) -> String {
+    match reason {
+        IdleReason::Disabled => texts.idle_reason_disabled().to_string(),
+        IdleReason::Unavailable => texts.idle_reason_unavailable().to_string(),
+        IdleReason::Active { active, tolerance } => {
+            texts.idle_reason_active(active.as_secs(), tolerance.as_secs())
+        }
+        IdleReason::IdleShare { percent, threshold } => texts.idle_reason_share(percent, threshold),
+        IdleReason::RestedBreakLength {
+            idle,
+            break_duration,
+        } => texts.idle_reason_rested(&approx_duration(idle), &approx_duration(break_duration)),
+    }
+}
+
+/// 暂停提醒时的提示
+fn paused_title(texts: &Texts, pause: Pause) -> String {
+    match pause {
+        Pause::Until(resume_at) => paused_until_title(texts, resume_at),
+        Pause::Indefinitely => texts.paused_title().to_string(),
+    }
+}
+
+fn paused_until_title(texts: &Texts, resume_at: SystemTime) -> String {
+    let day = weekday(local_secs(resume_at, SystemClock.utc_offset()));
+    texts.paused_until_title(texts.weekday_short(day), &format_hhmm(resume_at))
+}
+
 /// 刷新头部标题
 pub fn refresh_header_title() {
     with_state(|state| {
@@ -111,24 +285,68 @@ pub fn refresh_header_title() {
         let Some(item) = state.header_item.as_ref() else {
             return;
         };
-        let title = texts.header_title(state.config.interval_minutes, state.config.break_seconds);
+        let title = texts.header_title(
+            state.config.interval_minutes,
+            state.config.break_seconds,
+            goal_streak(&state.history, state.config.daily_goal_breaks()),
+        );
         item.setTitle(&NSString::from_str(&title));
     });
 }
 
+/// 刷新“成就”菜单项：标题显示解锁数量，子项勾选已解锁的成就
+fn refresh_achievements_item(texts: &Texts, item: &NSMenuItem, unlocked: &[Achievement]) {
+    let title = texts.menu_achievements(unlocked.len(), Achievement::ALL.len());
+    item.setTitle(&NSString::from_str(&title));
+    let Some(submenu) = item.submenu() else {
+        return;
+    };
+    for (index, achievement) in (0..).zip(Achievement::ALL) {
+        let Some(choice) = submenu.itemAtIndex(index) else {
+            continue;
+        };
+        choice.setTitle(&NSString::from_str(texts.achievement_name(achievement)));
+        choice.setToolTip(Some(&NSString::from_str(
+            texts.achievement_hint(achievement),
+        )));
+        choice.setState(if unlocked.contains(&achievement) {
+            NSControlStateValueOn
+        } else {
+            NSControlStateValueOff
+        });
+    }
+}
+
+/// 截至今天连续达到每日目标的天数
+fn goal_streak(history: &[Record], goal: u32) -> u32 {
+    let today = SystemClock.local_secs().div_euclid(SECS_PER_DAY);
+    goals::streak(
+        &stats::daily(history, SystemClock.utc_offset()),
+        today,
+        goal,
+    )
+}
+
 pub fn refresh_static_menu_titles() {
     with_state(|state| {
         let texts = Texts::new(state.config.effective_language());
 
         if let Some(item) = state.rest_now_item.as_ref() {
-            match state.phase {
-                Phase::Working => {
+            match state.scheduler.phase() {
+                Phase::Working | Phase::OffHours | Phase::AwaitingReturn => {
                     item.setTitle(&NSString::from_str(texts.menu_rest_now()));
                     item.setEnabled(true);
                     unsafe {
                         item.setAction(Some(sel!(restNow:)));
                     }
                 }
+                Phase::PreBreak => {
+                    item.setTitle(&NSString::from_str(texts.menu_start_break_now()));
+                    item.setEnabled(true);
+                    unsafe {
+                        item.setAction(Some(sel!(restNow:)));
+                    }
+                }
                 Phase::Breaking => {
                     if state.config.allow_skip_break {
                         let skip_title = if texts.language() == crate::i18n::Language::Zh {
@@ -156,9 +374,29 @@ pub fn refresh_static_menu_titles() {
                 }
             }
         }
+        if let Some(item) = state.pause_item.as_ref() {
+            item.setTitle(&NSString::from_str(texts.menu_pause()));
+            if let Some(submenu) = item.submenu() {
+                for index in 0..submenu.numberOfItems() {
+                    if let Some(choice) = submenu.itemAtIndex(index) {
+                        let title = pause_choice_title(&texts, choice.tag());
+                        choice.setTitle(&NSString::from_str(&title));
+                    }
+                }
+            }
+        }
+        if let Some(item) = state.profile_item.as_ref() {
+            item.setTitle(&NSString::from_str(texts.menu_profile()));
+        }
+        if let Some(item) = state.resume_item.as_ref() {
+            item.setTitle(&NSString::from_str(texts.menu_resume()));
+        }
         if let Some(item) = state.settings_item.as_ref() {
             item.setTitle(&NSString::from_str(texts.menu_settings()));
         }
+        if let Some(item) = state.export_history_item.as_ref() {
+            item.setTitle(&NSString::from_str(texts.menu_export_history()));
+        }
         if let Some(item) = state.about_item.as_ref() {
             item.setTitle(&NSString::from_str(&texts.menu_about()));
         }
@@ -168,6 +406,40 @@ pub fn refresh_static_menu_titles() {
     });
 }
 
+/// 按配置重建方案子菜单，勾选当前方案
+pub fn refresh_profile_menu(delegate: &RestGapDelegate) {
+    let (profiles, active) = with_state_ref(|state| {
+        // 时长已被改动的方案不再打勾
+        let active = state
+            .config
+            .active_profile_matches()
+            .then(|| state.config.active_profile.clone())
+            .flatten();
+        (state.config.available_profiles(), active)
+    });
+    let Some(submenu) = with_state_ref(|state| state.profile_item.as_ref()?.submenu()) else {
+        return;
+    };
+
+    submenu.removeAllItems();
+    for (tag, profile) in (0..).zip(&profiles) {
+        let choice = unsafe {
+            submenu.addItemWithTitle_action_keyEquivalent(
+                &NSString::from_str(&profile.name),
+                Some(sel!(switchProfile:)),
+                &NSString::from_str(""),
+            )
+        };
+        choice.setTag(tag);
+        choice.setState(if active.as_deref() == Some(profile.name.as_str()) {
+            NSControlStateValueOn
+        } else {
+            NSControlStateValueOff
+        });
+        unsafe { choice.setTarget(Some(target_anyobject(delegate))) };
+    }
+}
+
 /// 设置状态栏菜单
 #[allow(clippy::too_many_lines)]
 pub fn setup_status_item(delegate: &RestGapDelegate) {
@@ -186,6 +458,7 @@ pub fn setup_status_item(delegate: &RestGapDelegate) {
     let header = texts.header_title(
         with_state_ref(|s| s.config.interval_minutes),
         with_state_ref(|s| s.config.break_seconds),
+        with_state_ref(|s| goal_streak(&s.history, s.config.daily_goal_breaks())),
     );
     let header_item = NSMenuItem::sectionHeaderWithTitle(&NSString::from_str(&header), mtm);
     menu.addItem(&header_item);
@@ -199,6 +472,37 @@ pub fn setup_status_item(delegate: &RestGapDelegate) {
     };
     next_break_item.setEnabled(false);
 
+    let today_item = unsafe {
+        menu.addItemWithTitle_action_keyEquivalent(
+            &NSString::from_str(&texts.today_breaks_title(0, 0, None)),
+            None,
+            &NSString::from_str(""),
+        )
+    };
+    today_item.setEnabled(false);
+
+    // 标题与勾选状态在 refresh_menu_info 中按历史刷新
+    let achievements_menu = NSMenu::new(mtm);
+    achievements_menu.setAutoenablesItems(false);
+    for _ in Achievement::ALL {
+        let choice = unsafe {
+            achievements_menu.addItemWithTitle_action_keyEquivalent(
+                &NSString::from_str(""),
+                None,
+                &NSString::from_str(""),
+            )
+        };
+        choice.setEnabled(false);
+    }
+    let achievements_item = unsafe {
+        menu.addItemWithTitle_action_keyEquivalent(
+            &NSString::from_str(&texts.menu_achievements(0, Achievement::ALL.len())),
+            None,
+            &NSString::from_str(""),
+        )
+    };
+    achievements_item.setSubmenu(Some(&achievements_menu));
+
     let remaining_break_item = unsafe {
         menu.addItemWithTitle_action_keyEquivalent(
             &NSString::from_str(texts.menu_remaining_placeholder()),
@@ -219,6 +523,59 @@ pub fn setup_status_item(delegate: &RestGapDelegate) {
     };
     unsafe { rest_now_item.setTarget(Some(target_anyobject(delegate))) };
 
+    let postpone_item = unsafe {
+        menu.addItemWithTitle_action_keyEquivalent(
+            &NSString::from_str(""),
+            Some(sel!(postponeBreak:)),
+            &NSString::from_str(""),
+        )
+    };
+    unsafe { postpone_item.setTarget(Some(target_anyobject(delegate))) };
+    postpone_item.setHidden(true);
+
+    let pause_menu = NSMenu::new(mtm);
+    pause_menu.setAutoenablesItems(false);
+    for tag in 0..=PAUSE_TAG_INDEFINITELY {
+        let choice = unsafe {
+            pause_menu.addItemWithTitle_action_keyEquivalent(
+                &NSString::from_str(&pause_choice_title(&texts, tag)),
+                Some(sel!(pauseReminders:)),
+                &NSString::from_str(""),
+            )
+        };
+        choice.setTag(tag);
+        unsafe { choice.setTarget(Some(target_anyobject(delegate))) };
+    }
+    let pause_item = unsafe {
+        menu.addItemWithTitle_action_keyEquivalent(
+            &NSString::from_str(texts.menu_pause()),
+            None,
+            &NSString::from_str(""),
+        )
+    };
+    pause_item.setSubmenu(Some(&pause_menu));
+
+    let profile_menu = NSMenu::new(mtm);
+    profile_menu.setAutoenablesItems(false);
+    let profile_item = unsafe {
+        menu.addItemWithTitle_action_keyEquivalent(
+            &NSString::from_str(texts.menu_profile()),
+            None,
+            &NSString::from_str(""),
+        )
+    };
+    profile_item.setSubmenu(Some(&profile_menu));
+
+    let resume_item = unsafe {
+        menu.addItemWithTitle_action_keyEquivalent(
+            &NSString::from_str(texts.menu_resume()),
+            Some(sel!(resumeReminders:)),
+            &NSString::from_str(""),
+        )
+    };
+    unsafe { resume_item.setTarget(Some(target_anyobject(delegate))) };
+    resume_item.setHidden(true);
+
     let settings_item = unsafe {
         menu.addItemWithTitle_action_keyEquivalent(
             &NSString::from_str(texts.menu_settings()),
@@ -228,6 +585,15 @@ pub fn setup_status_item(delegate: &RestGapDelegate) {
     };
     unsafe { settings_item.setTarget(Some(target_anyobject(delegate))) };
 
+    let export_history_item = unsafe {
+        menu.addItemWithTitle_action_keyEquivalent(
+            &NSString::from_str(texts.menu_export_history()),
+            Some(sel!(exportHistory:)),
+            &NSString::from_str(""),
+        )
+    };
+    unsafe { export_history_item.setTarget(Some(target_anyobject(delegate))) };
+
     menu.addItem(&NSMenuItem::separatorItem(mtm));
 
     let about_item = unsafe {
@@ -256,10 +622,18 @@ pub fn setup_status_item(delegate: &RestGapDelegate) {
         state.status_item = Some(status_item);
         state.header_item = Some(header_item);
         state.rest_now_item = Some(rest_now_item);
+        state.postpone_item = Some(postpone_item);
+        state.pause_item = Some(pause_item);
+        state.profile_item = Some(profile_item);
+        state.resume_item = Some(resume_item);
         state.settings_item = Some(settings_item);
+        state.export_history_item = Some(export_history_item);
         state.about_item = Some(about_item);
         state.quit_item = Some(quit_item);
         state.next_break_item = Some(next_break_item);
+        state.today_item = Some(today_item);
+        state.achievements_item = Some(achievements_item);
         state.remaining_break_item = Some(remaining_break_item);
     });
+    refresh_profile_menu(delegate);
 }
@@ -3,18 +3,74 @@
 //! 使用原生 `AppKit` / `WebKit` 构建，仅支持 macOS，
 //! 采用事件驱动架构而非轮询，追求极低的 CPU 和内存占用。
 
+// 调度、配置、历史等模块的部分条目只由 macOS 层调用，在其他平台上整体允许未使用；
+// 命令行、导出、报告等所有平台共用的模块中未使用的代码照常报告。
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) mod bridge;
+pub(crate) mod cli;
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) mod config;
+pub(crate) mod error;
+pub(crate) mod export;
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) mod goals;
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) mod history;
 pub(crate) mod i18n;
 pub(crate) mod idle;
+pub(crate) mod migration;
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) mod profile;
+pub(crate) mod report;
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) mod scheduler;
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) mod stats;
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) mod watch;
+pub(crate) mod work_hours;
 
 #[cfg(target_os = "macos")]
 mod macos;
 
+use std::process::ExitCode;
+
 #[cfg(target_os = "macos")]
-fn main() {
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let store = macos::config::store();
+    let history = history::HistoryStore::default_location();
+    if let Some(code) = cli::run(
+        &args,
+        &*store,
+        history.as_ref(),
+        &scheduler::SystemClock,
+        &mut std::io::stdout(),
+        &mut std::io::stderr(),
+    ) {
+        return code;
+    }
     macos::run();
+    ExitCode::SUCCESS
 }
 
 #[cfg(not(target_os = "macos"))]
-fn main() {
+fn main() -> ExitCode {
+    // 其他平台上仍可用命令行管理 XDG 配置文件
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let history = history::HistoryStore::default_location();
+    if let Some(store) = config::FileStore::default_location()
+        && let Some(code) = cli::run(
+            &args,
+            &store,
+            history.as_ref(),
+            &scheduler::SystemClock,
+            &mut std::io::stdout(),
+            &mut std::io::stderr(),
+        )
+    {
+        return code;
+    }
     eprintln!("息间（RestGap）当前仅支持 macOS。");
+    ExitCode::SUCCESS
 }
@@ -0,0 +1,214 @@
+//! 配置结构的版本迁移。
+//!
+//! 存储中记录当前结构版本（[`VERSION_KEY`]）。启动时按顺序执行尚未执行的迁移，每一步把版本从
+//! N 升到 N+1，并把迁移名追加到 [`HISTORY_KEY`] 中作为执行记录。
+//! 新增字段只需追加一步迁移，读取配置时不再需要针对旧数据的临时兼容逻辑。
+
+use serde_json::{Map, Value};
+
+/// 当前配置结构版本
+pub const SCHEMA_VERSION: i64 = 2;
+/// 保存结构版本的键
+pub const VERSION_KEY: &str = "schema_version";
+/// 保存已执行迁移记录（逗号分隔的迁移名）的键
+pub const HISTORY_KEY: &str = "migrations";
+
+/// 迁移所操作的键值存储
+///
+/// 键名不含存储自身的前缀（如 `NSUserDefaults` 中的 `restgap.`）；含 `.` 的键视为完整键名，
+/// 用于访问旧版前缀下的数据。
+pub trait Settings {
+    fn integer(&self, key: &str) -> Option<i64>;
+    fn string(&self, key: &str) -> Option<String>;
+    fn set_integer(&mut self, key: &str, value: i64);
+    fn set_string(&mut self, key: &str, value: &str);
+    fn remove(&mut self, key: &str);
+}
+
+/// 一步迁移：把结构版本从 `to - 1` 升到 `to`
+#[derive(Clone, Copy, Debug)]
+pub struct Migration {
+    pub to: i64,
+    pub name: &'static str,
+    pub apply: fn(&mut dyn Settings),
+}
+
+/// 按版本顺序排列的全部迁移
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        to: 1,
+        name: "legacy_restp_keys",
+        apply: legacy_restp_keys,
+    },
+    Migration {
+        to: 2,
+        name: "drop_unset_integers",
+        apply: drop_unset_integers,
+    },
+];
+
+/// 执行所有尚未执行的迁移，返回本次执行的迁移名
+pub fn migrate(settings: &mut dyn Settings) -> Vec<&'static str> {
+    let version = settings.integer(VERSION_KEY).unwrap_or(0);
+    let mut history = settings.string(HISTORY_KEY).unwrap_or_default();
+    let mut applied = Vec::new();
+
+    for migration in MIGRATIONS.iter().filter(|m| m.to > version) {
+        (migration.apply)(settings);
+        settings.set_integer(VERSION_KEY, migration.to);
+        if !history.is_empty() {
+            history.push(',');
+        }
+        history.push_str(migration.name);
+        settings.set_string(HISTORY_KEY, &history);
+        applied.push(migration.name);
+    }
+    applied
+}
+
+/// v0 → v1：早期版本使用 `restp.` 前缀，迁移到当前键名（当前键名未设置时）后删除旧键
+fn legacy_restp_keys(settings: &mut dyn Settings) {
+    for key in ["interval_minutes", "break_seconds"] {
+        let legacy = format!("restp.{key}");
+        let current = settings.integer(key).filter(|&v| v > 0);
+        if let Some(value) = settings.integer(&legacy).filter(|&v| v > 0)
+            && current.is_none()
+        {
+            settings.set_integer(key, value);
+        }
+        settings.remove(&legacy);
+    }
+}
+
+/// v1 → v2：早期版本把 0 或负数当作“未设置”，删除这些值，之后只需判断键是否存在
+fn drop_unset_integers(settings: &mut dyn Settings) {
+    for key in [
+        "interval_minutes",
+        "break_seconds",
+        "long_break_seconds",
+        "postpone_minutes",
+        "idle_tolerance_seconds",
+        "idle_percent",
+        "active_idle_threshold_seconds",
+    ] {
+        if settings.integer(key).is_some_and(|v| v <= 0) {
+            settings.remove(key);
+        }
+    }
+    for key in ["long_break_every", "pre_break_seconds", "max_postpones"] {
+        if settings.integer(key).is_some_and(|v| v < 0) {
+            settings.remove(key);
+        }
+    }
+}
+
+/// JSON 文件存储中的配置对象
+impl Settings for Map<String, Value> {
+    fn integer(&self, key: &str) -> Option<i64> {
+        self.get(key).and_then(Value::as_i64)
+    }
+
+    fn string(&self, key: &str) -> Option<String> {
+        self.get(key).and_then(Value::as_str).map(str::to_string)
+    }
+
+    fn set_integer(&mut self, key: &str, value: i64) {
+        self.insert(key.to_string(), value.into());
+    }
+
+    fn set_string(&mut self, key: &str, value: &str) {
+        self.insert(key.to_string(), value.into());
+    }
+
+    fn remove(&mut self, key: &str) {
+        Self::remove(self, key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn settings(value: &Value) -> Map<String, Value> {
+        value.as_object().cloned().unwrap()
+    }
+
+    #[test]
+    fn migrations_are_ordered_and_reach_current_version() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(
+                migration.to,
+                i64::try_from(i).unwrap() + 1,
+                "{}",
+                migration.name
+            );
+        }
+        assert_eq!(MIGRATIONS.last().map(|m| m.to), Some(SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn legacy_keys_fill_unset_current_keys() {
+        let mut s = settings(&json!({
+            "restp.interval_minutes": 45,
+            "restp.break_seconds": 300,
+            "break_seconds": 60,
+        }));
+        legacy_restp_keys(&mut s);
+        assert_eq!(
+            Value::Object(s),
+            json!({"interval_minutes": 45, "break_seconds": 60})
+        );
+    }
+
+    #[test]
+    fn unset_integers_are_dropped() {
+        let mut s = settings(&json!({
+            "interval_minutes": 0,
+            "break_seconds": 120,
+            "postpone_minutes": -3,
+            "max_postpones": 0,
+            "pre_break_seconds": -1,
+        }));
+        drop_unset_integers(&mut s);
+        assert_eq!(
+            Value::Object(s),
+            json!({"break_seconds": 120, "max_postpones": 0})
+        );
+    }
+
+    #[test]
+    fn migrate_runs_pending_steps_once_and_records_them() {
+        let mut s = settings(&json!({"restp.interval_minutes": 0, "idle_percent": 0}));
+        assert_eq!(
+            migrate(&mut s),
+            vec!["legacy_restp_keys", "drop_unset_integers"]
+        );
+        assert_eq!(
+            Value::Object(s.clone()),
+            json!({
+                "schema_version": SCHEMA_VERSION,
+                "migrations": "legacy_restp_keys,drop_unset_integers",
+            })
+        );
+        assert!(migrate(&mut s).is_empty());
+    }
+
+    #[test]
+    fn migrate_skips_steps_already_applied() {
+        let mut s = settings(&json!({
+            "schema_version": 1,
+            "migrations": "legacy_restp_keys",
+            "restp.interval_minutes": 45,
+            "interval_minutes": 0,
+        }));
+        assert_eq!(migrate(&mut s), vec!["drop_unset_integers"]);
+        assert_eq!(s.integer("restp.interval_minutes"), Some(45));
+        assert_eq!(s.integer("interval_minutes"), None);
+        assert_eq!(
+            s.string(HISTORY_KEY).as_deref(),
+            Some("legacy_restp_keys,drop_unset_integers")
+        );
+    }
+}
@@ -0,0 +1,283 @@
+//! 配置方案（命名的工作/休息时长组合）。
+//!
+//! 内置方案随应用提供且不可修改；用户方案保存在配置中，与内置方案同名时覆盖内置方案。
+//! 配置中最多有一个当前方案，切换方案只替换时长相关的设置，语言、主题等保持不变。
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, RejectReason, Rejected, clamp_u64};
+
+/// 一个命名的配置方案
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub interval_minutes: u64,
+    pub break_seconds: u64,
+    /// 每隔多少次休息安排一次长休息（0 表示关闭）
+    #[serde(default)]
+    pub long_break_every: u64,
+    #[serde(default = "default_long_break_seconds")]
+    pub long_break_seconds: u64,
+}
+
+const fn default_long_break_seconds() -> u64 {
+    Config::DEFAULT_LONG_BREAK_SECONDS
+}
+
+impl Profile {
+    /// 内置方案：番茄钟 25/5（每 4 次一次 15 分钟长休息）、52/17、20-20-20 护眼法则
+    pub fn presets() -> Vec<Self> {
+        vec![
+            Self {
+                name: "Pomodoro 25/5".to_string(),
+                interval_minutes: 25,
+                break_seconds: 5 * 60,
+                long_break_every: 4,
+                long_break_seconds: 15 * 60,
+            },
+            Self {
+                name: "52/17".to_string(),
+                interval_minutes: 52,
+                break_seconds: 17 * 60,
+                long_break_every: 0,
+                long_break_seconds: Config::DEFAULT_LONG_BREAK_SECONDS,
+            },
+            Self {
+                name: "20-20-20".to_string(),
+                interval_minutes: 20,
+                break_seconds: 20,
+                long_break_every: 0,
+                long_break_seconds: Config::DEFAULT_LONG_BREAK_SECONDS,
+            },
+        ]
+    }
+
+    /// 以配置中的当前时长创建方案
+    pub fn from_config(name: &str, config: &Config) -> Self {
+        Self {
+            name: name.to_string(),
+            interval_minutes: config.interval_minutes,
+            break_seconds: config.break_seconds,
+            long_break_every: config.long_break_every,
+            long_break_seconds: config.long_break_seconds,
+        }
+    }
+
+    /// 把方案的时长写入配置
+    pub const fn apply_to(&self, config: &mut Config) {
+        config.interval_minutes = self.interval_minutes;
+        config.break_seconds = self.break_seconds;
+        config.long_break_every = self.long_break_every;
+        config.long_break_seconds = self.long_break_seconds;
+    }
+
+    /// 限制到与配置相同的合法范围内
+    pub fn clamped(&self) -> Self {
+        Self {
+            name: self.name.trim().to_string(),
+            interval_minutes: clamp_u64(
+                self.interval_minutes,
+                Config::MIN_INTERVAL_MINUTES,
+                Config::MAX_INTERVAL_MINUTES,
+            ),
+            break_seconds: clamp_u64(
+                self.break_seconds,
+                Config::MIN_BREAK_SECONDS,
+                Config::MAX_BREAK_SECONDS,
+            ),
+            long_break_every: self.long_break_every.min(Config::MAX_LONG_BREAK_EVERY),
+            long_break_seconds: clamp_u64(
+                self.long_break_seconds,
+                Config::MIN_BREAK_SECONDS,
+                Config::MAX_BREAK_SECONDS,
+            ),
+        }
+    }
+
+    /// 列出超出合法范围的值，`field` 为方案在配置中的位置（如 `profiles[0]`）
+    pub fn out_of_range(&self, field: &str) -> Vec<Rejected> {
+        let mut rejected: Vec<Rejected> = [
+            (
+                "interval_minutes",
+                self.interval_minutes,
+                Config::MIN_INTERVAL_MINUTES,
+                Config::MAX_INTERVAL_MINUTES,
+            ),
+            (
+                "break_seconds",
+                self.break_seconds,
+                Config::MIN_BREAK_SECONDS,
+                Config::MAX_BREAK_SECONDS,
+            ),
+            (
+                "long_break_every",
+                self.long_break_every,
+                0,
+                Config::MAX_LONG_BREAK_EVERY,
+            ),
+            (
+                "long_break_seconds",
+                self.long_break_seconds,
+                Config::MIN_BREAK_SECONDS,
+                Config::MAX_BREAK_SECONDS,
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, value, min, max)| !(min..=max).contains(&value))
+        .map(|(name, value, min, max)| Rejected {
+            field: format!("{field}.{name}"),
+            value: value.to_string(),
+            reason: RejectReason::OutOfRange { min, max },
+        })
+        .collect();
+
+        if self.name.trim().is_empty() {
+            rejected.push(Rejected {
+                field: format!("{field}.name"),
+                value: format!("{:?}", self.name),
+                reason: RejectReason::Invalid("方案名称不能为空".to_string()),
+            });
+        }
+        rejected
+    }
+
+    /// 时长是否与另一方案相同（不比较名称）
+    fn same_timings(&self, other: &Self) -> bool {
+        (
+            self.interval_minutes,
+            self.break_seconds,
+            self.long_break_every,
+            self.long_break_seconds,
+        ) == (
+            other.interval_minutes,
+            other.break_seconds,
+            other.long_break_every,
+            other.long_break_seconds,
+        )
+    }
+}
+
+impl Config {
+    /// 可切换的全部方案：未被覆盖的内置方案在前，用户方案在后
+    pub fn available_profiles(&self) -> Vec<Profile> {
+        let mut profiles: Vec<Profile> = Profile::presets()
+            .into_iter()
+            .filter(|preset| !self.profiles.iter().any(|p| p.name == preset.name))
+            .collect();
+        profiles.extend(self.profiles.iter().cloned());
+        profiles
+    }
+
+    /// 按名称查找方案
+    pub fn find_profile(&self, name: &str) -> Option<Profile> {
+        self.available_profiles()
+            .into_iter()
+            .find(|profile| profile.name == name)
+    }
+
+    /// 切换到指定方案；方案不存在时返回 `false`
+    pub fn switch_profile(&mut self, name: &str) -> bool {
+        let Some(profile) = self.find_profile(name) else {
+            return false;
+        };
+        profile.apply_to(self);
+        self.active_profile = Some(profile.name);
+        true
+    }
+
+    /// 以当前时长保存为指定名称的方案并设为当前方案；名称为空时不使用任何方案
+    ///
+    /// 与同名内置方案时长相同时不另存副本。
+    pub fn save_profile(&mut self, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            self.active_profile = None;
+            return;
+        }
+        let profile = Profile::from_config(name, self);
+        let matches_preset = Profile::presets()
+            .iter()
+            .any(|preset| preset.name == name && preset.same_timings(&profile));
+
+        self.profiles.retain(|p| p.name != name);
+        if !matches_preset {
+            self.profiles.push(profile);
+        }
+        self.active_profile = Some(name.to_string());
+    }
+
+    /// 当前方案的时长是否仍与配置一致（设置页修改时长后可能不一致）
+    pub fn active_profile_matches(&self) -> bool {
+        self.active_profile
+            .as_deref()
+            .and_then(|name| self.find_profile(name))
+            .is_some_and(|profile| profile.same_timings(&Profile::from_config("", self)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_are_within_bounds() {
+        for preset in Profile::presets() {
+            assert_eq!(preset.clamped(), preset);
+            assert!(preset.out_of_range("preset").is_empty());
+        }
+    }
+
+    #[test]
+    fn switching_applies_timings_only() {
+        let mut config = Config {
+            theme: crate::config::Theme::Light,
+            ..Config::default()
+        };
+        assert!(config.switch_profile("52/17"));
+        assert_eq!(config.interval_minutes, 52);
+        assert_eq!(config.break_seconds, 1020);
+        assert_eq!(config.theme, crate::config::Theme::Light);
+        assert_eq!(config.active_profile.as_deref(), Some("52/17"));
+        assert!(config.active_profile_matches());
+
+        assert!(!config.switch_profile("Crunch"));
+        assert_eq!(config.active_profile.as_deref(), Some("52/17"));
+
+        config.break_seconds = 600;
+        assert!(!config.active_profile_matches());
+    }
+
+    #[test]
+    fn saved_profiles_shadow_presets_and_can_be_switched_back() {
+        let mut config = Config {
+            interval_minutes: 50,
+            break_seconds: 600,
+            ..Config::default()
+        };
+        config.save_profile(" Deep work ");
+        assert_eq!(config.active_profile.as_deref(), Some("Deep work"));
+
+        config.switch_profile("20-20-20");
+        assert_eq!(config.interval_minutes, 20);
+        config.switch_profile("Deep work");
+        assert_eq!(config.interval_minutes, 50);
+
+        // 与内置方案时长一致时不另存副本
+        config.switch_profile("52/17");
+        config.save_profile("52/17");
+        assert_eq!(config.profiles.len(), 1);
+
+        config.break_seconds = 900;
+        config.save_profile("52/17");
+        let names: Vec<_> = config
+            .available_profiles()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, ["Pomodoro 25/5", "20-20-20", "Deep work", "52/17"]);
+        assert_eq!(config.find_profile("52/17").unwrap().break_seconds, 900);
+
+        config.save_profile("");
+        assert_eq!(config.active_profile, None);
+    }
+}
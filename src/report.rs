@@ -0,0 +1,606 @@
+//! 休息习惯报告。
+//!
+//! 由本地休息历史生成单个 HTML 页面（`restgap report --out report.html`）：休息日历热力图、
+//! 每周遵守率趋势、跳过休息的时段分布和最长的无跳过连续天数。图表都是内联 SVG，页面不引用
+//! 任何外部资源，可离线打开；配色与设置页的深色/浅色主题一致。
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::config::Theme;
+use crate::history::Record;
+use crate::i18n::Texts;
+use crate::stats::{self, DayStats, Streak, Tally, WeekStats};
+
+/// 热力图覆盖的周数（含本周）
+const HEATMAP_WEEKS: i64 = 53;
+/// 趋势图最多显示的周数
+const TREND_WEEKS: usize = 26;
+/// 列出的最长连续天数条目数
+const TOP_STREAKS: usize = 3;
+
+/// 热力图方格边长与间距
+const CELL: i64 = 11;
+const CELL_STEP: i64 = 14;
+/// 图表的宽度与绘图区高度
+const CHART_WIDTH: i64 = 640;
+const CHART_HEIGHT: i64 = 120;
+
+/// 报告数据，由历史记录计算得出
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Report {
+    /// 生成报告当天（本地天数）
+    pub today: i64,
+    /// 有记录的日期
+    pub days: Vec<DayStats>,
+    /// 每周汇总
+    pub weeks: Vec<WeekStats>,
+    /// 全部历史的合计
+    pub total: Tally,
+    /// 跳过休息的次数按小时分布
+    pub skips_by_hour: [u32; 24],
+    /// 最长的无跳过连续天数（当天至少休息一次且没有跳过），从长到短，同样长时较近的在前
+    pub best_streaks: Vec<Streak>,
+}
+
+impl Report {
+    pub fn new(records: &[Record], today: i64, utc_offset: i64) -> Self {
+        let days = stats::daily(records, utc_offset);
+        let mut best_streaks = stats::streaks(&days, |tally| tally.taken > 0 && tally.skipped == 0);
+        best_streaks.sort_by_key(|streak| (Reverse(streak.days()), Reverse(streak.end)));
+        best_streaks.truncate(TOP_STREAKS);
+        Self {
+            today,
+            weeks: stats::weekly(&days),
+            total: stats::total(&days),
+            skips_by_hour: stats::skips_by_hour(records, utc_offset),
+            best_streaks,
+            days,
+        }
+    }
+
+    /// 生成完整的 HTML 页面，文字使用 `texts` 的语言
+    pub fn to_html(&self, theme: Theme, texts: &Texts) -> String {
+        REPORT_HTML_TEMPLATE
+            .replace("__LANG__", texts.report_html_lang())
+            .replace(
+                "__THEME_CLASS__",
+                match theme {
+                    Theme::Dark => "dark",
+                    Theme::Light => "light",
+                },
+            )
+            .replace("__TITLE__", texts.report_title())
+            .replace(
+                "__SUBTITLE__",
+                &texts.report_subtitle(&stats::format_date(self.today)),
+            )
+            .replace("__OVERVIEW_LABEL__", texts.report_section_overview())
+            .replace("__HEATMAP_LABEL__", texts.report_section_heatmap())
+            .replace("__TREND_LABEL__", texts.report_section_trend())
+            .replace("__SKIP_HOURS_LABEL__", texts.report_section_skip_hours())
+            .replace("__STREAKS_LABEL__", texts.report_section_streaks())
+            .replace("__FOOTER__", texts.report_footer())
+            .replace("__SUMMARY__", &self.summary_html(texts))
+            .replace("__HEATMAP__", &self.heatmap_svg(texts))
+            .replace("__TREND__", &self.trend_svg(texts))
+            .replace("__SKIP_HOURS__", &self.skip_hours_svg(texts))
+            .replace("__STREAKS__", &self.streaks_html(texts))
+    }
+
+    fn summary_html(&self, texts: &Texts) -> String {
+        let total = &self.total;
+        let compliance = total
+            .compliance_percent()
+            .map_or_else(|| "—".to_string(), |percent| format!("{percent}%"));
+        let cards = [
+            (texts.report_taken_label(), texts.report_times(total.taken)),
+            (
+                texts.report_skipped_label(),
+                texts.report_times(total.skipped),
+            ),
+            (texts.report_compliance_label(), compliance),
+            (
+                texts.report_rest_time_label(),
+                texts.report_duration(total.rest_seconds),
+            ),
+            (
+                texts.report_longest_work_label(),
+                texts.report_duration(total.longest_work_seconds),
+            ),
+        ];
+        let mut html = String::new();
+        for (label, value) in cards {
+            let _ = write!(
+                html,
+                r#"<div class="stat"><div class="stat-value">{value}</div><div class="stat-label">{label}</div></div>"#
+            );
+        }
+        html
+    }
+
+    /// 最近 [`HEATMAP_WEEKS`] 周每天完成的休息次数，列为周、行为周一至周日
+    fn heatmap_svg(&self, texts: &Texts) -> String {
+        let first = stats::week_start(self.today) - (HEATMAP_WEEKS - 1) * 7;
+        let by_day: BTreeMap<i64, &Tally> = self
+            .days
+            .iter()
+            .filter(|stats| (first..=self.today).contains(&stats.day))
+            .map(|stats| (stats.day, &stats.tally))
+            .collect();
+        let max = by_day.values().map(|tally| tally.taken).max().unwrap_or(0);
+
+        let (left, top) = (24, 16);
+        let mut svg = format!(
+            r#"<svg class="heatmap" viewBox="0 0 {} {}" role="img">"#,
+            left + HEATMAP_WEEKS * CELL_STEP,
+            top + 7 * CELL_STEP
+        );
+        for (row, label) in [0, 2, 4].into_iter().zip(texts.report_weekday_labels()) {
+            let _ = write!(
+                svg,
+                r#"<text class="axis" x="0" y="{}">{label}</text>"#,
+                top + row * CELL_STEP + CELL - 1
+            );
+        }
+        for day in first..=self.today {
+            let (week, weekday) = ((day - first) / 7, (day - first) % 7);
+            let x = left + week * CELL_STEP;
+            let (_, month, date) = stats::civil_date(day);
+            if date == 1 {
+                let _ = write!(
+                    svg,
+                    r#"<text class="axis" x="{x}" y="10">{}</text>"#,
+                    texts.report_month_label(month)
+                );
+            }
+            let tally = by_day.get(&day).copied().copied().unwrap_or_default();
+            let _ = write!(
+                svg,
+                r#"<rect class="cell l{}" x="{x}" y="{}" width="{CELL}" height="{CELL}" rx="2"><title>{}</title></rect>"#,
+                heat_level(tally.taken, max),
+                top + weekday * CELL_STEP,
+                texts.report_day_tooltip(&stats::format_date(day), tally.taken, tally.skipped)
+            );
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+
+    /// 最近 [`TREND_WEEKS`] 个有应休息次数的周的遵守率折线
+    fn trend_svg(&self, texts: &Texts) -> String {
+        let weeks: Vec<(&WeekStats, u8)> = self
+            .weeks
+            .iter()
+            .filter_map(|week| Some((week, week.tally.compliance_percent()?)))
+            .collect();
+        let weeks = &weeks[weeks.len().saturating_sub(TREND_WEEKS)..];
+        if weeks.is_empty() {
+            return empty(texts);
+        }
+
+        let (left, top, right) = (40, 8, 16);
+        let width = CHART_WIDTH - left - right;
+        let y = |percent: u8| top + (100 - i64::from(percent)) * CHART_HEIGHT / 100;
+        let mut svg = format!(
+            r#"<svg class="chart" viewBox="0 0 {CHART_WIDTH} {}" role="img">"#,
+            top + CHART_HEIGHT + 24
+        );
+        for percent in [0, 50, 100] {
+            let _ = write!(
+                svg,
+                r#"<line class="grid" x1="{left}" x2="{}" y1="{y}" y2="{y}"/><text class="axis" x="0" y="{}">{percent}%</text>"#,
+                CHART_WIDTH - right,
+                y(percent) + 4,
+                y = y(percent)
+            );
+        }
+
+        let count = i64::try_from(weeks.len()).unwrap_or(i64::MAX);
+        let x = |i: i64| {
+            if count == 1 {
+                left + width / 2
+            } else {
+                left + i * width / (count - 1)
+            }
+        };
+        let points: Vec<String> = (0..)
+            .zip(weeks)
+            .map(|(i, (_, percent))| format!("{},{}", x(i), y(*percent)))
+            .collect();
+        let _ = write!(
+            svg,
+            r#"<polyline class="trend" points="{}"/>"#,
+            points.join(" ")
+        );
+        for (i, (week, percent)) in (0..).zip(weeks) {
+            let _ = write!(
+                svg,
+                r#"<circle class="dot" cx="{}" cy="{}" r="3.5"><title>{}</title></circle>"#,
+                x(i),
+                y(*percent),
+                texts.report_week_tooltip(
+                    &stats::format_date(week.week_start),
+                    *percent,
+                    week.tally.taken,
+                    week.tally.due()
+                )
+            );
+        }
+        // 首尾两周的日期
+        let (first, last) = (weeks[0].0, weeks[weeks.len() - 1].0);
+        let label_y = top + CHART_HEIGHT + 18;
+        let _ = write!(
+            svg,
+            r#"<text class="axis" x="{left}" y="{label_y}">{}</text>"#,
+            stats::format_date(first.week_start)
+        );
+        if count > 1 {
+            let _ = write!(
+                svg,
+                r#"<text class="axis end" x="{}" y="{label_y}">{}</text>"#,
+                CHART_WIDTH - right,
+                stats::format_date(last.week_start)
+            );
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+
+    /// 每个小时跳过休息的次数柱状图
+    fn skip_hours_svg(&self, texts: &Texts) -> String {
+        let max = self.skips_by_hour.iter().copied().max().unwrap_or(0);
+        if max == 0 {
+            return empty(texts);
+        }
+
+        let (left, top) = (16, 8);
+        let step = (CHART_WIDTH - 2 * left) / 24;
+        let mut svg = format!(
+            r#"<svg class="chart" viewBox="0 0 {CHART_WIDTH} {}" role="img">"#,
+            top + CHART_HEIGHT + 24
+        );
+        let _ = write!(
+            svg,
+            r#"<line class="grid" x1="{left}" x2="{}" y1="{y}" y2="{y}"/>"#,
+            CHART_WIDTH - left,
+            y = top + CHART_HEIGHT
+        );
+        for (hour, &skips) in (0_i64..).zip(&self.skips_by_hour) {
+            let x = left + hour * step;
+            let height = i64::from(skips) * CHART_HEIGHT / i64::from(max);
+            let _ = write!(
+                svg,
+                r#"<rect class="bar" x="{}" y="{}" width="{}" height="{height}" rx="2"><title>{}</title></rect>"#,
+                x + 2,
+                top + CHART_HEIGHT - height,
+                step - 4,
+                texts.report_hour_tooltip(hour, skips)
+            );
+            if hour % 3 == 0 {
+                let _ = write!(
+                    svg,
+                    r#"<text class="axis" x="{}" y="{}">{hour}</text>"#,
+                    x + 2,
+                    top + CHART_HEIGHT + 16
+                );
+            }
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+
+    fn streaks_html(&self, texts: &Texts) -> String {
+        if self.best_streaks.is_empty() {
+            return empty(texts);
+        }
+        let mut html = String::from(r#"<ol class="streaks">"#);
+        for streak in &self.best_streaks {
+            let _ = write!(
+                html,
+                r#"<li><span class="streak-days">{}</span><span class="streak-range">{} – {}</span></li>"#,
+                texts.report_streak_days(streak.days()),
+                stats::format_date(streak.start),
+                stats::format_date(streak.end)
+            );
+        }
+        html.push_str("</ol>");
+        html
+    }
+}
+
+/// 没有数据时显示的占位
+fn empty(texts: &Texts) -> String {
+    format!(r#"<p class="empty">{}</p>"#, texts.report_no_data())
+}
+
+/// 热力图颜色等级：0 表示没有休息，1–4 按当天次数占最多一天的比例
+fn heat_level(taken: u32, max: u32) -> u32 {
+    if taken == 0 || max == 0 {
+        return 0;
+    }
+    (taken * 4).div_ceil(max).clamp(1, 4)
+}
+
+/// 页面模板；配色变量与设置页相同
+const REPORT_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="__LANG__" class="__THEME_CLASS__">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <title>__TITLE__</title>
+    <style>
+        :root {
+            --bg: #1C1C1E;
+            --card-bg: rgba(255, 255, 255, 0.04);
+            --text: #FFFFFF;
+            --text-dim: rgba(255, 255, 255, 0.45);
+            --accent: #0A84FF;
+            --line: rgba(255, 255, 255, 0.08);
+            --cell-empty: rgba(255, 255, 255, 0.06);
+            --border-glow: rgba(255, 255, 255, 0.05);
+            --font-sans: -apple-system, BlinkMacSystemFont, "SF Pro Display", "SF Pro Text", "PingFang SC", sans-serif;
+            --radius: 14px;
+        }
+        html.light {
+            --bg: #F2F2F7;
+            --card-bg: #FFFFFF;
+            --text: #1C1C1E;
+            --text-dim: rgba(0, 0, 0, 0.45);
+            --accent: #007AFF;
+            --line: rgba(0, 0, 0, 0.08);
+            --cell-empty: rgba(0, 0, 0, 0.06);
+            --border-glow: rgba(0, 0, 0, 0.05);
+        }
+        body {
+            margin: 0 auto;
+            max-width: 760px;
+            padding: 32px 24px;
+            background-color: var(--bg);
+            color: var(--text);
+            font-family: var(--font-sans);
+            -webkit-font-smoothing: antialiased;
+        }
+        .title {
+            font-size: 22px;
+            font-weight: 700;
+            letter-spacing: -0.02em;
+        }
+        .subtitle {
+            font-size: 11px;
+            font-weight: 500;
+            color: var(--text-dim);
+            text-transform: uppercase;
+            letter-spacing: 0.05em;
+            margin: 2px 0 32px;
+        }
+        .section-label {
+            font-size: 12px;
+            font-weight: 600;
+            color: var(--text-dim);
+            margin: 0 0 8px 8px;
+            text-transform: uppercase;
+        }
+        .card {
+            background: var(--card-bg);
+            border: 1px solid var(--border-glow);
+            border-radius: var(--radius);
+            padding: 16px;
+            margin-bottom: 24px;
+        }
+        .stats {
+            display: grid;
+            grid-template-columns: repeat(5, 1fr);
+            gap: 12px;
+        }
+        .stat-value {
+            font-size: 20px;
+            font-weight: 700;
+        }
+        .stat-label {
+            font-size: 12px;
+            color: var(--text-dim);
+            margin-top: 4px;
+        }
+        svg {
+            display: block;
+            width: 100%;
+            height: auto;
+        }
+        .axis {
+            fill: var(--text-dim);
+            font-size: 10px;
+        }
+        .axis.end {
+            text-anchor: end;
+        }
+        .grid {
+            stroke: var(--line);
+        }
+        .cell.l0 { fill: var(--cell-empty); }
+        .cell.l1 { fill: var(--accent); fill-opacity: 0.3; }
+        .cell.l2 { fill: var(--accent); fill-opacity: 0.5; }
+        .cell.l3 { fill: var(--accent); fill-opacity: 0.75; }
+        .cell.l4 { fill: var(--accent); }
+        .trend {
+            fill: none;
+            stroke: var(--accent);
+            stroke-width: 2;
+            stroke-linejoin: round;
+        }
+        .dot, .bar {
+            fill: var(--accent);
+        }
+        .streaks {
+            margin: 0;
+            padding-left: 20px;
+        }
+        .streaks li {
+            padding: 6px 0;
+            border-bottom: 1px solid var(--line);
+        }
+        .streaks li:last-child {
+            border-bottom: none;
+        }
+        .streak-days {
+            font-weight: 600;
+            display: inline-block;
+            min-width: 64px;
+        }
+        .streak-range, .empty, .footer {
+            color: var(--text-dim);
+            font-size: 13px;
+        }
+        .empty {
+            margin: 8px 0;
+        }
+        .footer {
+            text-align: center;
+            font-size: 11px;
+        }
+    </style>
+</head>
+<body>
+    <div class="title">__TITLE__</div>
+    <div class="subtitle">__SUBTITLE__</div>
+
+    <div class="section-label">__OVERVIEW_LABEL__</div>
+    <div class="card stats">__SUMMARY__</div>
+
+    <div class="section-label">__HEATMAP_LABEL__</div>
+    <div class="card">__HEATMAP__</div>
+
+    <div class="section-label">__TREND_LABEL__</div>
+    <div class="card">__TREND__</div>
+
+    <div class="section-label">__SKIP_HOURS_LABEL__</div>
+    <div class="card">__SKIP_HOURS__</div>
+
+    <div class="section-label">__STREAKS_LABEL__</div>
+    <div class="card">__STREAKS__</div>
+
+    <div class="footer">__FOOTER__</div>
+</body>
+</html>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::RecordKind;
+    use crate::i18n::Language;
+    use crate::scheduler::BreakKind;
+    use crate::work_hours::SECS_PER_DAY;
+
+    /// 2024-01-01（周一）的本地天数
+    const MONDAY: i64 = 19_723;
+
+    fn record(day: i64, hh: u64, kind: RecordKind) -> Record {
+        Record {
+            timestamp: u64::try_from(day * SECS_PER_DAY).unwrap() + hh * 3600,
+            kind,
+            break_kind: BreakKind::Short,
+            planned_seconds: 120,
+            actual_seconds: if kind.is_rest() { 120 } else { 0 },
+            reason: None,
+        }
+    }
+
+    /// 两周的历史：第一周前三天每天两次休息，周四跳过一次；第二周每天一次
+    fn sample() -> Vec<Record> {
+        let mut records = Vec::new();
+        for day in MONDAY..MONDAY + 3 {
+            records.push(record(day, 9, RecordKind::Completed));
+            records.push(record(day, 15, RecordKind::Completed));
+        }
+        records.push(record(MONDAY + 3, 10, RecordKind::Completed));
+        records.push(record(MONDAY + 3, 14, RecordKind::Skipped));
+        for day in MONDAY + 7..MONDAY + 12 {
+            records.push(record(day, 11, RecordKind::Completed));
+        }
+        records.push(record(MONDAY + 12, 14, RecordKind::Skipped));
+        records
+    }
+
+    #[test]
+    fn report_collects_streaks_and_skips() {
+        let report = Report::new(&sample(), MONDAY + 13, 0);
+        assert_eq!((report.total.taken, report.total.skipped), (12, 2));
+        assert_eq!(report.weeks.len(), 2);
+        assert_eq!(report.skips_by_hour[14], 2);
+        assert_eq!(
+            report.best_streaks,
+            [
+                Streak {
+                    start: MONDAY + 7,
+                    end: MONDAY + 11,
+                },
+                Streak {
+                    start: MONDAY,
+                    end: MONDAY + 2,
+                },
+            ]
+        );
+        assert_eq!(heat_level(0, 4), 0);
+        assert_eq!(
+            (heat_level(1, 4), heat_level(3, 4), heat_level(4, 4)),
+            (1, 3, 4)
+        );
+        let texts = Texts::new(Language::Zh);
+        assert_eq!(texts.report_duration(125 * 60), "2 小时 5 分");
+        assert_eq!(texts.report_duration(120 * 60), "2 小时");
+        assert_eq!(Texts::new(Language::En).report_duration(5 * 60), "5 min");
+    }
+
+    #[test]
+    fn html_is_self_contained_and_themed() {
+        let report = Report::new(&sample(), MONDAY + 13, 0);
+        let html = report.to_html(Theme::Light, &Texts::new(Language::Zh));
+        assert!(html.contains(r#"<html lang="zh-CN" class="light">"#));
+        assert!(!html.contains("__"), "未替换的占位符");
+        // 不引用任何外部资源
+        for needle in [
+            "http:", "https:", "src=", "<link", "<script", "@import", "url(",
+        ] {
+            assert!(!html.contains(needle), "{needle}");
+        }
+        // 热力图从 52 周前的周一画到今天；今天是周日，正好画满 53 周
+        let cells = usize::try_from(HEATMAP_WEEKS * 7).unwrap();
+        assert_eq!(html.matches(r#"<rect class="cell"#).count(), cells);
+        assert!(html.contains("2024-01-09：休息 1 次，跳过 0 次"));
+        assert!(html.contains("2024-01-01 起的一周：88%（7/8）"));
+        assert!(html.contains("14:00–14:59：跳过 2 次"));
+
+        let empty = Report::new(&[], MONDAY, 0).to_html(Theme::Dark, &Texts::new(Language::Zh));
+        assert!(empty.contains(r#"class="dark""#));
+        assert_eq!(empty.matches(r#"<p class="empty">暂无数据</p>"#).count(), 3);
+    }
+
+    #[test]
+    fn html_follows_the_language() {
+        let report = Report::new(&sample(), MONDAY + 13, 0);
+        let html = report.to_html(Theme::Dark, &Texts::new(Language::En));
+        assert!(html.contains(r#"<html lang="en" class="dark">"#));
+        assert!(!html.contains("__"), "未替换的占位符");
+        for needle in [
+            "<title>RestGap · Break Report</title>",
+            r#"<div class="stat-value">12 times</div><div class="stat-label">Taken</div>"#,
+            ">Mon</text>",
+            ">Jan</text>",
+            "2024-01-09: 1 taken, 0 skipped",
+            "Week of 2024-01-01: 88% (7/8)",
+            "14:00–14:59: 2 skipped",
+            r#"<span class="streak-days">5 days</span>"#,
+        ] {
+            assert!(html.contains(needle), "{needle}");
+        }
+        // 页面中不应残留中文
+        assert!(
+            !html
+                .chars()
+                .any(|ch| ('\u{4e00}'..='\u{9fff}').contains(&ch)),
+            "{html}"
+        );
+    }
+}
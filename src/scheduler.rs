@@ -0,0 +1,2543 @@
+//! 与平台无关的工作/休息调度核心。
+//!
+//! 只负责阶段状态机与截止时间计算，不直接创建定时器或操作 UI。
+//! 每次状态变化都会返回一组 [`Command`]，由平台层（如 `macos::timer`）依次执行。
+
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::idle::{BreakCompliance, IdleDecision, IdlePolicy, IdleReason};
+use crate::work_hours::{self, WorkHours};
+
+/// 墙上时钟比单调时钟多走超过该时长时视为系统睡眠过（小于该值的差异可能来自对时）
+pub const MIN_SLEEP_GAP: Duration = Duration::from_secs(30);
+
+/// 等待用户回来期间采样空闲时长的间隔
+pub const RETURN_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 时钟抽象，测试时可注入假时钟
+pub trait Clock {
+    /// 单调时钟当前时刻
+    fn now(&self) -> Instant;
+    /// 墙上时钟当前时刻
+    fn now_wall(&self) -> SystemTime;
+    /// 本地时区相对 UTC 的偏移秒数（东区为正）
+    fn utc_offset(&self) -> i64;
+
+    /// 本地时间自 Unix 纪元起的秒数
+    fn local_secs(&self) -> i64 {
+        work_hours::local_secs(self.now_wall(), self.utc_offset())
+    }
+}
+
+/// 系统空闲时长来源
+pub trait IdleSource {
+    /// 距离最后一次用户输入的时长；无法获取时返回 `None`
+    fn idle_duration(&self) -> Option<Duration>;
+}
+
+/// 基于系统时间的默认时钟
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn now_wall(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    #[cfg(unix)]
+    #[allow(unsafe_code)]
+    fn utc_offset(&self) -> i64 {
+        let now: libc::time_t = unsafe { libc::time(std::ptr::null_mut()) };
+        let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+        let tm_ptr =
+            unsafe { libc::localtime_r(std::ptr::addr_of!(now), std::ptr::addr_of_mut!(tm)) };
+        if tm_ptr.is_null() {
+            return 0;
+        }
+        #[allow(clippy::useless_conversion)] // `c_long` 在 32 位平台上是 `i32`
+        i64::from(tm.tm_gmtoff)
+    }
+
+    #[cfg(not(unix))]
+    fn utc_offset(&self) -> i64 {
+        0
+    }
+}
+
+/// 基于 [`crate::idle`] 的系统空闲时长来源
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemIdle;
+
+impl IdleSource for SystemIdle {
+    fn idle_duration(&self) -> Option<Duration> {
+        crate::idle::current_idle_duration()
+    }
+}
+
+/// 工作阶段
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    Working,
+    /// 休息前的预告阶段，可立即开始或推迟
+    PreBreak,
+    Breaking,
+    /// 不在工作时段内，等待下一个时段开始
+    OffHours,
+    /// 休息结束后等待用户回到电脑前，检测到输入后才开始工作计时
+    AwaitingReturn,
+}
+
+/// 休息类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakKind {
+    /// 常规短休息
+    Short,
+    /// 每 N 次休息安排一次的长休息
+    Long,
+}
+
+/// 暂停提醒的方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pause {
+    /// 暂停到指定墙上时刻后自动恢复
+    Until(SystemTime),
+    /// 无限期暂停，直到手动恢复
+    Indefinitely,
+}
+
+impl Pause {
+    /// 从现在起暂停指定时长
+    pub fn for_duration(duration: Duration, clock: &impl Clock) -> Self {
+        Self::Until(clock.now_wall() + duration)
+    }
+
+    /// 暂停到本地时间的次日 00:00
+    pub fn until_tomorrow(clock: &impl Clock) -> Self {
+        let wait =
+            work_hours::SECS_PER_DAY - clock.local_secs().rem_euclid(work_hours::SECS_PER_DAY);
+        Self::for_duration(Duration::from_secs(wait.unsigned_abs()), clock)
+    }
+}
+
+/// 调度所需的时长参数
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timings {
+    pub work_interval: Duration,
+    pub break_duration: Duration,
+    pub work_tolerance: Duration,
+    pub break_tolerance: Duration,
+    pub long_break_duration: Duration,
+    pub long_break_tolerance: Duration,
+    /// 每隔多少次休息安排一次长休息（0 表示关闭）
+    pub long_break_every: u64,
+    pub postpone_duration: Duration,
+    /// 每轮最多可推迟的次数（0 表示关闭）
+    pub max_postpones: u32,
+    /// 休息前预告时长（0 表示关闭）
+    pub pre_break_duration: Duration,
+    /// 工作期间是否低频采样空闲时长，将足够长的离开视为一次自然休息
+    pub natural_break: bool,
+    /// 工作周期结束时的闲置跳过策略
+    pub idle_policy: IdlePolicy,
+    /// 活跃时间模式：超过该时长的连续空闲不计入工作时长；`None` 表示按墙上时间计时
+    pub active_threshold: Option<Duration>,
+    /// 休息结束时若并未真正休息，按操作电脑的时长延长一次休息
+    pub extend_active_breaks: bool,
+    /// 休息结束后等检测到输入再开始下一轮工作计时
+    pub await_return: bool,
+}
+
+/// 调度过程中产生的事件，由平台层负责记录
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// 休息按时结束，附带是否真正休息的判定与实际时长（含延长与睡眠）
+    BreakCompleted {
+        kind: BreakKind,
+        compliance: BreakCompliance,
+        elapsed: Duration,
+    },
+    /// 休息期间仍在操作电脑，休息被延长
+    BreakExtended { kind: BreakKind, extra: Duration },
+    /// 用户主动跳过休息，附带跳过前已休息的时长
+    BreakSkipped { kind: BreakKind, elapsed: Duration },
+    /// 用户推迟休息
+    BreakPostponed {
+        kind: BreakKind,
+        postpones_left: u32,
+    },
+    /// 按闲置策略自动跳过休息
+    IdleSkipped { kind: BreakKind, reason: IdleReason },
+    /// 工作期间连续空闲达到休息时长，视为已完成休息；`idle` 为采样时已空闲的时长
+    NaturalBreak { kind: BreakKind, idle: Duration },
+    /// 用户暂停提醒
+    Paused { pause: Pause },
+    /// 暂停结束，恢复提醒
+    Resumed,
+    /// 系统睡眠时长达到休息时长，视为已完成休息
+    SleepRest { kind: BreakKind, slept: Duration },
+    /// 系统从较短的睡眠中唤醒，阶段截止时间已校正
+    Woke { slept: Duration },
+    /// 休息后检测到输入，用户已回到电脑前
+    Returned { away: Duration },
+}
+
+/// 调度器输出的指令，由平台层执行
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    /// （重新）设置阶段定时器，在 `after` 之后触发
+    ArmTimer {
+        after: Duration,
+        tolerance: Duration,
+    },
+    /// 弹出休息倒计时界面
+    ShowBreak { seconds: u64, kind: BreakKind },
+    /// 结束休息并关闭倒计时界面
+    EndBreak,
+    /// 关闭倒计时界面（休息被推迟，不视为结束）
+    HideBreak,
+    /// 将正在进行的休息倒计时重设为剩余 `remaining`（休息延长或睡眠唤醒后校正）
+    UpdateCountdown { remaining: Duration },
+    /// 取消阶段定时器
+    CancelTimer,
+    /// 记录事件
+    Record(Event),
+}
+
+/// 工作/休息阶段调度器
+#[derive(Debug)]
+pub struct Scheduler {
+    timings: Timings,
+    phase: Phase,
+    phase_started_at: Option<Instant>,
+    /// 阶段开始时的墙上时刻，与 `phase_started_at` 对照即可得出系统睡眠时长
+    phase_started_wall: Option<SystemTime>,
+    deadline: Option<Instant>,
+    deadline_wall: Option<SystemTime>,
+    /// 活跃时间模式下最近一次核算空闲的时刻
+    idle_checked_at: Option<Instant>,
+    /// 当前（或即将开始、最近一次）休息的类型
+    break_kind: BreakKind,
+    /// 自上次长休息以来结束的短休息次数，设置变更时保留
+    short_breaks_since_long: u64,
+    /// 本轮已推迟的次数
+    postpones_used: u32,
+    /// 当前休息是否已经延长过
+    break_extended: bool,
+    /// 仅在这些时段内提醒；`None` 表示全天提醒
+    work_hours: Option<WorkHours>,
+    /// 暂停状态，与 `phase` 并存；暂停期间不安排任何休息
+    pause: Option<Pause>,
+    /// 最近一次因闲置跳过休息的原因，下一次休息开始时清除
+    last_idle_skip: Option<IdleReason>,
+}
+
+impl Scheduler {
+    pub const fn new(timings: Timings) -> Self {
+        Self {
+            timings,
+            phase: Phase::Working,
+            phase_started_at: None,
+            phase_started_wall: None,
+            deadline: None,
+            deadline_wall: None,
+            idle_checked_at: None,
+            break_kind: BreakKind::Short,
+            short_breaks_since_long: 0,
+            postpones_used: 0,
+            break_extended: false,
+            work_hours: None,
+            pause: None,
+            last_idle_skip: None,
+        }
+    }
+
+    pub const fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub const fn deadline_wall(&self) -> Option<SystemTime> {
+        self.deadline_wall
+    }
+
+    /// 当前（或即将开始、最近一次）休息的类型
+    pub const fn break_kind(&self) -> BreakKind {
+        self.break_kind
+    }
+
+    /// 下一次休息的类型
+    pub const fn next_break_kind(&self) -> BreakKind {
+        let every = self.timings.long_break_every;
+        if every > 0 && self.short_breaks_since_long + 1 >= every {
+            BreakKind::Long
+        } else {
+            BreakKind::Short
+        }
+    }
+
+    /// 指定类型休息的时长
+    pub const fn break_duration(&self, kind: BreakKind) -> Duration {
+        match kind {
+            BreakKind::Short => self.timings.break_duration,
+            BreakKind::Long => self.timings.long_break_duration,
+        }
+    }
+
+    /// 本轮剩余可推迟次数
+    pub const fn postpones_left(&self) -> u32 {
+        self.timings
+            .max_postpones
+            .saturating_sub(self.postpones_used)
+    }
+
+    /// 当前是否可以推迟休息
+    pub const fn can_postpone(&self) -> bool {
+        matches!(self.phase, Phase::PreBreak | Phase::Breaking) && self.postpones_left() > 0
+    }
+
+    /// 最近一次因闲置跳过休息的原因
+    pub const fn last_idle_skip(&self) -> Option<IdleReason> {
+        self.last_idle_skip
+    }
+
+    /// 当前的暂停状态
+    pub const fn paused(&self) -> Option<Pause> {
+        self.pause
+    }
+
+    /// 恢复上次退出前保存的暂停状态（不产生指令，之后进入阶段时生效）
+    pub const fn restore_pause(&mut self, pause: Option<Pause>) {
+        self.pause = pause;
+    }
+
+    /// 当前阶段剩余时长
+    pub fn remaining(&self, clock: &impl Clock) -> Duration {
+        self.deadline
+            .and_then(|t| t.checked_duration_since(clock.now()))
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// 更新时长参数（仅对之后进入的阶段生效）
+    pub const fn set_timings(&mut self, timings: Timings) {
+        self.timings = timings;
+    }
+
+    /// 更新工作时段（仅对之后进入的阶段生效）
+    pub fn set_work_hours(&mut self, work_hours: Option<WorkHours>) {
+        self.work_hours = work_hours;
+    }
+
+    /// 应用新的设置，当前阶段已经过去的时长保持不变
+    ///
+    /// 时长参数与工作时段都没有变化时不产生指令：仅影响界面的设置（主题、语言等）无需重新调度。
+    pub fn apply_settings(
+        &mut self,
+        timings: Timings,
+        work_hours: Option<WorkHours>,
+        clock: &impl Clock,
+    ) -> Vec<Command> {
+        let hours_changed = work_hours != self.work_hours;
+        if timings == self.timings && !hours_changed {
+            return Vec::new();
+        }
+
+        let old_duration = self.phase_duration();
+        self.set_timings(timings);
+        self.set_work_hours(work_hours);
+        if self.pause.is_some() {
+            // 暂停以墙上时刻为准，恢复时按新设置开始
+            return Vec::new();
+        }
+
+        match self.phase {
+            Phase::OffHours | Phase::AwaitingReturn => return self.enter(self.phase, clock),
+            Phase::Working | Phase::PreBreak if hours_changed && !self.within_work_hours(clock) => {
+                return self.enter_off_hours(clock);
+            }
+            Phase::Working | Phase::PreBreak | Phase::Breaking => {}
+        }
+
+        // 按阶段总时长的变化量平移截止时间，保留已过去的时长与顺延、延长等调整
+        let new_duration = self.phase_duration();
+        if let Some(longer) = new_duration.checked_sub(old_duration) {
+            self.deadline = self.deadline.map(|t| t + longer);
+            self.deadline_wall = self.deadline_wall.map(|t| t + longer);
+        } else {
+            let shorter = old_duration.saturating_sub(new_duration);
+            self.deadline = self.deadline.map(|t| t.checked_sub(shorter).unwrap_or(t));
+            self.deadline_wall = self
+                .deadline_wall
+                .map(|t| t.checked_sub(shorter).unwrap_or(t));
+        }
+
+        let remaining = self.remaining(clock);
+        match self.phase {
+            Phase::Working => vec![self.sample_timer(Some(Duration::ZERO), clock)],
+            Phase::PreBreak => vec![Command::ArmTimer {
+                after: remaining,
+                tolerance: self.timings.break_tolerance,
+            }],
+            Phase::Breaking => {
+                let mut commands = Vec::new();
+                if !remaining.is_zero() {
+                    commands.push(Command::UpdateCountdown { remaining });
+                }
+                commands.push(Command::ArmTimer {
+                    after: remaining,
+                    tolerance: self.break_tolerance(),
+                });
+                commands
+            }
+            Phase::OffHours | Phase::AwaitingReturn => Vec::new(),
+        }
+    }
+
+    /// 当前阶段按设置应有的总时长
+    ///
+    /// 推迟后的工作阶段（本轮已推迟过）以推迟时长计；没有固定时长的阶段返回零。
+    const fn phase_duration(&self) -> Duration {
+        match self.phase {
+            Phase::Working if self.postpones_used > 0 => self.timings.postpone_duration,
+            Phase::Working => self.timings.work_interval,
+            Phase::PreBreak => self.timings.pre_break_duration,
+            Phase::Breaking => self.break_duration(self.break_kind),
+            Phase::OffHours | Phase::AwaitingReturn => Duration::ZERO,
+        }
+    }
+
+    /// 进入指定阶段并从零开始计时
+    ///
+    /// 进入 `Working` 时若不在工作时段内，会转为 `OffHours` 并等待下一个时段开始。
+    /// 暂停期间只会重新等待暂停结束。
+    pub fn enter(&mut self, phase: Phase, clock: &impl Clock) -> Vec<Command> {
+        if let Some(pause) = self.pause {
+            if !pause_expired(pause, clock) {
+                return self.arm_pause(pause, clock);
+            }
+            self.pause = None;
+            let mut commands = vec![Command::Record(Event::Resumed)];
+            commands.extend(self.enter(Phase::Working, clock));
+            return commands;
+        }
+
+        if matches!(
+            phase,
+            Phase::Working | Phase::OffHours | Phase::AwaitingReturn
+        ) && !self.within_work_hours(clock)
+        {
+            return self.enter_off_hours(clock);
+        }
+        if phase == Phase::AwaitingReturn && self.timings.await_return {
+            return self.await_return(clock);
+        }
+
+        let (phase, duration, tolerance) = match phase {
+            Phase::Working | Phase::OffHours | Phase::AwaitingReturn => (
+                Phase::Working,
+                self.timings.work_interval,
+                self.timings.work_tolerance,
+            ),
+            Phase::PreBreak => (
+                Phase::PreBreak,
+                self.timings.pre_break_duration,
+                self.timings.break_tolerance,
+            ),
+            Phase::Breaking => {
+                let (duration, tolerance) = match self.break_kind {
+                    BreakKind::Short => (self.timings.break_duration, self.timings.break_tolerance),
+                    BreakKind::Long => (
+                        self.timings.long_break_duration,
+                        self.timings.long_break_tolerance,
+                    ),
+                };
+                (Phase::Breaking, duration, tolerance)
+            }
+        };
+        let commands = self.arm(phase, duration, tolerance, clock);
+        if phase == Phase::Working
+            && (self.timings.natural_break || self.timings.active_threshold.is_some())
+        {
+            return vec![self.sample_timer(Some(Duration::ZERO), clock)];
+        }
+        commands
+    }
+
+    /// 下一次空闲采样的定时器
+    ///
+    /// 在连续空闲最早可能达到休息时长（自然休息）或空闲阈值（活跃时间模式）时唤醒，
+    /// 间隔不小于工作定时器容差，且不晚于截止时间。无法获取空闲时长时直接等到截止时间。
+    fn sample_timer(&self, idle: Option<Duration>, clock: &impl Clock) -> Command {
+        let tolerance = self.timings.work_tolerance;
+        let remaining = self.remaining(clock);
+
+        let natural = idle.filter(|_| self.timings.natural_break).map(|idle| {
+            self.break_duration(self.next_break_kind())
+                .saturating_sub(idle)
+        });
+        let active = self
+            .timings
+            .active_threshold
+            .zip(idle)
+            .map(|(threshold, idle)| {
+                // 已超过阈值时每隔一个阈值确认一次是否仍在离开
+                threshold
+                    .checked_sub(idle)
+                    .filter(|left| !left.is_zero())
+                    .unwrap_or(threshold)
+            });
+
+        let after = natural
+            .into_iter()
+            .chain(active)
+            .min()
+            .map_or(remaining, |sample| sample.max(tolerance).min(remaining));
+        Command::ArmTimer { after, tolerance }
+    }
+
+    /// 工作期间的空闲采样：离开足够久则视为自然休息并重新开始工作周期
+    fn on_idle_sample(&mut self, idle: Option<Duration>, clock: &impl Clock) -> Vec<Command> {
+        let kind = self.next_break_kind();
+        if self.timings.natural_break
+            && let Some(idle) = idle.filter(|idle| *idle >= self.break_duration(kind))
+        {
+            self.break_kind = kind;
+            self.count_break();
+            let mut commands = vec![Command::Record(Event::NaturalBreak { kind, idle })];
+            commands.extend(self.enter(Phase::AwaitingReturn, clock));
+            return commands;
+        }
+        vec![self.sample_timer(idle, clock)]
+    }
+
+    /// 活跃时间模式：将自上次核算以来超过阈值的连续空闲从工作计时中扣除（顺延截止时间）
+    ///
+    /// 只在定时器唤醒时核算；采样间隔不超过阈值，因此每段长空闲的误差不超过一个阈值。
+    fn exclude_idle(&mut self, idle: Option<Duration>, clock: &impl Clock) {
+        let now = clock.now();
+        let checked_at = self.idle_checked_at.replace(now);
+        let (Some(threshold), Some(idle), Some(checked_at)) =
+            (self.timings.active_threshold, idle, checked_at)
+        else {
+            return;
+        };
+        if idle < threshold {
+            return;
+        }
+
+        let idle_since = now.checked_sub(idle).unwrap_or(checked_at).max(checked_at);
+        let excluded = now.saturating_duration_since(idle_since);
+        self.deadline = self.deadline.map(|t| t + excluded);
+        self.deadline_wall = self.deadline_wall.map(|t| t + excluded);
+    }
+
+    /// 当前是否处于工作时段内（未配置时始终为真）
+    fn within_work_hours(&self, clock: &impl Clock) -> bool {
+        self.work_hours
+            .as_ref()
+            .is_none_or(|hours| hours.is_active(clock.local_secs()))
+    }
+
+    fn enter_off_hours(&mut self, clock: &impl Clock) -> Vec<Command> {
+        self.postpones_used = 0;
+
+        let now_local = clock.local_secs();
+        let next_start = self
+            .work_hours
+            .as_ref()
+            .and_then(|hours| hours.next_start_after(now_local));
+        if let Some(next_start) = next_start {
+            let wait = Duration::from_secs(u64::try_from(next_start - now_local).unwrap_or(0));
+            return self.arm(Phase::OffHours, wait, self.timings.work_tolerance, clock);
+        }
+
+        // 没有任何工作时段：停在 OffHours，直到设置变更
+        self.stop(Phase::OffHours, clock)
+    }
+
+    /// 暂停提醒；正在进行的休息会被关闭且不计入统计
+    pub fn pause(&mut self, pause: Pause, clock: &impl Clock) -> Vec<Command> {
+        let mut commands = Vec::new();
+        if self.phase == Phase::Breaking {
+            commands.push(Command::HideBreak);
+        }
+        commands.push(Command::Record(Event::Paused { pause }));
+        commands.extend(self.arm_pause(pause, clock));
+        commands
+    }
+
+    /// 结束暂停，以全新的工作周期恢复提醒
+    pub fn resume(&mut self, clock: &impl Clock) -> Vec<Command> {
+        if self.pause.take().is_none() {
+            return Vec::new();
+        }
+        let mut commands = vec![Command::Record(Event::Resumed)];
+        commands.extend(self.enter(Phase::Working, clock));
+        commands
+    }
+
+    fn arm_pause(&mut self, pause: Pause, clock: &impl Clock) -> Vec<Command> {
+        self.pause = Some(pause);
+        self.postpones_used = 0;
+        match pause {
+            Pause::Until(until) => {
+                let wait = until
+                    .duration_since(clock.now_wall())
+                    .unwrap_or(Duration::ZERO);
+                self.arm(Phase::Working, wait, self.timings.work_tolerance, clock)
+            }
+            Pause::Indefinitely => self.stop(Phase::Working, clock),
+        }
+    }
+
+    /// 停在指定阶段且不设置定时器
+    fn stop(&mut self, phase: Phase, clock: &impl Clock) -> Vec<Command> {
+        self.phase = phase;
+        self.phase_started_at = Some(clock.now());
+        self.phase_started_wall = Some(clock.now_wall());
+        self.deadline = None;
+        self.deadline_wall = None;
+        vec![Command::CancelTimer]
+    }
+
+    /// 自阶段开始以来墙上时钟比单调时钟多走的时长，即系统睡眠时长
+    ///
+    /// 单调时钟在睡眠期间停止，墙上时钟照常前进；墙上时钟被回拨时返回零。
+    fn sleep_gap(&self, clock: &impl Clock) -> Duration {
+        let (Some(started), Some(started_wall)) = (self.phase_started_at, self.phase_started_wall)
+        else {
+            return Duration::ZERO;
+        };
+        let wall = clock
+            .now_wall()
+            .duration_since(started_wall)
+            .unwrap_or(Duration::ZERO);
+        wall.saturating_sub(clock.now().saturating_duration_since(started))
+    }
+
+    /// 系统唤醒后核对睡眠时长并校正截止时间
+    ///
+    /// 睡眠达到下一次休息时长时计为一次休息并重新开始工作周期；休息中睡过剩余时长则直接结束休息；
+    /// 较短的睡眠计入当前阶段（活跃时间模式的工作阶段除外）。未检测到睡眠时不产生指令。
+    pub fn on_wake(&mut self, clock: &impl Clock) -> Vec<Command> {
+        let slept = self.sleep_gap(clock);
+        if slept < MIN_SLEEP_GAP {
+            return Vec::new();
+        }
+
+        if self.pause.is_some() || matches!(self.phase, Phase::OffHours | Phase::AwaitingReturn) {
+            // 暂停与非工作时段都以墙上时刻为准，重新计算等待时长即可；
+            // 等待回来时唤醒电脑本身就说明用户已经回来
+            let mut commands = vec![Command::Record(Event::Woke { slept })];
+            if self.pause.is_none() && self.phase == Phase::AwaitingReturn {
+                let away = self.phase_elapsed(clock) + slept;
+                commands.push(Command::Record(Event::Returned { away }));
+            }
+            commands.extend(self.enter(Phase::Working, clock));
+            return commands;
+        }
+
+        match self.phase {
+            Phase::Working | Phase::PreBreak => {
+                let kind = if self.phase == Phase::PreBreak {
+                    self.break_kind
+                } else {
+                    self.next_break_kind()
+                };
+                if slept >= self.break_duration(kind) {
+                    self.break_kind = kind;
+                    self.count_break();
+                    let mut commands = vec![Command::Record(Event::SleepRest { kind, slept })];
+                    commands.extend(self.enter(Phase::Working, clock));
+                    return commands;
+                }
+            }
+            Phase::Breaking if slept >= self.remaining(clock) => {
+                let kind = self.break_kind;
+                return self.end_break(
+                    Event::BreakCompleted {
+                        kind,
+                        compliance: BreakCompliance::Rested,
+                        elapsed: self.phase_elapsed(clock) + slept,
+                    },
+                    clock,
+                );
+            }
+            Phase::Breaking | Phase::OffHours | Phase::AwaitingReturn => {}
+        }
+
+        // 较短的睡眠：让单调时钟上的开始与截止时间追上墙上时钟
+        self.phase_started_at = self
+            .phase_started_at
+            .map(|t| t.checked_sub(slept).unwrap_or(t));
+        if self.phase == Phase::Working && self.timings.active_threshold.is_some() {
+            // 活跃时间模式下睡眠不算工作时长，墙上截止时间顺延
+            self.deadline_wall = self.deadline_wall.map(|t| t + slept);
+        } else {
+            self.deadline = self.deadline.map(|t| t.checked_sub(slept).unwrap_or(t));
+        }
+
+        let remaining = self.remaining(clock);
+        let mut commands = vec![Command::Record(Event::Woke { slept })];
+        match self.phase {
+            Phase::Working => commands.push(self.sample_timer(Some(Duration::ZERO), clock)),
+            Phase::PreBreak => commands.push(Command::ArmTimer {
+                after: remaining,
+                tolerance: self.timings.break_tolerance,
+            }),
+            Phase::Breaking => commands.extend([
+                Command::UpdateCountdown { remaining },
+                Command::ArmTimer {
+                    after: remaining,
+                    tolerance: self.break_tolerance(),
+                },
+            ]),
+            Phase::OffHours | Phase::AwaitingReturn => {}
+        }
+        commands
+    }
+
+    /// 阶段定时器触发时的状态转换
+    ///
+    /// 先核对是否刚从睡眠中唤醒，避免按过期的截止时间立即弹出休息。
+    pub fn on_timer(&mut self, clock: &impl Clock, idle: &impl IdleSource) -> Vec<Command> {
+        let woke = self.on_wake(clock);
+        if !woke.is_empty() {
+            return woke;
+        }
+        if self.pause.is_some() {
+            return self.resume(clock);
+        }
+
+        match self.phase {
+            Phase::Working if !self.within_work_hours(clock) => self.enter_off_hours(clock),
+            Phase::Working => {
+                let idle = idle.idle_duration();
+                self.exclude_idle(idle, clock);
+                if self.deadline.is_some_and(|t| clock.now() < t) {
+                    return self.on_idle_sample(idle, clock);
+                }
+
+                let kind = self.next_break_kind();
+                let cycle_elapsed = self
+                    .phase_started_at
+                    .map_or(Duration::ZERO, |t| clock.now().saturating_duration_since(t));
+                let decision =
+                    self.timings
+                        .idle_policy
+                        .decide(cycle_elapsed, idle, self.break_duration(kind));
+                if let IdleDecision::Skip(reason) = decision {
+                    if matches!(reason, IdleReason::RestedBreakLength { .. }) {
+                        // 离开时长已够一次休息：计入休息周期
+                        self.break_kind = kind;
+                        self.count_break();
+                    } else {
+                        self.postpones_used = 0;
+                    }
+                    self.last_idle_skip = Some(reason);
+                    let mut commands = vec![Command::Record(Event::IdleSkipped { kind, reason })];
+                    commands.extend(self.enter(Phase::AwaitingReturn, clock));
+                    commands
+                } else if self.timings.pre_break_duration > Duration::ZERO {
+                    self.break_kind = self.next_break_kind();
+                    self.enter(Phase::PreBreak, clock)
+                } else {
+                    self.begin_break(clock)
+                }
+            }
+            Phase::PreBreak => self.begin_break(clock),
+            Phase::OffHours => self.enter(Phase::Working, clock),
+            Phase::AwaitingReturn => self.on_return_poll(clock, idle),
+            Phase::Breaking => self.on_break_timer(clock, idle),
+        }
+    }
+
+    /// 等待用户回来：自上次采样以来有输入即开始工作计时
+    ///
+    /// 无法获取空闲时长时无从判断，直接开始工作计时。
+    fn on_return_poll(&mut self, clock: &impl Clock, idle: &impl IdleSource) -> Vec<Command> {
+        let now = clock.now();
+        let since_poll = self
+            .idle_checked_at
+            .replace(now)
+            .map_or(Duration::ZERO, |t| now.saturating_duration_since(t));
+        match idle.idle_duration() {
+            Some(idle) if idle >= since_poll.max(RETURN_POLL_INTERVAL) => {
+                vec![Command::ArmTimer {
+                    after: RETURN_POLL_INTERVAL,
+                    tolerance: RETURN_POLL_INTERVAL / 5,
+                }]
+            }
+            Some(_) => {
+                let away = self
+                    .phase_started_at
+                    .map_or(Duration::ZERO, |t| now.saturating_duration_since(t));
+                let mut commands = vec![Command::Record(Event::Returned { away })];
+                commands.extend(self.enter(Phase::Working, clock));
+                commands
+            }
+            None => self.enter(Phase::Working, clock),
+        }
+    }
+
+    /// 休息结束：核对休息期间是否真的离开了电脑，必要时延长一次
+    fn on_break_timer(&mut self, clock: &impl Clock, idle: &impl IdleSource) -> Vec<Command> {
+        let kind = self.break_kind;
+        let elapsed = self.phase_elapsed(clock);
+        let idle = idle.idle_duration();
+        let compliance = BreakCompliance::classify(elapsed, idle);
+
+        let extra = idle.map_or(Duration::ZERO, |idle| elapsed.saturating_sub(idle));
+        if self.timings.extend_active_breaks
+            && !self.break_extended
+            && matches!(
+                compliance,
+                BreakCompliance::Partial | BreakCompliance::Active
+            )
+            && !extra.is_zero()
+        {
+            self.break_extended = true;
+            self.deadline = Some(clock.now() + extra);
+            self.deadline_wall = Some(clock.now_wall() + extra);
+            let tolerance = self.break_tolerance();
+            return vec![
+                Command::Record(Event::BreakExtended { kind, extra }),
+                Command::UpdateCountdown { remaining: extra },
+                Command::ArmTimer {
+                    after: extra,
+                    tolerance,
+                },
+            ];
+        }
+
+        self.end_break(
+            Event::BreakCompleted {
+                kind,
+                compliance,
+                elapsed,
+            },
+            clock,
+        )
+    }
+
+    /// 立即开始休息（`Breaking` 阶段除外），同时结束暂停
+    pub fn start_break_now(&mut self, clock: &impl Clock) -> Vec<Command> {
+        if self.phase == Phase::Breaking {
+            return Vec::new();
+        }
+        let mut commands = Vec::new();
+        if self.pause.take().is_some() {
+            commands.push(Command::Record(Event::Resumed));
+        }
+        commands.extend(self.begin_break(clock));
+        commands
+    }
+
+    /// 跳过当前休息（仅在 Breaking 阶段生效）
+    pub fn skip_break(&mut self, clock: &impl Clock) -> Vec<Command> {
+        if self.phase != Phase::Breaking {
+            return Vec::new();
+        }
+        self.end_break(
+            Event::BreakSkipped {
+                kind: self.break_kind,
+                elapsed: self.phase_elapsed(clock),
+            },
+            clock,
+        )
+    }
+
+    /// 推迟当前休息，在推迟时长结束后重新开始同类型的休息
+    pub fn postpone(&mut self, clock: &impl Clock) -> Vec<Command> {
+        if !self.can_postpone() {
+            return Vec::new();
+        }
+        self.postpones_used += 1;
+
+        let mut commands = Vec::new();
+        if self.phase == Phase::Breaking {
+            commands.push(Command::HideBreak);
+        }
+        commands.push(Command::Record(Event::BreakPostponed {
+            kind: self.break_kind,
+            postpones_left: self.postpones_left(),
+        }));
+        commands.extend(self.arm(
+            Phase::Working,
+            self.timings.postpone_duration,
+            self.timings.break_tolerance,
+            clock,
+        ));
+        commands
+    }
+
+    fn arm(
+        &mut self,
+        phase: Phase,
+        duration: Duration,
+        tolerance: Duration,
+        clock: &impl Clock,
+    ) -> Vec<Command> {
+        let started_at = clock.now();
+        self.phase = phase;
+        self.phase_started_at = Some(started_at);
+        self.phase_started_wall = Some(clock.now_wall());
+        self.idle_checked_at = Some(started_at);
+        self.deadline = Some(started_at + duration);
+        self.deadline_wall = Some(clock.now_wall() + duration);
+
+        vec![Command::ArmTimer {
+            after: duration,
+            tolerance,
+        }]
+    }
+
+    /// 当前阶段按单调时钟已经过去的时长（不含系统睡眠）
+    fn phase_elapsed(&self, clock: &impl Clock) -> Duration {
+        self.phase_started_at
+            .map_or(Duration::ZERO, |t| clock.now().saturating_duration_since(t))
+    }
+
+    /// 当前休息类型的定时器容差
+    const fn break_tolerance(&self) -> Duration {
+        match self.break_kind {
+            BreakKind::Short => self.timings.break_tolerance,
+            BreakKind::Long => self.timings.long_break_tolerance,
+        }
+    }
+
+    /// 进入等待回来阶段：不设截止时间，定期采样空闲时长
+    fn await_return(&mut self, clock: &impl Clock) -> Vec<Command> {
+        self.stop(Phase::AwaitingReturn, clock);
+        self.idle_checked_at = self.phase_started_at;
+        vec![Command::ArmTimer {
+            after: RETURN_POLL_INTERVAL,
+            tolerance: RETURN_POLL_INTERVAL / 5,
+        }]
+    }
+
+    fn begin_break(&mut self, clock: &impl Clock) -> Vec<Command> {
+        self.last_idle_skip = None;
+        self.break_extended = false;
+        let kind = self.next_break_kind();
+        self.break_kind = kind;
+
+        let mut commands = vec![Command::ShowBreak {
+            seconds: self.break_duration(kind).as_secs(),
+            kind,
+        }];
+        commands.extend(self.enter(Phase::Breaking, clock));
+        commands
+    }
+
+    /// 计入一次已结束的休息（类型为 `break_kind`）
+    const fn count_break(&mut self) {
+        match self.break_kind {
+            BreakKind::Short => self.short_breaks_since_long += 1,
+            BreakKind::Long => self.short_breaks_since_long = 0,
+        }
+        self.postpones_used = 0;
+    }
+
+    fn end_break(&mut self, event: Event, clock: &impl Clock) -> Vec<Command> {
+        // 跳过的休息不推进长休息周期，被跳过的长休息会在下一次休息时重新安排
+        if matches!(event, Event::BreakSkipped { .. }) {
+            self.postpones_used = 0;
+        } else {
+            self.count_break();
+        }
+
+        // 按时结束的休息之后等待用户回来；主动跳过说明人就在电脑前
+        let next = if matches!(event, Event::BreakCompleted { .. }) {
+            Phase::AwaitingReturn
+        } else {
+            Phase::Working
+        };
+        let mut commands = vec![Command::EndBreak, Command::Record(event)];
+        commands.extend(self.enter(next, clock));
+        commands
+    }
+}
+
+fn pause_expired(pause: Pause, clock: &impl Clock) -> bool {
+    match pause {
+        Pause::Until(until) => until <= clock.now_wall(),
+        Pause::Indefinitely => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    /// 可手动推进的假时钟
+    pub struct FakeClock {
+        now: Cell<Instant>,
+        wall: Cell<SystemTime>,
+        utc_offset: i64,
+    }
+
+    impl FakeClock {
+        pub fn new() -> Self {
+            Self::at(1_700_000_000)
+        }
+
+        /// 墙上时钟位于指定 Unix 秒数的假时钟（UTC）
+        pub fn at(unix_secs: u64) -> Self {
+            Self {
+                now: Cell::new(Instant::now()),
+                wall: Cell::new(SystemTime::UNIX_EPOCH + Duration::from_secs(unix_secs)),
+                utc_offset: 0,
+            }
+        }
+
+        pub fn advance(&self, d: Duration) {
+            self.now.set(self.now.get() + d);
+            self.wall.set(self.wall.get() + d);
+        }
+
+        /// 模拟系统睡眠：只有墙上时钟前进，单调时钟停止
+        pub fn sleep(&self, d: Duration) {
+            self.wall.set(self.wall.get() + d);
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Instant {
+            self.now.get()
+        }
+
+        fn now_wall(&self) -> SystemTime {
+            self.wall.get()
+        }
+
+        fn utc_offset(&self) -> i64 {
+            self.utc_offset
+        }
+    }
+
+    /// 返回固定空闲时长的假空闲来源
+    pub struct FakeIdle(pub Cell<Option<Duration>>);
+
+    impl FakeIdle {
+        pub const fn active() -> Self {
+            Self(Cell::new(Some(Duration::ZERO)))
+        }
+    }
+
+    impl IdleSource for FakeIdle {
+        fn idle_duration(&self) -> Option<Duration> {
+            self.0.get()
+        }
+    }
+
+    const fn timings() -> Timings {
+        Timings {
+            work_interval: Duration::from_secs(30 * 60),
+            break_duration: Duration::from_secs(120),
+            work_tolerance: Duration::from_secs(120),
+            break_tolerance: Duration::from_secs(5),
+            long_break_duration: Duration::from_secs(600),
+            long_break_tolerance: Duration::from_secs(5),
+            long_break_every: 0,
+            postpone_duration: Duration::from_secs(300),
+            max_postpones: 2,
+            pre_break_duration: Duration::ZERO,
+            natural_break: false,
+            idle_policy: IdlePolicy::Tolerance(Duration::from_secs(8)),
+            active_threshold: None,
+            extend_active_breaks: false,
+            await_return: false,
+        }
+    }
+
+    const fn short_break() -> Command {
+        Command::ShowBreak {
+            seconds: 120,
+            kind: BreakKind::Short,
+        }
+    }
+
+    fn started() -> (Scheduler, FakeClock) {
+        let clock = FakeClock::new();
+        let mut scheduler = Scheduler::new(timings());
+        scheduler.enter(Phase::Working, &clock);
+        (scheduler, clock)
+    }
+
+    #[test]
+    fn enter_working_arms_work_timer() {
+        let clock = FakeClock::new();
+        let mut scheduler = Scheduler::new(timings());
+        let commands = scheduler.enter(Phase::Working, &clock);
+
+        assert_eq!(
+            commands,
+            vec![Command::ArmTimer {
+                after: Duration::from_secs(1800),
+                tolerance: Duration::from_secs(120),
+            }]
+        );
+        assert_eq!(scheduler.phase(), Phase::Working);
+        assert_eq!(scheduler.phase_started_at, Some(clock.now()));
+        assert_eq!(
+            scheduler.deadline,
+            Some(clock.now() + Duration::from_secs(1800))
+        );
+        assert_eq!(
+            scheduler.deadline_wall(),
+            Some(clock.now_wall() + Duration::from_secs(1800))
+        );
+    }
+
+    #[test]
+    fn work_timer_starts_break() {
+        let (mut scheduler, clock) = started();
+        clock.advance(Duration::from_secs(1800));
+
+        let commands = scheduler.on_timer(&clock, &FakeIdle::active());
+        assert_eq!(
+            commands,
+            vec![
+                short_break(),
+                Command::ArmTimer {
+                    after: Duration::from_secs(120),
+                    tolerance: Duration::from_secs(5),
+                },
+            ]
+        );
+        assert_eq!(scheduler.phase(), Phase::Breaking);
+    }
+
+    #[test]
+    fn break_timer_returns_to_work() {
+        let (mut scheduler, clock) = started();
+        scheduler.start_break_now(&clock);
+        clock.advance(Duration::from_secs(120));
+
+        let commands = scheduler.on_timer(&clock, &FakeIdle::active());
+        assert_eq!(commands[0], Command::EndBreak);
+        assert_eq!(scheduler.phase(), Phase::Working);
+        assert_eq!(
+            scheduler.deadline,
+            Some(clock.now() + Duration::from_secs(1800))
+        );
+    }
+
+    #[test]
+    fn idle_for_whole_cycle_skips_break() {
+        let (mut scheduler, clock) = started();
+        clock.advance(Duration::from_secs(1800));
+        let idle = FakeIdle(Cell::new(Some(Duration::from_secs(1797))));
+
+        let commands = scheduler.on_timer(&clock, &idle);
+        assert_eq!(
+            commands,
+            vec![
+                Command::Record(Event::IdleSkipped {
+                    kind: BreakKind::Short,
+                    reason: IdleReason::Active {
+                        active: Duration::from_secs(3),
+                        tolerance: Duration::from_secs(8),
+                    },
+                }),
+                Command::ArmTimer {
+                    after: Duration::from_secs(1800),
+                    tolerance: Duration::from_secs(120),
+                },
+            ]
+        );
+        assert_eq!(scheduler.phase(), Phase::Working);
+        assert!(scheduler.last_idle_skip().is_some());
+    }
+
+    #[test]
+    fn disabled_idle_policy_always_breaks() {
+        let clock = FakeClock::new();
+        let mut scheduler = Scheduler::new(Timings {
+            idle_policy: IdlePolicy::Disabled,
+            ..timings()
+        });
+        scheduler.enter(Phase::Working, &clock);
+        clock.advance(Duration::from_secs(1800));
+
+        let idle = FakeIdle(Cell::new(Some(Duration::from_secs(1800))));
+        assert_eq!(scheduler.on_timer(&clock, &idle)[0], short_break());
+        assert_eq!(scheduler.last_idle_skip(), None);
+    }
+
+    #[test]
+    fn idle_break_length_policy_counts_as_the_break() {
+        let clock = FakeClock::new();
+        let mut scheduler = Scheduler::new(Timings {
+            idle_policy: IdlePolicy::BreakLength,
+            long_break_every: 2,
+            ..timings()
+        });
+        scheduler.enter(Phase::Working, &clock);
+        clock.advance(Duration::from_secs(1800));
+
+        let idle = FakeIdle(Cell::new(Some(Duration::from_secs(150))));
+        let commands = scheduler.on_timer(&clock, &idle);
+        assert_eq!(
+            commands[0],
+            Command::Record(Event::IdleSkipped {
+                kind: BreakKind::Short,
+                reason: IdleReason::RestedBreakLength {
+                    idle: Duration::from_secs(150),
+                    break_duration: Duration::from_secs(120),
+                },
+            })
+        );
+        // 计入休息周期：下一次是长休息
+        assert_eq!(scheduler.next_break_kind(), BreakKind::Long);
+
+        clock.advance(Duration::from_secs(1800));
+        assert_eq!(
+            scheduler.on_timer(&clock, &FakeIdle::active())[0],
+            Command::ShowBreak {
+                seconds: 600,
+                kind: BreakKind::Long,
+            }
+        );
+        assert_eq!(scheduler.last_idle_skip(), None);
+    }
+
+    #[test]
+    fn unknown_idle_never_skips_break() {
+        let (mut scheduler, clock) = started();
+        clock.advance(Duration::from_secs(1800));
+        let idle = FakeIdle(Cell::new(None));
+
+        scheduler.on_timer(&clock, &idle);
+        assert_eq!(scheduler.phase(), Phase::Breaking);
+    }
+
+    #[test]
+    fn start_break_now_only_while_working() {
+        let (mut scheduler, clock) = started();
+        assert_eq!(scheduler.start_break_now(&clock)[0], short_break());
+        assert!(scheduler.start_break_now(&clock).is_empty());
+        assert_eq!(scheduler.phase(), Phase::Breaking);
+    }
+
+    #[test]
+    fn skip_break_only_while_breaking() {
+        let (mut scheduler, clock) = started();
+        assert!(scheduler.skip_break(&clock).is_empty());
+
+        scheduler.start_break_now(&clock);
+        clock.advance(Duration::from_secs(30));
+        let commands = scheduler.skip_break(&clock);
+        assert_eq!(commands[0], Command::EndBreak);
+        assert_eq!(scheduler.phase(), Phase::Working);
+        assert_eq!(scheduler.phase_started_at, Some(clock.now()));
+    }
+
+    #[test]
+    fn remaining_counts_down_and_saturates() {
+        let (scheduler, clock) = started();
+        clock.advance(Duration::from_secs(600));
+        assert_eq!(scheduler.remaining(&clock), Duration::from_secs(1200));
+        clock.advance(Duration::from_secs(3600));
+        assert_eq!(scheduler.remaining(&clock), Duration::ZERO);
+    }
+
+    #[test]
+    fn new_timings_apply_to_next_phase() {
+        let (mut scheduler, clock) = started();
+        scheduler.set_timings(Timings {
+            break_duration: Duration::from_secs(300),
+            ..timings()
+        });
+        assert_eq!(
+            scheduler.deadline,
+            Some(clock.now() + Duration::from_secs(1800))
+        );
+        assert_eq!(
+            scheduler.start_break_now(&clock)[0],
+            Command::ShowBreak {
+                seconds: 300,
+                kind: BreakKind::Short,
+            }
+        );
+    }
+
+    fn take_break(scheduler: &mut Scheduler, clock: &FakeClock) -> BreakKind {
+        clock.advance(Duration::from_secs(1800));
+        scheduler.on_timer(clock, &FakeIdle::active());
+        let kind = scheduler.break_kind();
+        clock.advance(scheduler.break_duration(kind));
+        scheduler.on_timer(clock, &FakeIdle::active());
+        kind
+    }
+
+    #[test]
+    fn every_nth_break_is_long() {
+        let (mut scheduler, clock) = started();
+        scheduler.set_timings(Timings {
+            long_break_every: 4,
+            ..timings()
+        });
+
+        let kinds: Vec<_> = (0..8).map(|_| take_break(&mut scheduler, &clock)).collect();
+        assert_eq!(
+            kinds,
+            [
+                BreakKind::Short,
+                BreakKind::Short,
+                BreakKind::Short,
+                BreakKind::Long,
+                BreakKind::Short,
+                BreakKind::Short,
+                BreakKind::Short,
+                BreakKind::Long,
+            ]
+        );
+    }
+
+    #[test]
+    fn long_break_uses_its_own_duration() {
+        let (mut scheduler, clock) = started();
+        scheduler.set_timings(Timings {
+            long_break_every: 1,
+            ..timings()
+        });
+        assert_eq!(scheduler.next_break_kind(), BreakKind::Long);
+
+        let commands = scheduler.start_break_now(&clock);
+        assert_eq!(
+            commands,
+            vec![
+                Command::ShowBreak {
+                    seconds: 600,
+                    kind: BreakKind::Long,
+                },
+                Command::ArmTimer {
+                    after: Duration::from_secs(600),
+                    tolerance: Duration::from_secs(5),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn cycle_counter_survives_settings_change() {
+        let (mut scheduler, clock) = started();
+        scheduler.set_timings(Timings {
+            long_break_every: 3,
+            ..timings()
+        });
+        take_break(&mut scheduler, &clock);
+        take_break(&mut scheduler, &clock);
+
+        scheduler.set_timings(Timings {
+            long_break_every: 3,
+            break_duration: Duration::from_secs(60),
+            ..timings()
+        });
+        scheduler.enter(Phase::Working, &clock);
+        assert_eq!(scheduler.next_break_kind(), BreakKind::Long);
+    }
+
+    #[test]
+    fn long_breaks_disabled_by_zero() {
+        let (mut scheduler, clock) = started();
+        for _ in 0..10 {
+            assert_eq!(take_break(&mut scheduler, &clock), BreakKind::Short);
+        }
+    }
+
+    #[test]
+    fn postpone_rearms_and_records_distinct_event() {
+        let (mut scheduler, clock) = started();
+        scheduler.start_break_now(&clock);
+        assert!(scheduler.can_postpone());
+
+        let commands = scheduler.postpone(&clock);
+        assert_eq!(
+            commands,
+            vec![
+                Command::HideBreak,
+                Command::Record(Event::BreakPostponed {
+                    kind: BreakKind::Short,
+                    postpones_left: 1,
+                }),
+                Command::ArmTimer {
+                    after: Duration::from_secs(300),
+                    tolerance: Duration::from_secs(5),
+                },
+            ]
+        );
+        assert_eq!(scheduler.phase(), Phase::Working);
+        assert_eq!(
+            scheduler.deadline,
+            Some(clock.now() + Duration::from_secs(300))
+        );
+
+        clock.advance(Duration::from_secs(300));
+        assert_eq!(
+            scheduler.on_timer(&clock, &FakeIdle::active())[0],
+            short_break()
+        );
+    }
+
+    #[test]
+    fn skip_is_recorded_differently_from_postpone() {
+        let (mut scheduler, clock) = started();
+        scheduler.start_break_now(&clock);
+        let commands = scheduler.skip_break(&clock);
+        assert_eq!(
+            commands[1],
+            Command::Record(Event::BreakSkipped {
+                kind: BreakKind::Short,
+                elapsed: Duration::ZERO,
+            })
+        );
+    }
+
+    #[test]
+    fn postpones_are_capped_per_cycle() {
+        let (mut scheduler, clock) = started();
+        for expected_left in [1, 0] {
+            scheduler.start_break_now(&clock);
+            scheduler.postpone(&clock);
+            assert_eq!(scheduler.postpones_left(), expected_left);
+        }
+
+        scheduler.start_break_now(&clock);
+        assert!(!scheduler.can_postpone());
+        assert!(scheduler.postpone(&clock).is_empty());
+        assert_eq!(scheduler.phase(), Phase::Breaking);
+
+        clock.advance(Duration::from_secs(120));
+        scheduler.on_timer(&clock, &FakeIdle::active());
+        assert_eq!(scheduler.postpones_left(), 2);
+    }
+
+    #[test]
+    fn postpone_only_while_breaking() {
+        let (mut scheduler, clock) = started();
+        assert!(!scheduler.can_postpone());
+        assert!(scheduler.postpone(&clock).is_empty());
+    }
+
+    #[test]
+    fn skipped_breaks_do_not_advance_the_long_break_cycle() {
+        let (mut scheduler, clock) = started();
+        scheduler.set_timings(Timings {
+            long_break_every: 2,
+            ..timings()
+        });
+        scheduler.start_break_now(&clock);
+        scheduler.skip_break(&clock);
+        assert_eq!(scheduler.short_breaks_since_long, 0);
+        assert_eq!(scheduler.next_break_kind(), BreakKind::Short);
+
+        take_break(&mut scheduler, &clock);
+        scheduler.start_break_now(&clock);
+        assert_eq!(scheduler.break_kind(), BreakKind::Long);
+        scheduler.skip_break(&clock);
+        assert_eq!(scheduler.next_break_kind(), BreakKind::Long);
+    }
+
+    #[test]
+    fn postponed_long_break_stays_long() {
+        let (mut scheduler, clock) = started();
+        scheduler.set_timings(Timings {
+            long_break_every: 2,
+            ..timings()
+        });
+        take_break(&mut scheduler, &clock);
+        scheduler.start_break_now(&clock);
+        assert_eq!(scheduler.break_kind(), BreakKind::Long);
+
+        scheduler.postpone(&clock);
+        assert_eq!(scheduler.next_break_kind(), BreakKind::Long);
+    }
+
+    fn with_pre_break() -> (Scheduler, FakeClock) {
+        let (mut scheduler, clock) = started();
+        scheduler.set_timings(Timings {
+            pre_break_duration: Duration::from_secs(30),
+            ..timings()
+        });
+        (scheduler, clock)
+    }
+
+    #[test]
+    fn work_timer_enters_pre_break_when_enabled() {
+        let (mut scheduler, clock) = with_pre_break();
+        clock.advance(Duration::from_secs(1800));
+
+        let commands = scheduler.on_timer(&clock, &FakeIdle::active());
+        assert_eq!(
+            commands,
+            vec![Command::ArmTimer {
+                after: Duration::from_secs(30),
+                tolerance: Duration::from_secs(5),
+            }]
+        );
+        assert_eq!(scheduler.phase(), Phase::PreBreak);
+    }
+
+    #[test]
+    fn pre_break_timer_shows_break() {
+        let (mut scheduler, clock) = with_pre_break();
+        clock.advance(Duration::from_secs(1800));
+        scheduler.on_timer(&clock, &FakeIdle::active());
+        clock.advance(Duration::from_secs(30));
+
+        let commands = scheduler.on_timer(&clock, &FakeIdle::active());
+        assert_eq!(commands[0], short_break());
+        assert_eq!(scheduler.phase(), Phase::Breaking);
+    }
+
+    #[test]
+    fn idle_skip_bypasses_pre_break() {
+        let (mut scheduler, clock) = with_pre_break();
+        clock.advance(Duration::from_secs(1800));
+        let idle = FakeIdle(Cell::new(Some(Duration::from_secs(1800))));
+
+        scheduler.on_timer(&clock, &idle);
+        assert_eq!(scheduler.phase(), Phase::Working);
+    }
+
+    #[test]
+    fn pre_break_can_start_now() {
+        let (mut scheduler, clock) = with_pre_break();
+        clock.advance(Duration::from_secs(1800));
+        scheduler.on_timer(&clock, &FakeIdle::active());
+
+        assert_eq!(scheduler.start_break_now(&clock)[0], short_break());
+        assert_eq!(scheduler.phase(), Phase::Breaking);
+    }
+
+    #[test]
+    fn pre_break_postpone_has_no_window_to_hide() {
+        let (mut scheduler, clock) = with_pre_break();
+        clock.advance(Duration::from_secs(1800));
+        scheduler.on_timer(&clock, &FakeIdle::active());
+        assert!(scheduler.can_postpone());
+
+        let commands = scheduler.postpone(&clock);
+        assert!(!commands.contains(&Command::HideBreak));
+        assert_eq!(scheduler.phase(), Phase::Working);
+        assert_eq!(
+            scheduler.deadline,
+            Some(clock.now() + Duration::from_secs(300))
+        );
+    }
+
+    #[test]
+    fn pre_break_announces_upcoming_kind() {
+        let (mut scheduler, clock) = with_pre_break();
+        scheduler.set_timings(Timings {
+            pre_break_duration: Duration::from_secs(30),
+            long_break_every: 1,
+            ..timings()
+        });
+        clock.advance(Duration::from_secs(1800));
+        scheduler.on_timer(&clock, &FakeIdle::active());
+        assert_eq!(scheduler.break_kind(), BreakKind::Long);
+    }
+
+    /// 2024-01-01 00:00 UTC，星期一
+    const MONDAY: u64 = 1_704_067_200;
+
+    fn office_hours() -> WorkHours {
+        WorkHours::parse("Mon-Fri 09:00-12:00 13:00-18:30").unwrap()
+    }
+
+    #[test]
+    fn outside_work_hours_waits_for_next_window() {
+        // 周一 08:00
+        let clock = FakeClock::at(MONDAY + 8 * 3600);
+        let mut scheduler = Scheduler::new(timings());
+        scheduler.set_work_hours(Some(office_hours()));
+
+        let commands = scheduler.enter(Phase::Working, &clock);
+        assert_eq!(
+            commands,
+            vec![Command::ArmTimer {
+                after: Duration::from_secs(3600),
+                tolerance: Duration::from_secs(120),
+            }]
+        );
+        assert_eq!(scheduler.phase(), Phase::OffHours);
+        assert_eq!(
+            scheduler.deadline_wall(),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(MONDAY + 9 * 3600))
+        );
+
+        clock.advance(Duration::from_secs(3600));
+        scheduler.on_timer(&clock, &FakeIdle::active());
+        assert_eq!(scheduler.phase(), Phase::Working);
+        assert_eq!(
+            scheduler.deadline,
+            Some(clock.now() + Duration::from_secs(1800))
+        );
+    }
+
+    #[test]
+    fn work_timer_after_window_end_does_not_break() {
+        // 周五 18:10，工作计时在 18:40 到期
+        let clock = FakeClock::at(MONDAY + 4 * 86_400 + 18 * 3600 + 600);
+        let mut scheduler = Scheduler::new(timings());
+        scheduler.set_work_hours(Some(office_hours()));
+        scheduler.enter(Phase::Working, &clock);
+        assert_eq!(scheduler.phase(), Phase::Working);
+
+        clock.advance(Duration::from_secs(1800));
+        let commands = scheduler.on_timer(&clock, &FakeIdle::active());
+        assert!(
+            !commands
+                .iter()
+                .any(|c| matches!(c, Command::ShowBreak { .. }))
+        );
+        assert_eq!(scheduler.phase(), Phase::OffHours);
+        // 下周一 09:00
+        assert_eq!(
+            scheduler.deadline_wall(),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(MONDAY + 7 * 86_400 + 9 * 3600))
+        );
+    }
+
+    #[test]
+    fn work_hours_respect_utc_offset() {
+        // UTC 周一 00:30，本地（UTC+9）为 09:30
+        let mut clock = FakeClock::at(MONDAY + 1800);
+        clock.utc_offset = 9 * 3600;
+        let mut scheduler = Scheduler::new(timings());
+        scheduler.set_work_hours(Some(office_hours()));
+
+        scheduler.enter(Phase::Working, &clock);
+        assert_eq!(scheduler.phase(), Phase::Working);
+    }
+
+    #[test]
+    fn empty_work_hours_cancel_timer() {
+        let clock = FakeClock::at(MONDAY + 10 * 3600);
+        let mut scheduler = Scheduler::new(timings());
+        scheduler.set_work_hours(Some(WorkHours::default()));
+
+        assert_eq!(
+            scheduler.enter(Phase::Working, &clock),
+            vec![Command::CancelTimer]
+        );
+        assert_eq!(scheduler.phase(), Phase::OffHours);
+        assert_eq!(scheduler.deadline_wall(), None);
+    }
+
+    #[test]
+    fn manual_break_allowed_off_hours() {
+        let clock = FakeClock::at(MONDAY + 20 * 3600);
+        let mut scheduler = Scheduler::new(timings());
+        scheduler.set_work_hours(Some(office_hours()));
+        scheduler.enter(Phase::Working, &clock);
+
+        assert_eq!(scheduler.start_break_now(&clock)[0], short_break());
+        clock.advance(Duration::from_secs(120));
+        scheduler.on_timer(&clock, &FakeIdle::active());
+        assert_eq!(scheduler.phase(), Phase::OffHours);
+    }
+
+    #[test]
+    fn pause_cancels_break_and_resumes_with_fresh_deadline() {
+        let (mut scheduler, clock) = started();
+        scheduler.start_break_now(&clock);
+
+        let pause = Pause::for_duration(Duration::from_secs(3600), &clock);
+        assert_eq!(
+            scheduler.pause(pause, &clock),
+            vec![
+                Command::HideBreak,
+                Command::Record(Event::Paused { pause }),
+                Command::ArmTimer {
+                    after: Duration::from_secs(3600),
+                    tolerance: Duration::from_secs(120),
+                },
+            ]
+        );
+        assert_eq!(scheduler.paused(), Some(pause));
+        assert_eq!(
+            scheduler.deadline_wall(),
+            Some(clock.now_wall() + Duration::from_secs(3600))
+        );
+
+        clock.advance(Duration::from_secs(3600));
+        assert_eq!(
+            scheduler.on_timer(&clock, &FakeIdle::active()),
+            vec![
+                Command::Record(Event::Resumed),
+                Command::ArmTimer {
+                    after: Duration::from_secs(30 * 60),
+                    tolerance: Duration::from_secs(120),
+                },
+            ]
+        );
+        assert_eq!(scheduler.paused(), None);
+        assert_eq!(scheduler.phase(), Phase::Working);
+        assert_eq!(scheduler.remaining(&clock), Duration::from_secs(30 * 60));
+    }
+
+    #[test]
+    fn indefinite_pause_cancels_timer_until_resumed() {
+        let (mut scheduler, clock) = started();
+        let commands = scheduler.pause(Pause::Indefinitely, &clock);
+        assert_eq!(commands.last(), Some(&Command::CancelTimer));
+        assert_eq!(scheduler.deadline_wall(), None);
+
+        // 设置变更重新进入阶段时仍保持暂停
+        assert_eq!(
+            scheduler.enter(Phase::Working, &clock),
+            vec![Command::CancelTimer]
+        );
+
+        clock.advance(Duration::from_secs(5 * 3600));
+        let commands = scheduler.resume(&clock);
+        assert_eq!(commands[0], Command::Record(Event::Resumed));
+        assert_eq!(scheduler.remaining(&clock), Duration::from_secs(30 * 60));
+        assert!(scheduler.resume(&clock).is_empty());
+    }
+
+    #[test]
+    fn restored_pause_resumes_once_expired() {
+        let clock = FakeClock::new();
+        let mut scheduler = Scheduler::new(timings());
+        scheduler.restore_pause(Some(Pause::for_duration(Duration::from_secs(600), &clock)));
+        assert_eq!(
+            scheduler.enter(Phase::Working, &clock)[0],
+            Command::ArmTimer {
+                after: Duration::from_secs(600),
+                tolerance: Duration::from_secs(120),
+            }
+        );
+
+        // 重启时暂停已过期：直接恢复
+        let mut scheduler = Scheduler::new(timings());
+        scheduler.restore_pause(Some(Pause::Until(clock.now_wall())));
+        assert_eq!(
+            scheduler.enter(Phase::Working, &clock)[0],
+            Command::Record(Event::Resumed)
+        );
+        assert_eq!(scheduler.paused(), None);
+    }
+
+    #[test]
+    fn pause_until_tomorrow_ends_at_local_midnight() {
+        let mut clock = FakeClock::at(MONDAY + 22 * 3600);
+        clock.utc_offset = 3600;
+        assert_eq!(
+            Pause::until_tomorrow(&clock),
+            Pause::Until(clock.now_wall() + Duration::from_secs(3600))
+        );
+    }
+
+    #[test]
+    fn manual_break_ends_pause() {
+        let (mut scheduler, clock) = started();
+        scheduler.pause(Pause::Indefinitely, &clock);
+        let commands = scheduler.start_break_now(&clock);
+        assert_eq!(
+            commands[..2],
+            [Command::Record(Event::Resumed), short_break()]
+        );
+        assert_eq!(scheduler.paused(), None);
+    }
+
+    fn with_natural_breaks() -> (Scheduler, FakeClock) {
+        let clock = FakeClock::new();
+        let mut scheduler = Scheduler::new(Timings {
+            natural_break: true,
+            ..timings()
+        });
+        scheduler.enter(Phase::Working, &clock);
+        (scheduler, clock)
+    }
+
+    #[test]
+    fn natural_breaks_sample_idle_at_break_length() {
+        let clock = FakeClock::new();
+        let mut scheduler = Scheduler::new(Timings {
+            natural_break: true,
+            ..timings()
+        });
+        assert_eq!(
+            scheduler.enter(Phase::Working, &clock),
+            vec![Command::ArmTimer {
+                after: Duration::from_secs(120),
+                tolerance: Duration::from_secs(120),
+            }]
+        );
+        // 截止时间不受采样影响
+        assert_eq!(scheduler.remaining(&clock), Duration::from_secs(30 * 60));
+    }
+
+    #[test]
+    fn sample_waits_until_idle_could_reach_break_length() {
+        let (mut scheduler, clock) = with_natural_breaks();
+        clock.advance(Duration::from_secs(600));
+
+        let idle = FakeIdle(Cell::new(Some(Duration::from_secs(100))));
+        // 还差 20 秒，但采样间隔不小于容差
+        assert_eq!(
+            scheduler.on_timer(&clock, &idle),
+            vec![Command::ArmTimer {
+                after: Duration::from_secs(120),
+                tolerance: Duration::from_secs(120),
+            }]
+        );
+        assert_eq!(scheduler.phase(), Phase::Working);
+        assert_eq!(scheduler.remaining(&clock), Duration::from_secs(20 * 60));
+    }
+
+    #[test]
+    fn long_idle_mid_cycle_counts_as_natural_break() {
+        let (mut scheduler, clock) = with_natural_breaks();
+        clock.advance(Duration::from_secs(15 * 60));
+
+        let idle = FakeIdle(Cell::new(Some(Duration::from_secs(600))));
+        let commands = scheduler.on_timer(&clock, &idle);
+        assert_eq!(
+            commands[0],
+            Command::Record(Event::NaturalBreak {
+                kind: BreakKind::Short,
+                idle: Duration::from_secs(600),
+            })
+        );
+        assert!(!commands.contains(&short_break()));
+        assert_eq!(scheduler.phase(), Phase::Working);
+        assert_eq!(scheduler.remaining(&clock), Duration::from_secs(30 * 60));
+        assert_eq!(scheduler.short_breaks_since_long, 1);
+    }
+
+    #[test]
+    fn idle_does_not_reset_the_cycle_without_natural_breaks() {
+        let clock = FakeClock::new();
+        let mut scheduler = Scheduler::new(timings());
+        // 不采样空闲，直接等到截止时间
+        assert_eq!(
+            scheduler.enter(Phase::Working, &clock),
+            vec![Command::ArmTimer {
+                after: Duration::from_secs(30 * 60),
+                tolerance: Duration::from_secs(120),
+            }]
+        );
+
+        clock.advance(Duration::from_secs(15 * 60));
+        let idle = FakeIdle(Cell::new(Some(Duration::from_secs(600))));
+        let commands = scheduler.on_timer(&clock, &idle);
+        assert!(
+            !commands
+                .iter()
+                .any(|c| matches!(c, Command::Record(Event::NaturalBreak { .. })))
+        );
+        assert_eq!(scheduler.phase(), Phase::Working);
+        assert_eq!(scheduler.remaining(&clock), Duration::from_secs(15 * 60));
+        assert_eq!(scheduler.short_breaks_since_long, 0);
+    }
+
+    #[test]
+    fn natural_long_break_needs_long_break_length() {
+        let clock = FakeClock::new();
+        let mut scheduler = Scheduler::new(Timings {
+            natural_break: true,
+            long_break_every: 1,
+            ..timings()
+        });
+        scheduler.enter(Phase::Working, &clock);
+        clock.advance(Duration::from_secs(600));
+
+        let idle = FakeIdle(Cell::new(Some(Duration::from_secs(300))));
+        assert_eq!(
+            scheduler.on_timer(&clock, &idle),
+            vec![Command::ArmTimer {
+                after: Duration::from_secs(300),
+                tolerance: Duration::from_secs(120),
+            }]
+        );
+
+        idle.0.set(Some(Duration::from_secs(600)));
+        clock.advance(Duration::from_secs(300));
+        assert_eq!(
+            scheduler.on_timer(&clock, &idle)[0],
+            Command::Record(Event::NaturalBreak {
+                kind: BreakKind::Long,
+                idle: Duration::from_secs(600),
+            })
+        );
+    }
+
+    #[test]
+    fn samples_stop_at_the_deadline() {
+        let (mut scheduler, clock) = with_natural_breaks();
+        clock.advance(Duration::from_secs(30 * 60 - 60));
+        assert_eq!(
+            scheduler.on_timer(&clock, &FakeIdle::active()),
+            vec![Command::ArmTimer {
+                after: Duration::from_secs(60),
+                tolerance: Duration::from_secs(120),
+            }]
+        );
+
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(
+            scheduler.on_timer(&clock, &FakeIdle::active())[0],
+            short_break()
+        );
+    }
+
+    #[test]
+    fn unknown_idle_arms_the_deadline_directly() {
+        let (mut scheduler, clock) = with_natural_breaks();
+        clock.advance(Duration::from_secs(120));
+        assert_eq!(
+            scheduler.on_timer(&clock, &FakeIdle(Cell::new(None))),
+            vec![Command::ArmTimer {
+                after: Duration::from_secs(28 * 60),
+                tolerance: Duration::from_secs(120),
+            }]
+        );
+    }
+
+    /// 由离开时间段构成的合成空闲轨迹（相对周期开始的秒数，左闭右开），其余时间一直在操作
+    struct TraceIdle<'a> {
+        clock: &'a FakeClock,
+        start: Instant,
+        away: &'a [(u64, u64)],
+    }
+
+    impl IdleSource for TraceIdle<'_> {
+        fn idle_duration(&self) -> Option<Duration> {
+            let t = self.clock.now().duration_since(self.start).as_secs();
+            let idle = self
+                .away
+                .iter()
+                .find(|(from, to)| (*from..*to).contains(&t))
+                .map_or(0, |(from, _)| t - from);
+            Some(Duration::from_secs(idle))
+        }
+    }
+
+    /// 按定时器指令推进假时钟直到开始休息，返回距周期开始的时长与唤醒次数
+    fn run_until_break(
+        scheduler: &mut Scheduler,
+        clock: &FakeClock,
+        idle: &impl IdleSource,
+        mut commands: Vec<Command>,
+    ) -> (Duration, usize) {
+        let start = clock.now();
+        let mut wakes = 0;
+        loop {
+            let after = commands
+                .iter()
+                .find_map(|c| match c {
+                    Command::ArmTimer { after, .. } => Some(*after),
+                    _ => None,
+                })
+                .expect("timer armed");
+            clock.advance(after);
+            wakes += 1;
+            commands = scheduler.on_timer(clock, idle);
+            if commands
+                .iter()
+                .any(|c| matches!(c, Command::ShowBreak { .. }))
+            {
+                return (clock.now() - start, wakes);
+            }
+        }
+    }
+
+    fn active_time_scheduler() -> Scheduler {
+        Scheduler::new(Timings {
+            active_threshold: Some(Duration::from_secs(120)),
+            ..timings()
+        })
+    }
+
+    #[test]
+    fn active_time_without_idle_matches_wall_clock() {
+        let clock = FakeClock::new();
+        let mut scheduler = active_time_scheduler();
+        let idle = TraceIdle {
+            clock: &clock,
+            start: clock.now(),
+            away: &[],
+        };
+        let commands = scheduler.enter(Phase::Working, &clock);
+
+        let (elapsed, wakes) = run_until_break(&mut scheduler, &clock, &idle, commands);
+        assert_eq!(elapsed, Duration::from_secs(30 * 60));
+        // 每个阈值最多唤醒一次，不做逐秒轮询
+        assert_eq!(wakes, 15);
+    }
+
+    #[test]
+    fn long_idle_stretch_does_not_count_toward_interval() {
+        let clock = FakeClock::new();
+        let mut scheduler = active_time_scheduler();
+        let idle = TraceIdle {
+            clock: &clock,
+            start: clock.now(),
+            away: &[(5 * 60, 15 * 60)],
+        };
+        let commands = scheduler.enter(Phase::Working, &clock);
+
+        let (elapsed, _) = run_until_break(&mut scheduler, &clock, &idle, commands);
+        // 离开 10 分钟，误差不超过两个阈值（开始与结束各一个）
+        assert!(elapsed <= Duration::from_secs(40 * 60), "{elapsed:?}");
+        assert!(elapsed >= Duration::from_secs(36 * 60), "{elapsed:?}");
+    }
+
+    #[test]
+    fn short_idle_stretches_still_count() {
+        let clock = FakeClock::new();
+        let mut scheduler = active_time_scheduler();
+        let idle = TraceIdle {
+            clock: &clock,
+            start: clock.now(),
+            away: &[(100, 190), (400, 500), (1000, 1110)],
+        };
+        let commands = scheduler.enter(Phase::Working, &clock);
+
+        let (elapsed, _) = run_until_break(&mut scheduler, &clock, &idle, commands);
+        assert_eq!(elapsed, Duration::from_secs(30 * 60));
+    }
+
+    #[test]
+    fn idle_at_deadline_defers_the_break() {
+        let clock = FakeClock::new();
+        let mut scheduler = active_time_scheduler();
+        scheduler.enter(Phase::Working, &clock);
+        clock.advance(Duration::from_secs(30 * 60));
+
+        // 最近 5 分钟都在看纸质文档
+        let idle = FakeIdle(Cell::new(Some(Duration::from_secs(300))));
+        let commands = scheduler.on_timer(&clock, &idle);
+        assert_eq!(
+            commands,
+            vec![Command::ArmTimer {
+                after: Duration::from_secs(120),
+                tolerance: Duration::from_secs(120),
+            }]
+        );
+        // 5 分钟空闲不计入，截止时间顺延
+        assert_eq!(scheduler.remaining(&clock), Duration::from_secs(300));
+        assert_eq!(scheduler.phase(), Phase::Working);
+    }
+
+    #[test]
+    fn active_time_with_unknown_idle_uses_wall_clock() {
+        let clock = FakeClock::new();
+        let mut scheduler = active_time_scheduler();
+        let commands = scheduler.enter(Phase::Working, &clock);
+        let (elapsed, wakes) =
+            run_until_break(&mut scheduler, &clock, &FakeIdle(Cell::new(None)), commands);
+        assert_eq!(elapsed, Duration::from_secs(30 * 60));
+        assert_eq!(wakes, 2);
+    }
+
+    #[test]
+    fn completed_break_records_compliance() {
+        let (mut scheduler, clock) = started();
+        scheduler.start_break_now(&clock);
+        clock.advance(Duration::from_secs(120));
+
+        let idle = FakeIdle(Cell::new(Some(Duration::from_secs(70))));
+        let commands = scheduler.on_timer(&clock, &idle);
+        assert_eq!(
+            commands[..2],
+            [
+                Command::EndBreak,
+                Command::Record(Event::BreakCompleted {
+                    kind: BreakKind::Short,
+                    compliance: BreakCompliance::Partial,
+                    elapsed: Duration::from_secs(120),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn active_break_is_extended_once_by_the_active_portion() {
+        let clock = FakeClock::new();
+        let mut scheduler = Scheduler::new(Timings {
+            extend_active_breaks: true,
+            ..timings()
+        });
+        scheduler.enter(Phase::Working, &clock);
+        scheduler.start_break_now(&clock);
+        clock.advance(Duration::from_secs(120));
+
+        let idle = FakeIdle(Cell::new(Some(Duration::from_secs(30))));
+        let extra = Duration::from_secs(90);
+        assert_eq!(
+            scheduler.on_timer(&clock, &idle),
+            vec![
+                Command::Record(Event::BreakExtended {
+                    kind: BreakKind::Short,
+                    extra,
+                }),
+                Command::UpdateCountdown { remaining: extra },
+                Command::ArmTimer {
+                    after: extra,
+                    tolerance: Duration::from_secs(5),
+                },
+            ]
+        );
+        assert_eq!(scheduler.phase(), Phase::Breaking);
+        assert_eq!(scheduler.remaining(&clock), extra);
+
+        // 延长后仍在操作电脑：只记录判定，不再延长
+        clock.advance(extra);
+        idle.0.set(Some(Duration::from_secs(20)));
+        assert_eq!(
+            scheduler.on_timer(&clock, &idle)[1],
+            Command::Record(Event::BreakCompleted {
+                kind: BreakKind::Short,
+                compliance: BreakCompliance::Active,
+                elapsed: Duration::from_secs(120) + extra,
+            })
+        );
+        assert_eq!(scheduler.phase(), Phase::Working);
+    }
+
+    #[test]
+    fn rested_break_is_not_extended() {
+        let clock = FakeClock::new();
+        let mut scheduler = Scheduler::new(Timings {
+            extend_active_breaks: true,
+            ..timings()
+        });
+        scheduler.enter(Phase::Working, &clock);
+        scheduler.start_break_now(&clock);
+        clock.advance(Duration::from_secs(120));
+
+        let idle = FakeIdle(Cell::new(Some(Duration::from_secs(119))));
+        assert_eq!(scheduler.on_timer(&clock, &idle)[0], Command::EndBreak);
+    }
+
+    #[test]
+    fn no_sleep_means_no_commands() {
+        let (mut scheduler, clock) = started();
+        clock.advance(Duration::from_secs(600));
+        clock.sleep(Duration::from_secs(29));
+        assert!(scheduler.on_wake(&clock).is_empty());
+    }
+
+    #[test]
+    fn long_sleep_counts_as_rest_and_restarts_work() {
+        let (mut scheduler, clock) = started();
+        clock.advance(Duration::from_secs(600));
+        let deadline_wall = scheduler.deadline_wall();
+        clock.sleep(Duration::from_secs(3600));
+
+        assert_eq!(
+            scheduler.on_wake(&clock),
+            vec![
+                Command::Record(Event::SleepRest {
+                    kind: BreakKind::Short,
+                    slept: Duration::from_secs(3600),
+                }),
+                Command::ArmTimer {
+                    after: Duration::from_secs(1800),
+                    tolerance: Duration::from_secs(120),
+                },
+            ]
+        );
+        assert_eq!(scheduler.remaining(&clock), Duration::from_secs(1800));
+        assert_ne!(scheduler.deadline_wall(), deadline_wall);
+        // 睡眠只核算一次
+        assert!(scheduler.on_wake(&clock).is_empty());
+    }
+
+    #[test]
+    fn stale_timer_after_sleep_does_not_start_break() {
+        let (mut scheduler, clock) = started();
+        clock.advance(Duration::from_secs(1200));
+        clock.sleep(Duration::from_secs(2 * 3600));
+
+        let commands = scheduler.on_timer(&clock, &FakeIdle::active());
+        assert!(
+            !commands
+                .iter()
+                .any(|c| matches!(c, Command::ShowBreak { .. }))
+        );
+        assert_eq!(scheduler.phase(), Phase::Working);
+        assert_eq!(scheduler.remaining(&clock), Duration::from_secs(1800));
+    }
+
+    #[test]
+    fn sleep_rest_advances_long_break_cycle() {
+        let clock = FakeClock::new();
+        let mut scheduler = Scheduler::new(Timings {
+            long_break_every: 2,
+            ..timings()
+        });
+        scheduler.enter(Phase::Working, &clock);
+        take_break(&mut scheduler, &clock);
+
+        // 下一次是长休息，睡眠不足长休息时长时不计为休息
+        clock.sleep(Duration::from_secs(300));
+        assert_eq!(
+            scheduler.on_wake(&clock)[0],
+            Command::Record(Event::Woke {
+                slept: Duration::from_secs(300),
+            })
+        );
+        assert_eq!(scheduler.next_break_kind(), BreakKind::Long);
+
+        clock.sleep(Duration::from_secs(600));
+        assert_eq!(
+            scheduler.on_wake(&clock)[0],
+            Command::Record(Event::SleepRest {
+                kind: BreakKind::Long,
+                slept: Duration::from_secs(600),
+            })
+        );
+        assert_eq!(scheduler.next_break_kind(), BreakKind::Short);
+    }
+
+    #[test]
+    fn short_sleep_counts_toward_work_interval() {
+        let (mut scheduler, clock) = started();
+        clock.advance(Duration::from_secs(600));
+        let deadline_wall = scheduler.deadline_wall();
+        clock.sleep(Duration::from_secs(60));
+
+        assert_eq!(
+            scheduler.on_wake(&clock),
+            vec![
+                Command::Record(Event::Woke {
+                    slept: Duration::from_secs(60),
+                }),
+                Command::ArmTimer {
+                    after: Duration::from_secs(1140),
+                    tolerance: Duration::from_secs(120),
+                },
+            ]
+        );
+        assert_eq!(scheduler.remaining(&clock), Duration::from_secs(1140));
+        assert_eq!(scheduler.deadline_wall(), deadline_wall);
+    }
+
+    #[test]
+    fn short_sleep_in_active_time_mode_is_not_work() {
+        let clock = FakeClock::new();
+        let mut scheduler = active_time_scheduler();
+        scheduler.enter(Phase::Working, &clock);
+        clock.advance(Duration::from_secs(600));
+        let deadline_wall = scheduler.deadline_wall().unwrap();
+        clock.sleep(Duration::from_secs(60));
+
+        scheduler.on_wake(&clock);
+        assert_eq!(scheduler.remaining(&clock), Duration::from_secs(1200));
+        assert_eq!(
+            scheduler.deadline_wall(),
+            Some(deadline_wall + Duration::from_secs(60))
+        );
+    }
+
+    #[test]
+    fn sleep_through_break_completes_it() {
+        let (mut scheduler, clock) = started();
+        scheduler.start_break_now(&clock);
+        clock.advance(Duration::from_secs(30));
+        clock.sleep(Duration::from_secs(90));
+
+        let commands = scheduler.on_wake(&clock);
+        assert_eq!(commands[0], Command::EndBreak);
+        assert_eq!(
+            commands[1],
+            Command::Record(Event::BreakCompleted {
+                kind: BreakKind::Short,
+                compliance: BreakCompliance::Rested,
+                elapsed: Duration::from_secs(120),
+            })
+        );
+        assert_eq!(scheduler.phase(), Phase::Working);
+    }
+
+    #[test]
+    fn short_sleep_during_break_fixes_countdown() {
+        let (mut scheduler, clock) = started();
+        scheduler.start_break_now(&clock);
+        clock.advance(Duration::from_secs(30));
+        clock.sleep(Duration::from_secs(40));
+
+        assert_eq!(
+            scheduler.on_wake(&clock),
+            vec![
+                Command::Record(Event::Woke {
+                    slept: Duration::from_secs(40),
+                }),
+                Command::UpdateCountdown {
+                    remaining: Duration::from_secs(50),
+                },
+                Command::ArmTimer {
+                    after: Duration::from_secs(50),
+                    tolerance: Duration::from_secs(5),
+                },
+            ]
+        );
+        assert_eq!(scheduler.phase(), Phase::Breaking);
+    }
+
+    #[test]
+    fn pause_expiring_during_sleep_resumes_on_wake() {
+        let (mut scheduler, clock) = started();
+        scheduler.pause(
+            Pause::for_duration(Duration::from_secs(1800), &clock),
+            &clock,
+        );
+        clock.sleep(Duration::from_secs(3600));
+
+        let commands = scheduler.on_wake(&clock);
+        assert!(commands.contains(&Command::Record(Event::Resumed)));
+        assert_eq!(scheduler.paused(), None);
+        assert_eq!(scheduler.remaining(&clock), Duration::from_secs(1800));
+    }
+
+    fn awaiting_return() -> (Scheduler, FakeClock) {
+        let clock = FakeClock::new();
+        let mut scheduler = Scheduler::new(Timings {
+            await_return: true,
+            ..timings()
+        });
+        scheduler.enter(Phase::Working, &clock);
+        scheduler.start_break_now(&clock);
+        clock.advance(Duration::from_secs(120));
+        let idle = FakeIdle(Cell::new(Some(Duration::from_secs(120))));
+        assert_eq!(
+            scheduler.on_timer(&clock, &idle).last(),
+            Some(&Command::ArmTimer {
+                after: RETURN_POLL_INTERVAL,
+                tolerance: Duration::from_secs(1),
+            })
+        );
+        (scheduler, clock)
+    }
+
+    #[test]
+    fn completed_break_waits_for_return() {
+        let (mut scheduler, clock) = awaiting_return();
+        assert_eq!(scheduler.phase(), Phase::AwaitingReturn);
+        assert_eq!(scheduler.deadline_wall(), None);
+
+        // 仍未回来：继续采样
+        clock.advance(RETURN_POLL_INTERVAL);
+        let idle = FakeIdle(Cell::new(Some(Duration::from_secs(125))));
+        assert_eq!(
+            scheduler.on_timer(&clock, &idle),
+            vec![Command::ArmTimer {
+                after: RETURN_POLL_INTERVAL,
+                tolerance: Duration::from_secs(1),
+            }]
+        );
+        assert_eq!(scheduler.phase(), Phase::AwaitingReturn);
+
+        // 检测到输入：从此刻开始完整的工作周期
+        clock.advance(RETURN_POLL_INTERVAL);
+        idle.0.set(Some(Duration::from_secs(2)));
+        assert_eq!(
+            scheduler.on_timer(&clock, &idle),
+            vec![
+                Command::Record(Event::Returned {
+                    away: Duration::from_secs(10),
+                }),
+                Command::ArmTimer {
+                    after: Duration::from_secs(1800),
+                    tolerance: Duration::from_secs(120),
+                },
+            ]
+        );
+        assert_eq!(scheduler.phase(), Phase::Working);
+    }
+
+    #[test]
+    fn waking_while_awaiting_return_counts_as_returning() {
+        let (mut scheduler, clock) = awaiting_return();
+        clock.advance(RETURN_POLL_INTERVAL);
+        clock.sleep(Duration::from_secs(3600));
+        assert_eq!(
+            scheduler.on_wake(&clock),
+            vec![
+                Command::Record(Event::Woke {
+                    slept: Duration::from_secs(3600),
+                }),
+                Command::Record(Event::Returned {
+                    away: Duration::from_secs(3605),
+                }),
+                Command::ArmTimer {
+                    after: Duration::from_secs(1800),
+                    tolerance: Duration::from_secs(120),
+                },
+            ]
+        );
+        assert_eq!(scheduler.phase(), Phase::Working);
+    }
+
+    #[test]
+    fn skipped_break_does_not_wait_for_return() {
+        let clock = FakeClock::new();
+        let mut scheduler = Scheduler::new(Timings {
+            await_return: true,
+            ..timings()
+        });
+        scheduler.enter(Phase::Working, &clock);
+        scheduler.start_break_now(&clock);
+        scheduler.skip_break(&clock);
+        assert_eq!(scheduler.phase(), Phase::Working);
+    }
+
+    #[test]
+    fn unknown_idle_starts_work_without_waiting() {
+        let (mut scheduler, clock) = awaiting_return();
+        clock.advance(RETURN_POLL_INTERVAL);
+        assert_eq!(
+            scheduler.on_timer(&clock, &FakeIdle(Cell::new(None))),
+            vec![Command::ArmTimer {
+                after: Duration::from_secs(1800),
+                tolerance: Duration::from_secs(120),
+            }]
+        );
+        assert_eq!(scheduler.phase(), Phase::Working);
+    }
+
+    #[test]
+    fn waking_the_computer_counts_as_return() {
+        let (mut scheduler, clock) = awaiting_return();
+        clock.sleep(Duration::from_secs(3600));
+        scheduler.on_wake(&clock);
+        assert_eq!(scheduler.phase(), Phase::Working);
+        assert_eq!(scheduler.remaining(&clock), Duration::from_secs(1800));
+    }
+
+    #[test]
+    fn disabling_await_return_starts_work() {
+        let (mut scheduler, clock) = awaiting_return();
+        scheduler.set_timings(timings());
+        scheduler.enter(Phase::AwaitingReturn, &clock);
+        assert_eq!(scheduler.phase(), Phase::Working);
+    }
+
+    #[test]
+    fn ui_only_settings_change_does_not_reschedule() {
+        let (mut scheduler, clock) = started();
+        clock.advance(Duration::from_secs(600));
+        assert!(scheduler.apply_settings(timings(), None, &clock).is_empty());
+        assert_eq!(scheduler.remaining(&clock), Duration::from_secs(1200));
+    }
+
+    #[test]
+    fn longer_interval_keeps_elapsed_work() {
+        let (mut scheduler, clock) = started();
+        clock.advance(Duration::from_secs(600));
+        let commands = scheduler.apply_settings(
+            Timings {
+                work_interval: Duration::from_secs(45 * 60),
+                ..timings()
+            },
+            None,
+            &clock,
+        );
+        assert_eq!(
+            commands,
+            vec![Command::ArmTimer {
+                after: Duration::from_secs(2100),
+                tolerance: Duration::from_secs(120),
+            }]
+        );
+        assert_eq!(
+            scheduler.deadline_wall(),
+            Some(clock.now_wall() + Duration::from_secs(2100))
+        );
+    }
+
+    #[test]
+    fn shorter_interval_already_elapsed_breaks_now() {
+        let (mut scheduler, clock) = started();
+        clock.advance(Duration::from_secs(1200));
+        let commands = scheduler.apply_settings(
+            Timings {
+                work_interval: Duration::from_secs(15 * 60),
+                ..timings()
+            },
+            None,
+            &clock,
+        );
+        assert_eq!(
+            commands,
+            vec![Command::ArmTimer {
+                after: Duration::ZERO,
+                tolerance: Duration::from_secs(120),
+            }]
+        );
+        assert_eq!(
+            scheduler.on_timer(&clock, &FakeIdle::active())[0],
+            short_break()
+        );
+    }
+
+    #[test]
+    fn break_length_change_keeps_countdown_progress() {
+        let (mut scheduler, clock) = started();
+        scheduler.start_break_now(&clock);
+        clock.advance(Duration::from_secs(30));
+        let commands = scheduler.apply_settings(
+            Timings {
+                break_duration: Duration::from_secs(300),
+                ..timings()
+            },
+            None,
+            &clock,
+        );
+        assert_eq!(
+            commands,
+            vec![
+                Command::UpdateCountdown {
+                    remaining: Duration::from_secs(270),
+                },
+                Command::ArmTimer {
+                    after: Duration::from_secs(270),
+                    tolerance: Duration::from_secs(5),
+                },
+            ]
+        );
+        assert_eq!(scheduler.phase(), Phase::Breaking);
+    }
+
+    #[test]
+    fn postponed_cycle_keeps_postpone_length() {
+        let (mut scheduler, clock) = started();
+        scheduler.start_break_now(&clock);
+        scheduler.postpone(&clock);
+        clock.advance(Duration::from_secs(60));
+        scheduler.apply_settings(
+            Timings {
+                work_interval: Duration::from_secs(45 * 60),
+                ..timings()
+            },
+            None,
+            &clock,
+        );
+        assert_eq!(scheduler.remaining(&clock), Duration::from_secs(240));
+    }
+
+    #[test]
+    fn new_work_hours_outside_window_go_off_hours() {
+        let clock = FakeClock::at(MONDAY + 20 * 3600);
+        let mut scheduler = Scheduler::new(timings());
+        scheduler.enter(Phase::Working, &clock);
+        scheduler.apply_settings(timings(), Some(office_hours()), &clock);
+        assert_eq!(scheduler.phase(), Phase::OffHours);
+    }
+}
@@ -0,0 +1,411 @@
+//! 休息统计。
+//!
+//! 由休息历史（[`Record`]）计算每日、每周的汇总与连续天数，全部为纯函数。日期按本地时间划分，
+//! 调用方需传入本地时区相对 UTC 的偏移秒数；日期以自 Unix 纪元起的本地天数表示。
+
+use std::collections::BTreeMap;
+
+use crate::history::{Record, RecordKind};
+use crate::work_hours::{self, SECS_PER_DAY};
+
+/// 一段时间内的休息汇总
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Tally {
+    /// 完成的休息：按时结束、工作中离开足够久、睡眠达到休息时长
+    pub taken: u32,
+    /// 用户跳过的休息
+    pub skipped: u32,
+    /// 按闲置策略自动跳过的休息（人不在电脑前，不计入遵守率）
+    pub idle_skipped: u32,
+    /// 完成的休息的实际总时长（秒）
+    pub rest_seconds: u64,
+    /// 同一天内两次休息之间最长的连续工作时长（秒）
+    pub longest_work_seconds: u64,
+}
+
+impl Tally {
+    /// 应当休息的次数：完成与跳过之和
+    pub const fn due(&self) -> u32 {
+        self.taken + self.skipped
+    }
+
+    /// 遵守率（完成次数占应当休息次数的百分比，四舍五入）；没有应当休息的休息时为 `None`
+    pub fn compliance_percent(&self) -> Option<u8> {
+        let due = u64::from(self.due());
+        if due == 0 {
+            return None;
+        }
+        let percent = (u64::from(self.taken) * 100 + due / 2) / due;
+        u8::try_from(percent).ok()
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.taken += other.taken;
+        self.skipped += other.skipped;
+        self.idle_skipped += other.idle_skipped;
+        self.rest_seconds += other.rest_seconds;
+        self.longest_work_seconds = self.longest_work_seconds.max(other.longest_work_seconds);
+    }
+}
+
+/// 某一天的汇总
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DayStats {
+    /// 本地天数（自 1970-01-01 起）
+    pub day: i64,
+    pub tally: Tally,
+}
+
+/// 某一周（周一至周日）的汇总
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WeekStats {
+    /// 该周周一的本地天数
+    pub week_start: i64,
+    pub tally: Tally,
+}
+
+/// 连续若干天（首尾都包含）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Streak {
+    /// 第一天（本地天数）
+    pub start: i64,
+    /// 最后一天（本地天数）
+    pub end: i64,
+}
+
+impl Streak {
+    /// 天数
+    pub const fn days(&self) -> i64 {
+        self.end - self.start + 1
+    }
+}
+
+/// 时刻（Unix 秒）所在的本地天数
+pub fn local_day(timestamp: u64, utc_offset: i64) -> i64 {
+    i64::try_from(timestamp)
+        .unwrap_or(i64::MAX)
+        .saturating_add(utc_offset)
+        .div_euclid(SECS_PER_DAY)
+}
+
+/// 本地天数所在周的周一
+pub const fn week_start(day: i64) -> i64 {
+    #[allow(clippy::cast_possible_wrap)] // 星期几位于 0..7
+    let weekday = work_hours::weekday(day * SECS_PER_DAY) as i64;
+    day - weekday
+}
+
+/// 本地天数对应的公历日期（年、月、日）
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // 月、日位于 1..=31
+pub const fn civil_date(day: i64) -> (i64, u32, u32) {
+    // 算法见 Howard Hinnant 的 `civil_from_days`，以 0000-03-01 为纪元起点
+    let z = day + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    (y, m as u32, d as u32)
+}
+
+/// 公历日期对应的本地天数；不检查月、日是否有效
+pub const fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let (month, day) = (month as i64, day as i64);
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// 解析 `YYYY-MM-DD` 格式的日期为本地天数；格式不对或日期不存在时返回 `None`
+pub fn parse_date(s: &str) -> Option<i64> {
+    let mut parts = s.trim().splitn(3, '-');
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return None;
+    }
+    let year: i64 = year.parse().ok()?;
+    let (month, day): (u32, u32) = (month.parse().ok()?, day.parse().ok()?);
+    let days = days_from_civil(year, month, day);
+    // 2 月 30 日等不存在的日期换算回来会变成另一天
+    (civil_date(days) == (year, month, day)).then_some(days)
+}
+
+/// 把本地天数格式化为 `YYYY-MM-DD`
+pub fn format_date(day: i64) -> String {
+    let (year, month, day) = civil_date(day);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// 按天汇总，只包含有记录的日期，按日期升序
+///
+/// 连续工作时长从一次休息（或闲置跳过）结束算到下一次完成的休息开始，跨天的区间不计入
+/// （通常是下班或退出了应用）；跳过与推迟不打断连续工作。
+pub fn daily(records: &[Record], utc_offset: i64) -> Vec<DayStats> {
+    let mut sorted: Vec<&Record> = records.iter().collect();
+    sorted.sort_by_key(|record| record.timestamp);
+
+    let mut days: BTreeMap<i64, Tally> = BTreeMap::new();
+    let mut rest_ended: Option<u64> = None;
+    for record in sorted {
+        let day = local_day(record.timestamp, utc_offset);
+        let tally = days.entry(day).or_default();
+        match record.kind {
+            kind if kind.is_rest() => {
+                tally.taken += 1;
+                tally.rest_seconds += record.actual_seconds;
+
+                let rest_started = record.timestamp.saturating_sub(record.actual_seconds);
+                if let Some(ended) = rest_ended
+                    && local_day(ended, utc_offset) == local_day(rest_started, utc_offset)
+                {
+                    let stretch = rest_started.saturating_sub(ended);
+                    tally.longest_work_seconds = tally.longest_work_seconds.max(stretch);
+                }
+                rest_ended = Some(record.timestamp);
+            }
+            RecordKind::IdleSkipped => {
+                tally.idle_skipped += 1;
+                rest_ended = Some(record.timestamp);
+            }
+            RecordKind::Skipped => tally.skipped += 1,
+            _ => {}
+        }
+    }
+    days.into_iter()
+        .map(|(day, tally)| DayStats { day, tally })
+        .collect()
+}
+
+/// 指定本地天数的汇总；当天没有记录时全部为零
+pub fn day_tally(records: &[Record], day: i64, utc_offset: i64) -> Tally {
+    daily(records, utc_offset)
+        .into_iter()
+        .find(|stats| stats.day == day)
+        .map_or_else(Tally::default, |stats| stats.tally)
+}
+
+/// 把每日汇总合并为每周汇总，按周升序
+pub fn weekly(days: &[DayStats]) -> Vec<WeekStats> {
+    let mut weeks: BTreeMap<i64, Tally> = BTreeMap::new();
+    for stats in days {
+        weeks
+            .entry(week_start(stats.day))
+            .or_default()
+            .merge(&stats.tally);
+    }
+    weeks
+        .into_iter()
+        .map(|(week_start, tally)| WeekStats { week_start, tally })
+        .collect()
+}
+
+/// 指定本地天数所在周的汇总
+pub fn week_tally(records: &[Record], day: i64, utc_offset: i64) -> Tally {
+    let week = week_start(day);
+    weekly(&daily(records, utc_offset))
+        .into_iter()
+        .find(|stats| stats.week_start == week)
+        .map_or_else(Tally::default, |stats| stats.tally)
+}
+
+/// 全部日期的合计；最长连续工作取各天的最大值
+pub fn total(days: &[DayStats]) -> Tally {
+    let mut total = Tally::default();
+    for stats in days {
+        total.merge(&stats.tally);
+    }
+    total
+}
+
+/// 满足条件的连续日期段，按时间先后排列；没有记录的日期视为不满足
+pub fn streaks(days: &[DayStats], meets: impl Fn(&Tally) -> bool) -> Vec<Streak> {
+    let mut streaks: Vec<Streak> = Vec::new();
+    for stats in days.iter().filter(|stats| meets(&stats.tally)) {
+        match streaks.last_mut() {
+            Some(streak) if streak.end + 1 == stats.day => streak.end = stats.day,
+            _ => streaks.push(Streak {
+                start: stats.day,
+                end: stats.day,
+            }),
+        }
+    }
+    streaks
+}
+
+/// 用户跳过休息的次数按本地时间的小时（0–23）分布
+pub fn skips_by_hour(records: &[Record], utc_offset: i64) -> [u32; 24] {
+    let mut hours = [0; 24];
+    for record in records.iter().filter(|r| r.kind == RecordKind::Skipped) {
+        let local = i64::try_from(record.timestamp)
+            .unwrap_or(i64::MAX)
+            .saturating_add(utc_offset);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // 位于 0..24
+        let hour = (local.rem_euclid(SECS_PER_DAY) / 3600) as usize;
+        hours[hour] += 1;
+    }
+    hours
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::BreakKind;
+
+    /// 2024-01-01（周一）00:00 UTC 的本地天数
+    const MONDAY: i64 = 19_723;
+
+    fn record(day: i64, hh: u64, mm: u64, kind: RecordKind, actual: u64) -> Record {
+        Record {
+            timestamp: u64::try_from(day * SECS_PER_DAY).unwrap() + hh * 3600 + mm * 60,
+            kind,
+            break_kind: BreakKind::Short,
+            planned_seconds: 120,
+            actual_seconds: actual,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn counts_outcomes_per_local_day() {
+        let records = [
+            record(MONDAY, 9, 30, RecordKind::Started, 0),
+            record(MONDAY, 9, 32, RecordKind::Completed, 120),
+            record(MONDAY, 10, 2, RecordKind::Skipped, 5),
+            record(MONDAY, 10, 40, RecordKind::IdleSkipped, 0),
+            record(MONDAY, 11, 10, RecordKind::Postponed, 0),
+            record(MONDAY, 11, 20, RecordKind::NaturalBreak, 120),
+            record(MONDAY, 23, 30, RecordKind::Completed, 60),
+        ];
+        let days = daily(&records, 0);
+        assert_eq!(days.len(), 1);
+        let tally = days[0].tally;
+        assert_eq!((tally.taken, tally.skipped, tally.idle_skipped), (3, 1, 1));
+        assert_eq!(tally.rest_seconds, 300);
+        assert_eq!(tally.compliance_percent(), Some(75));
+
+        // 东八区：23:30 UTC 已是次日
+        let days = daily(&records, 8 * 3600);
+        assert_eq!(
+            days.iter()
+                .map(|d| (d.day, d.tally.taken))
+                .collect::<Vec<_>>(),
+            [(MONDAY, 2), (MONDAY + 1, 1)]
+        );
+        assert_eq!(day_tally(&records, MONDAY + 1, 8 * 3600).taken, 1);
+        assert_eq!(day_tally(&records, MONDAY + 2, 0), Tally::default());
+    }
+
+    #[test]
+    fn longest_work_stretch_spans_skips_but_not_nights() {
+        let records = [
+            // 前一晚最后一次休息到次日第一次休息之间不计
+            record(MONDAY - 1, 22, 0, RecordKind::Completed, 120),
+            record(MONDAY, 9, 2, RecordKind::Completed, 120),
+            // 跳过不打断：09:02 → 10:30 开始休息，共 88 分钟
+            record(MONDAY, 9, 40, RecordKind::Skipped, 0),
+            record(MONDAY, 10, 32, RecordKind::Completed, 120),
+            // 闲置跳过视为离开，之后重新计算
+            record(MONDAY, 12, 0, RecordKind::IdleSkipped, 0),
+            record(MONDAY, 12, 50, RecordKind::SleepRest, 600),
+        ];
+        let days = daily(&records, 0);
+        assert_eq!(days[0].tally.longest_work_seconds, 0);
+        assert_eq!(days[1].tally.longest_work_seconds, 88 * 60);
+    }
+
+    #[test]
+    fn weeks_start_on_monday_and_merge_days() {
+        assert_eq!(week_start(MONDAY), MONDAY);
+        assert_eq!(week_start(MONDAY + 6), MONDAY);
+        assert_eq!(week_start(MONDAY + 7), MONDAY + 7);
+        assert_eq!(
+            local_day(u64::try_from(MONDAY * SECS_PER_DAY).unwrap() - 1, 0),
+            MONDAY - 1
+        );
+
+        let records = [
+            record(MONDAY, 10, 0, RecordKind::Completed, 120),
+            record(MONDAY + 6, 10, 0, RecordKind::Skipped, 0),
+            record(MONDAY + 7, 10, 0, RecordKind::Completed, 120),
+            record(MONDAY + 8, 10, 0, RecordKind::IdleSkipped, 0),
+        ];
+        let weeks = weekly(&daily(&records, 0));
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(weeks[0].week_start, MONDAY);
+        assert_eq!(weeks[0].tally.compliance_percent(), Some(50));
+        assert_eq!(weeks[1].tally.compliance_percent(), Some(100));
+        assert_eq!(weeks[1].tally.idle_skipped, 1);
+        assert_eq!(week_tally(&records, MONDAY + 3, 0), weeks[0].tally);
+        assert_eq!(week_tally(&records, MONDAY + 14, 0), Tally::default());
+        assert_eq!(Tally::default().compliance_percent(), None);
+    }
+
+    #[test]
+    fn streaks_need_consecutive_days_and_skips_group_by_hour() {
+        let records = [
+            record(MONDAY, 9, 0, RecordKind::Completed, 120),
+            record(MONDAY + 1, 9, 0, RecordKind::Completed, 120),
+            record(MONDAY + 1, 14, 59, RecordKind::Skipped, 0),
+            record(MONDAY + 2, 9, 0, RecordKind::Completed, 120),
+            // MONDAY + 3 没有记录，打断连续
+            record(MONDAY + 4, 9, 0, RecordKind::Completed, 120),
+            record(MONDAY + 4, 15, 0, RecordKind::Skipped, 0),
+        ];
+        let days = daily(&records, 0);
+        assert_eq!(
+            streaks(&days, |tally| tally.taken > 0),
+            [
+                Streak {
+                    start: MONDAY,
+                    end: MONDAY + 2,
+                },
+                Streak {
+                    start: MONDAY + 4,
+                    end: MONDAY + 4,
+                },
+            ]
+        );
+        let no_skips = streaks(&days, |tally| tally.taken > 0 && tally.skipped == 0);
+        assert_eq!(
+            no_skips.iter().map(Streak::days).collect::<Vec<_>>(),
+            [1, 1]
+        );
+
+        let total = total(&days);
+        assert_eq!((total.taken, total.skipped), (4, 2));
+
+        let hours = skips_by_hour(&records, 0);
+        assert_eq!((hours[14], hours[15], hours.iter().sum::<u32>()), (1, 1, 2));
+        // 东八区：14:59 UTC 为 22:59
+        assert_eq!(skips_by_hour(&records, 8 * 3600)[22], 1);
+    }
+
+    #[test]
+    fn dates_round_trip_through_days() {
+        assert_eq!(parse_date("2024-01-01"), Some(MONDAY));
+        assert_eq!(parse_date("1970-01-01"), Some(0));
+        assert_eq!(parse_date("1969-12-31"), Some(-1));
+        assert_eq!(parse_date("2024-02-29"), Some(MONDAY + 59));
+        assert_eq!(format_date(MONDAY + 59), "2024-02-29");
+        for bad in [
+            "2023-02-29",
+            "2024-13-01",
+            "2024-1-1",
+            "2024/01/01",
+            "",
+            "2024-01-01x",
+        ] {
+            assert_eq!(parse_date(bad), None, "{bad}");
+        }
+        for day in (-719_000..2_900_000).step_by(997) {
+            assert_eq!(parse_date(&format_date(day)), Some(day), "{day}");
+        }
+    }
+}
@@ -0,0 +1,611 @@
+//! 配置文件监听与热加载。
+//!
+//! 监听配置文件所在目录（Linux 使用 inotify，macOS 使用 kqueue），而不是文件本身：
+//! [`FileStore`](crate::config::FileStore) 与同步工具通常先写临时文件再重命名替换，
+//! 替换后原文件上的监听会失效。文件变化后由 [`reload`] 重新读取、校验并列出变化的字段。
+
+use std::io;
+use std::path::Path;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::config::{Config, ConfigStore, FileStore};
+use crate::error::AppError;
+
+/// 收到变化后直到这么长时间内没有新的变化才通知，合并编辑器保存时的连续写入
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// 一次等待的结果
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Wake {
+    /// 文件被写入或替换
+    Changed,
+    /// 到达超时仍没有变化
+    TimedOut,
+    /// 监听被停止
+    Stopped,
+}
+
+/// 监听单个文件的变化
+pub struct FileWatcher {
+    inner: platform::Watcher,
+}
+
+impl FileWatcher {
+    /// 开始监听；文件所在目录必须已存在
+    pub fn new(path: &Path) -> io::Result<Self> {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let name = path
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "路径不是文件"))?;
+        Ok(Self {
+            inner: platform::Watcher::new(dir, name)?,
+        })
+    }
+
+    /// 阻塞直到文件被写入或替换；监听被停止时返回 `Ok(false)`
+    pub fn wait(&mut self) -> io::Result<bool> {
+        Ok(self.inner.wait(None)? == Wake::Changed)
+    }
+
+    /// 收到一次变化后继续等待，直到 [`DEBOUNCE`] 内没有新的变化；监听被停止时返回 `Ok(false)`
+    fn wait_settled(&mut self) -> io::Result<bool> {
+        if !self.wait()? {
+            return Ok(false);
+        }
+        loop {
+            match self.inner.wait(Some(DEBOUNCE))? {
+                Wake::Changed => {}
+                Wake::TimedOut => return Ok(true),
+                Wake::Stopped => return Ok(false),
+            }
+        }
+    }
+}
+
+/// 后台监听线程的句柄；丢弃时停止监听并等待线程退出
+#[derive(Debug)]
+pub struct WatchHandle {
+    stopper: platform::Stopper,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        // 无法唤醒监听线程时不等待，避免阻塞调用方
+        if self.stopper.stop().is_ok()
+            && let Some(thread) = self.thread.take()
+        {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// 在后台线程中监听文件，每次变化后调用 `on_change`
+///
+/// 监听建立失败时立即返回错误；之后读取事件出错时线程静默退出。
+/// 需要保留返回的句柄，丢弃句柄即停止监听。
+pub fn spawn(path: &Path, on_change: impl Fn() + Send + 'static) -> io::Result<WatchHandle> {
+    let mut watcher = FileWatcher::new(path)?;
+    let stopper = watcher.inner.stopper()?;
+    let thread = std::thread::Builder::new()
+        .name("restgap-config-watch".to_string())
+        .spawn(move || {
+            while matches!(watcher.wait_settled(), Ok(true)) {
+                on_change();
+            }
+        })?;
+    Ok(WatchHandle {
+        stopper,
+        thread: Some(thread),
+    })
+}
+
+/// 重新加载后的配置及其变化
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reloaded {
+    pub config: Config,
+    /// 变化的字段，如 `interval_minutes: 30 → 45`
+    pub changes: Vec<String>,
+}
+
+/// 从存储重新加载配置并与当前配置比较
+///
+/// 文件不存在或有不合法的值时整体拒绝（不像启动时那样使用默认值或限制到合法范围），
+/// 保留当前配置；内容与当前配置相同（例如应用自己保存触发的变化）时返回 `Ok(None)`。
+pub fn reload(store: &FileStore, current: &Config) -> Result<Option<Reloaded>, Vec<AppError>> {
+    // 文件被删除时读取会得到全部默认值，不能当作一次修改
+    match store.path().try_exists() {
+        Ok(true) => {}
+        Ok(false) => {
+            return Err(vec![AppError::System(format!(
+                "配置文件不存在: {}",
+                store.path().display()
+            ))]);
+        }
+        Err(err) => return Err(vec![AppError::System(format!("读取配置失败: {err}"))]),
+    }
+    store
+        .migrate()
+        .map_err(|err| vec![AppError::System(format!("配置迁移失败: {err}"))])?;
+    let raw = store
+        .load_raw()
+        .map_err(|err| vec![AppError::System(format!("读取配置失败: {err}"))])?;
+    let rejected = raw.out_of_range();
+    if !rejected.is_empty() {
+        return Err(rejected.into_iter().map(AppError::from).collect());
+    }
+
+    let config = Config::from_raw(&raw);
+    let changes = describe_changes(current, &config);
+    Ok((!changes.is_empty()).then_some(Reloaded { config, changes }))
+}
+
+/// 按存储中的字段名列出两份配置的差异
+fn describe_changes(old: &Config, new: &Config) -> Vec<String> {
+    let fields = |config: &Config| match serde_json::to_value(config.to_raw()) {
+        Ok(Value::Object(fields)) => fields,
+        _ => serde_json::Map::new(),
+    };
+    let (old, new) = (fields(old), fields(new));
+    let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter_map(|key| {
+            let before = old.get(key).unwrap_or(&Value::Null);
+            let after = new.get(key).unwrap_or(&Value::Null);
+            (before != after).then(|| format!("{key}: {before} → {after}"))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+#[allow(unsafe_code)]
+mod platform {
+    use std::ffi::{CString, OsStr, OsString};
+    use std::fs::File;
+    use std::io::{self, Read, Write};
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+    use std::time::{Duration, Instant};
+
+    use super::Wake;
+
+    /// `struct inotify_event` 中名称之前的固定部分长度
+    const EVENT_HEADER_LEN: usize = 16;
+
+    pub struct Watcher {
+        inotify: File,
+        /// 停止监听时写入的 eventfd
+        wake: File,
+        name: OsString,
+        buf: Vec<u8>,
+    }
+
+    #[derive(Debug)]
+    pub struct Stopper {
+        wake: File,
+    }
+
+    impl Stopper {
+        pub fn stop(&self) -> io::Result<()> {
+            (&self.wake).write_all(&1u64.to_ne_bytes())
+        }
+    }
+
+    impl Watcher {
+        pub fn new(dir: &Path, name: &OsStr) -> io::Result<Self> {
+            let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC) };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let inotify = File::from(unsafe { OwnedFd::from_raw_fd(fd) });
+            let dir = CString::new(dir.as_os_str().as_bytes())?;
+            // 原地写入以关闭文件为准，替换以移入目录为准
+            let mask = libc::IN_CLOSE_WRITE | libc::IN_MOVED_TO;
+            if unsafe { libc::inotify_add_watch(inotify.as_raw_fd(), dir.as_ptr(), mask) } < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let fd = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC) };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Self {
+                inotify,
+                wake: File::from(unsafe { OwnedFd::from_raw_fd(fd) }),
+                name: name.to_os_string(),
+                buf: vec![0; 4096],
+            })
+        }
+
+        pub fn stopper(&self) -> io::Result<Stopper> {
+            Ok(Stopper {
+                wake: self.wake.try_clone()?,
+            })
+        }
+
+        pub fn wait(&mut self, timeout: Option<Duration>) -> io::Result<Wake> {
+            let deadline = timeout.map(|timeout| Instant::now() + timeout);
+            loop {
+                let millis = deadline.map_or(-1, |deadline| {
+                    let left = deadline.saturating_duration_since(Instant::now());
+                    i32::try_from(left.as_millis()).unwrap_or(i32::MAX)
+                });
+                let mut fds =
+                    [self.inotify.as_raw_fd(), self.wake.as_raw_fd()].map(|fd| libc::pollfd {
+                        fd,
+                        events: libc::POLLIN,
+                        revents: 0,
+                    });
+                let n = unsafe { libc::poll(fds.as_mut_ptr(), 2, millis) };
+                if n < 0 {
+                    let err = io::Error::last_os_error();
+                    if err.kind() == io::ErrorKind::Interrupted {
+                        continue;
+                    }
+                    return Err(err);
+                }
+                if n == 0 {
+                    return Ok(Wake::TimedOut);
+                }
+                // eventfd 一经写入便保持可读，之后的等待也会立即返回
+                if fds[1].revents != 0 {
+                    return Ok(Wake::Stopped);
+                }
+                if fds[0].revents == 0 {
+                    continue;
+                }
+                let len = self.inotify.read(&mut self.buf)?;
+                if event_names(&self.buf[..len]).any(|name| name == self.name.as_bytes()) {
+                    return Ok(Wake::Changed);
+                }
+            }
+        }
+    }
+
+    /// 依次取出事件中的文件名（去掉末尾补齐的 NUL）
+    fn event_names(mut events: &[u8]) -> impl Iterator<Item = &[u8]> {
+        std::iter::from_fn(move || {
+            let header = events.get(..EVENT_HEADER_LEN)?;
+            let name_len = u32::from_ne_bytes(header[12..16].try_into().ok()?);
+            let end = EVENT_HEADER_LEN + usize::try_from(name_len).ok()?;
+            let name = events.get(EVENT_HEADER_LEN..end)?;
+            events = &events[end..];
+            let trimmed = name.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+            Some(&name[..trimmed])
+        })
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[allow(unsafe_code)]
+mod platform {
+    use std::ffi::OsStr;
+    use std::fs::{File, OpenOptions};
+    use std::io;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::os::unix::fs::{MetadataExt, OpenOptionsExt};
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, Instant};
+
+    use super::Wake;
+
+    pub struct Watcher {
+        kqueue: OwnedFd,
+        /// 目录上的监听：目录中任意文件被创建、删除或重命名
+        dir: File,
+        /// 文件上的监听：原地写入；文件被替换后需要重新打开
+        file: Option<File>,
+        path: PathBuf,
+        /// 上次看到的文件状态；目录变化时据此判断是否与配置文件有关
+        stamp: Option<Stamp>,
+    }
+
+    /// 文件的 inode、大小与修改时间（秒、纳秒）
+    type Stamp = (u64, u64, i64, i64);
+
+    fn stamp(path: &Path) -> Option<Stamp> {
+        let meta = std::fs::metadata(path).ok()?;
+        Some((meta.ino(), meta.size(), meta.mtime(), meta.mtime_nsec()))
+    }
+
+    /// 持有同一个 kqueue，触发其上的用户事件以唤醒监听线程
+    #[derive(Debug)]
+    pub struct Stopper {
+        kqueue: OwnedFd,
+    }
+
+    impl Stopper {
+        pub fn stop(&self) -> io::Result<()> {
+            apply(&self.kqueue, &stop_event(0, libc::NOTE_TRIGGER))
+        }
+    }
+
+    impl Watcher {
+        pub fn new(dir: &Path, name: &OsStr) -> io::Result<Self> {
+            let fd = unsafe { libc::kqueue() };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let kqueue = unsafe { OwnedFd::from_raw_fd(fd) };
+            let dir_file = open_event_only(dir)?;
+            register(&kqueue, &dir_file, libc::NOTE_WRITE)?;
+            // 不带 EV_CLEAR：触发后保持触发状态，之后的等待也会立即返回
+            apply(&kqueue, &stop_event(libc::EV_ADD, 0))?;
+            let path = dir.join(name);
+            let mut watcher = Self {
+                kqueue,
+                dir: dir_file,
+                file: None,
+                stamp: stamp(&path),
+                path,
+            };
+            watcher.watch_file();
+            Ok(watcher)
+        }
+
+        pub fn stopper(&self) -> io::Result<Stopper> {
+            Ok(Stopper {
+                kqueue: self.kqueue.try_clone()?,
+            })
+        }
+
+        pub fn wait(&mut self, timeout: Option<Duration>) -> io::Result<Wake> {
+            let deadline = timeout.map(|timeout| Instant::now() + timeout);
+            let mut event: libc::kevent = unsafe { std::mem::zeroed() };
+            loop {
+                let left = deadline.map(|deadline| {
+                    let left = deadline.saturating_duration_since(Instant::now());
+                    libc::timespec {
+                        tv_sec: libc::time_t::try_from(left.as_secs()).unwrap_or(libc::time_t::MAX),
+                        tv_nsec: libc::c_long::from(left.subsec_nanos()),
+                    }
+                });
+                let n = unsafe {
+                    libc::kevent(
+                        self.kqueue.as_raw_fd(),
+                        std::ptr::null(),
+                        0,
+                        &raw mut event,
+                        1,
+                        left.as_ref().map_or(std::ptr::null(), std::ptr::from_ref),
+                    )
+                };
+                if n < 0 {
+                    let err = io::Error::last_os_error();
+                    if err.kind() == io::ErrorKind::Interrupted {
+                        continue;
+                    }
+                    return Err(err);
+                }
+                if n == 0 {
+                    return Ok(Wake::TimedOut);
+                }
+                if event.filter == libc::EVFILT_USER {
+                    return Ok(Wake::Stopped);
+                }
+                // 目录中其他文件的变化不影响配置文件的状态
+                let current = stamp(&self.path);
+                let from_dir = usize::try_from(self.dir.as_raw_fd()) == Ok(event.ident);
+                if from_dir && current == self.stamp {
+                    continue;
+                }
+                self.stamp = current;
+                // 文件可能已被替换，重新打开以监听新文件
+                self.watch_file();
+                return Ok(Wake::Changed);
+            }
+        }
+
+        /// 监听当前路径上的文件；文件暂时不存在时只依赖目录上的监听
+        fn watch_file(&mut self) {
+            // 关闭旧文件会自动移除其上的监听
+            self.file = open_event_only(&self.path).ok().filter(|file| {
+                let flags =
+                    libc::NOTE_WRITE | libc::NOTE_EXTEND | libc::NOTE_DELETE | libc::NOTE_RENAME;
+                register(&self.kqueue, file, flags).is_ok()
+            });
+        }
+    }
+
+    fn open_event_only(path: &Path) -> io::Result<File> {
+        OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_EVTONLY)
+            .open(path)
+    }
+
+    fn register(kqueue: &OwnedFd, file: &File, fflags: u32) -> io::Result<()> {
+        let ident = usize::try_from(file.as_raw_fd())
+            .map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+        let change = libc::kevent {
+            ident,
+            filter: libc::EVFILT_VNODE,
+            flags: libc::EV_ADD | libc::EV_CLEAR,
+            fflags,
+            data: 0,
+            udata: std::ptr::null_mut(),
+        };
+        apply(kqueue, &change)
+    }
+
+    /// 用于停止监听的用户事件
+    const fn stop_event(flags: u16, note: u32) -> libc::kevent {
+        libc::kevent {
+            ident: 0,
+            filter: libc::EVFILT_USER,
+            flags,
+            fflags: note,
+            data: 0,
+            udata: std::ptr::null_mut(),
+        }
+    }
+
+    fn apply(kqueue: &OwnedFd, change: &libc::kevent) -> io::Result<()> {
+        let n = unsafe {
+            libc::kevent(
+                kqueue.as_raw_fd(),
+                change,
+                1,
+                std::ptr::null_mut(),
+                0,
+                std::ptr::null(),
+            )
+        };
+        if n < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod platform {
+    use std::ffi::OsStr;
+    use std::io;
+    use std::path::Path;
+    use std::time::Duration;
+
+    use super::Wake;
+
+    pub struct Watcher;
+
+    #[derive(Debug)]
+    pub struct Stopper;
+
+    impl Stopper {
+        pub fn stop(&self) -> io::Result<()> {
+            Err(io::ErrorKind::Unsupported.into())
+        }
+    }
+
+    impl Watcher {
+        pub fn new(_dir: &Path, _name: &OsStr) -> io::Result<Self> {
+            Err(io::ErrorKind::Unsupported.into())
+        }
+
+        pub fn stopper(&self) -> io::Result<Stopper> {
+            Err(io::ErrorKind::Unsupported.into())
+        }
+
+        pub fn wait(&mut self, _timeout: Option<Duration>) -> io::Result<Wake> {
+            Err(io::ErrorKind::Unsupported.into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    fn temp_store(name: &str) -> FileStore {
+        let dir = std::env::temp_dir().join(format!("restgap-watch-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        FileStore::new(dir.join(FileStore::FILE_NAME))
+    }
+
+    #[test]
+    fn watcher_reports_saves_and_ignores_other_files() {
+        let store = temp_store("notify");
+        store.save(&Config::default()).unwrap();
+        let (tx, rx) = mpsc::channel();
+        let handle = spawn(store.path(), move || {
+            let _ = tx.send(());
+        })
+        .unwrap();
+
+        let sibling = store.path().with_file_name("notes.txt");
+        std::fs::write(sibling, "unrelated").unwrap();
+        assert!(rx.recv_timeout(DEBOUNCE * 3).is_err());
+
+        // 保存时先写临时文件再重命名替换
+        let config = Config {
+            interval_minutes: 45,
+            ..Config::default()
+        };
+        store.save(&config).unwrap();
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+
+        // 连续的多次保存合并为一次通知
+        for interval_minutes in [46, 47, 48] {
+            let config = Config {
+                interval_minutes,
+                ..Config::default()
+            };
+            store.save(&config).unwrap();
+        }
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+        assert!(rx.recv_timeout(DEBOUNCE * 3).is_err());
+
+        // 丢弃句柄会唤醒并等待监听线程退出，之后回调随线程一起释放
+        drop(handle);
+        assert_eq!(
+            rx.recv_timeout(DEBOUNCE),
+            Err(mpsc::RecvTimeoutError::Disconnected)
+        );
+        let _ = std::fs::remove_dir_all(store.path().parent().unwrap());
+    }
+
+    #[test]
+    fn reload_reports_changes_and_rejects_invalid_files() {
+        let store = temp_store("reload");
+        let current = Config::default();
+        store.save(&current).unwrap();
+        assert_eq!(reload(&store, &current).unwrap(), None);
+
+        let edited = Config {
+            interval_minutes: 45,
+            allow_skip_break: !current.allow_skip_break,
+            ..current.clone()
+        };
+        store.save(&edited).unwrap();
+        let reloaded = reload(&store, &current).unwrap().unwrap();
+        assert_eq!(reloaded.config, edited);
+        assert_eq!(
+            reloaded.changes,
+            [
+                format!(
+                    "allow_skip_break: {} → {}",
+                    current.allow_skip_break, !current.allow_skip_break
+                ),
+                format!("interval_minutes: {} → 45", current.interval_minutes),
+            ]
+        );
+
+        std::fs::write(
+            store.path(),
+            r#"{"interval_minutes": 500, "break_seconds": 20}"#,
+        )
+        .unwrap();
+        let errors = reload(&store, &edited).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(
+            errors[0].to_string().contains("interval_minutes = 500"),
+            "{}",
+            errors[0]
+        );
+
+        std::fs::write(store.path(), "{ not json").unwrap();
+        assert!(reload(&store, &edited).is_err());
+
+        // 文件被删除时保留当前配置，而不是恢复为默认值
+        std::fs::remove_file(store.path()).unwrap();
+        let errors = reload(&store, &edited).unwrap_err();
+        assert!(
+            errors[0].to_string().contains("配置文件不存在"),
+            "{}",
+            errors[0]
+        );
+        let _ = std::fs::remove_dir_all(store.path().parent().unwrap());
+    }
+}
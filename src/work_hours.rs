@@ -0,0 +1,288 @@
+//! 工作时段（按星期几配置的提醒时间窗口）。
+//!
+//! 使用紧凑的文本格式描述，例如 `Mon-Fri 09:00-12:00 13:00-18:30; Sat 10:00-12:00`。
+//! 所有计算都基于“本地时间自 Unix 纪元起的秒数”，时区偏移由调用方提供。
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const SECS_PER_DAY: i64 = 86_400;
+const MINUTES_PER_DAY: u16 = 24 * 60;
+const DAY_NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// 一天内的时间窗口，单位为自 00:00 起的分钟数，左闭右开
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TimeWindow {
+    pub start: u16,
+    pub end: u16,
+}
+
+/// 按星期几（周一为 0）配置的工作时段
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorkHours {
+    days: [Vec<TimeWindow>; 7],
+}
+
+impl WorkHours {
+    /// 默认工作时段：周一至周五 09:00-18:00
+    pub fn weekdays_nine_to_six() -> Self {
+        let mut hours = Self::default();
+        for day in &mut hours.days[..5] {
+            day.push(TimeWindow {
+                start: 9 * 60,
+                end: 18 * 60,
+            });
+        }
+        hours
+    }
+
+    /// 解析文本格式的工作时段
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut hours = Self::default();
+        for entry in spec.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+            let (days, windows) = entry
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| format!("missing time window in `{entry}`"))?;
+            let days = parse_days(days)?;
+
+            let mut parsed = Vec::new();
+            for window in windows
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|w| !w.is_empty())
+            {
+                parsed.push(parse_window(window)?);
+            }
+
+            for day in days {
+                hours.days[day].extend_from_slice(&parsed);
+            }
+        }
+
+        for day in &mut hours.days {
+            merge_windows(day);
+        }
+        Ok(hours)
+    }
+
+    /// 是否没有配置任何时间窗口
+    pub fn is_empty(&self) -> bool {
+        self.days.iter().all(Vec::is_empty)
+    }
+
+    /// 指定本地时刻是否处于工作时段内
+    pub fn is_active(&self, local_secs: i64) -> bool {
+        let minute = minute_of_day(local_secs);
+        self.days[weekday(local_secs)]
+            .iter()
+            .any(|w| w.start <= minute && minute < w.end)
+    }
+
+    /// 严格晚于指定本地时刻的下一个时段开始时刻；未配置任何时段时返回 `None`
+    pub fn next_start_after(&self, local_secs: i64) -> Option<i64> {
+        let today_start = local_secs - local_secs.rem_euclid(SECS_PER_DAY);
+        (0..=7).find_map(|offset| {
+            let day_start = today_start + offset * SECS_PER_DAY;
+            self.days[weekday(day_start)]
+                .iter()
+                .map(|w| day_start + i64::from(w.start) * 60)
+                .find(|&start| start > local_secs)
+        })
+    }
+}
+
+impl fmt::Display for WorkHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        let mut day = 0;
+        while day < 7 {
+            let windows = &self.days[day];
+            let mut last = day;
+            while last + 1 < 7 && self.days[last + 1] == *windows {
+                last += 1;
+            }
+
+            if !windows.is_empty() {
+                if !first {
+                    f.write_str("; ")?;
+                }
+                first = false;
+
+                f.write_str(DAY_NAMES[day])?;
+                if last > day {
+                    write!(f, "-{}", DAY_NAMES[last])?;
+                }
+                for w in windows {
+                    write!(f, " {}-{}", format_minutes(w.start), format_minutes(w.end))?;
+                }
+            }
+            day = last + 1;
+        }
+        Ok(())
+    }
+}
+
+/// 将墙上时刻换算为本地时间自 Unix 纪元起的秒数
+pub fn local_secs(t: SystemTime, utc_offset: i64) -> i64 {
+    let secs = t
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| i64::try_from(d.as_secs()).unwrap_or(i64::MAX));
+    secs.saturating_add(utc_offset)
+}
+
+/// 本地时刻对应的星期几（周一为 0）
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // 结果位于 0..7
+pub const fn weekday(local_secs: i64) -> usize {
+    // 1970-01-01 是星期四
+    (local_secs.div_euclid(SECS_PER_DAY) + 3).rem_euclid(7) as usize
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // 结果位于 0..1440
+const fn minute_of_day(local_secs: i64) -> u16 {
+    (local_secs.rem_euclid(SECS_PER_DAY) / 60) as u16
+}
+
+fn parse_days(token: &str) -> Result<Vec<usize>, String> {
+    let mut days = Vec::new();
+    for part in token.split(',').filter(|p| !p.is_empty()) {
+        if let Some((from, to)) = part.split_once('-') {
+            let (from, to) = (parse_day(from)?, parse_day(to)?);
+            if from > to {
+                return Err(format!("invalid day range `{part}`"));
+            }
+            days.extend(from..=to);
+        } else {
+            days.push(parse_day(part)?);
+        }
+    }
+    Ok(days)
+}
+
+fn parse_day(name: &str) -> Result<usize, String> {
+    DAY_NAMES
+        .iter()
+        .position(|d| d.eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("unknown day `{name}`"))
+}
+
+fn parse_window(token: &str) -> Result<TimeWindow, String> {
+    let (start, end) = token
+        .split_once('-')
+        .ok_or_else(|| format!("invalid time window `{token}`"))?;
+    let window = TimeWindow {
+        start: parse_time(start)?,
+        end: parse_time(end)?,
+    };
+    if window.start >= window.end {
+        return Err(format!("time window `{token}` ends before it starts"));
+    }
+    Ok(window)
+}
+
+fn parse_time(token: &str) -> Result<u16, String> {
+    let invalid = || format!("invalid time `{token}`");
+    let (h, m) = token.split_once(':').ok_or_else(invalid)?;
+    let h: u16 = h.parse().map_err(|_| invalid())?;
+    let m: u16 = m.parse().map_err(|_| invalid())?;
+    if h > 24 || m >= 60 || h * 60 + m > MINUTES_PER_DAY {
+        return Err(invalid());
+    }
+    Ok(h * 60 + m)
+}
+
+fn merge_windows(windows: &mut Vec<TimeWindow>) {
+    windows.sort_unstable();
+    let mut merged: Vec<TimeWindow> = Vec::with_capacity(windows.len());
+    for w in windows.drain(..) {
+        match merged.last_mut() {
+            Some(last) if w.start <= last.end => last.end = last.end.max(w.end),
+            _ => merged.push(w),
+        }
+    }
+    *windows = merged;
+}
+
+fn format_minutes(minutes: u16) -> String {
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024-01-01 00:00 是星期一
+    const MONDAY: i64 = 1_704_067_200;
+
+    const fn at(day: i64, h: i64, m: i64) -> i64 {
+        MONDAY + day * SECS_PER_DAY + h * 3600 + m * 60
+    }
+
+    #[test]
+    fn weekday_of_known_dates() {
+        assert_eq!(weekday(0), 3);
+        assert_eq!(weekday(MONDAY), 0);
+        assert_eq!(weekday(at(6, 23, 59)), 6);
+    }
+
+    #[test]
+    fn parses_and_formats_round_trip() {
+        let spec = "Mon-Fri 09:00-12:00 13:00-18:30; Sat 10:00-12:00";
+        let hours = WorkHours::parse(spec).unwrap();
+        assert_eq!(hours.to_string(), spec);
+        assert_eq!(WorkHours::parse(&hours.to_string()).unwrap(), hours);
+    }
+
+    #[test]
+    fn merges_overlapping_windows_and_day_lists() {
+        let hours = WorkHours::parse("sat,sun 10:00-12:00,11:00-13:00").unwrap();
+        assert_eq!(hours.to_string(), "Sat-Sun 10:00-13:00");
+    }
+
+    #[test]
+    fn rejects_malformed_specs() {
+        for spec in [
+            "Mon",
+            "Funday 09:00-10:00",
+            "Fri-Mon 09:00-10:00",
+            "Mon 10:00-09:00",
+            "Mon 9-10",
+            "Mon 09:60-10:00",
+            "Mon 23:00-24:30",
+            "Mon 09:00-9999:00",
+        ] {
+            assert!(WorkHours::parse(spec).is_err(), "{spec}");
+        }
+    }
+
+    #[test]
+    fn empty_spec_has_no_windows() {
+        let hours = WorkHours::parse("  ").unwrap();
+        assert!(hours.is_empty());
+        assert_eq!(hours.next_start_after(MONDAY), None);
+        assert_eq!(hours.to_string(), "");
+    }
+
+    #[test]
+    fn active_only_inside_windows() {
+        let hours = WorkHours::parse("Mon-Fri 09:00-12:00 13:00-18:30").unwrap();
+        assert!(!hours.is_active(at(0, 8, 59)));
+        assert!(hours.is_active(at(0, 9, 0)));
+        assert!(!hours.is_active(at(0, 12, 30)));
+        assert!(hours.is_active(at(4, 18, 29)));
+        assert!(!hours.is_active(at(4, 18, 30)));
+        assert!(!hours.is_active(at(5, 10, 0)));
+    }
+
+    #[test]
+    fn next_start_finds_later_today_or_next_week() {
+        let hours = WorkHours::parse("Mon-Fri 09:00-12:00 13:00-18:30").unwrap();
+        assert_eq!(hours.next_start_after(at(0, 12, 30)), Some(at(0, 13, 0)));
+        assert_eq!(hours.next_start_after(at(4, 19, 0)), Some(at(7, 9, 0)));
+        assert_eq!(hours.next_start_after(at(0, 9, 0)), Some(at(0, 13, 0)));
+    }
+
+    #[test]
+    fn next_start_wraps_a_full_week() {
+        let hours = WorkHours::parse("Wed 09:00-10:00").unwrap();
+        assert_eq!(hours.next_start_after(at(2, 9, 30)), Some(at(9, 9, 0)));
+    }
+}